@@ -5,8 +5,9 @@
 
 use std::path::Path;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use groan_rs::files::FileType;
+use groan_rs::structures::dimension::Dimension;
 
 use crate::errors::RunError;
 
@@ -25,11 +26,28 @@ pub struct Args {
         short = 's',
         long = "structure",
         help = "Input structure file",
-        long_help = "Path to a gro, pdb, pqr, or tpr file containing the system structure. If a trajectory is also provided, the coordinates from the structure file are ignored.",
-        value_parser = validate_structure_type,
+        long_help = "Path to a gro, pdb, pqr, tpr, PDBx/mmCIF (cif), or GROMOS87 (g96) file containing the system structure. If a trajectory is also provided, the coordinates from the structure file are ignored.
+Only the atoms of the first model of a cif file are read. Masses of common elements are assigned based on the element symbols provided in the cif file.
+Only the TITLE, POSITION, VELOCITY, and BOX blocks of a g96 file are read.
+Can be omitted if the trajectory is a single gro file; the first frame of the trajectory is then used as the structure.",
+        value_parser = validate_structure,
+        required_unless_present = "trajectories",
+        default_value = "",
+        hide_default_value = true,
     )]
     pub structure: String,
 
+    #[arg(
+        long = "coordinates",
+        help = "Input coordinates file",
+        long_help = "Path to a gro file containing the atom coordinates and the simulation box to use instead of those from the structure file.
+The topology (atom names, masses, bonds) is still read from the structure file, while the atom positions are taken from the provided gro file.
+Atoms are matched by their order, so both files must contain the same number of atoms.
+This is useful when combining a tpr file with a manually edited gro file.",
+        value_parser = validate_coordinates_type,
+    )]
+    pub coordinates: Option<String>,
+
     #[arg(
         short = 'f',
         long = "trajectory",
@@ -37,9 +55,11 @@ pub struct Args {
         long_help = "Path to xtc or trr file(s) or to a single gro file containing the trajectory (or trajectories) to be manipulated. 
 If not provided, the centering operation will use the structure file itself.
 Multiple files separated by whitespace can be provided. These will be concatenated into one output file.
-All trajectory files must be of the same type (i.e., all must be either xtc or trr files).
+All trajectory files must be of the same type (i.e., all must be either xtc or trr files), unless `allow-mixed` is provided.
 When joining trajectories, the last frame of each trajectory and the first frame of the following trajectory are checked for matching simulation steps. 
-If the simulation steps coincide, only the first of these frames is centered and written to output.",
+If the simulation steps coincide, only the first of these frames is centered and written to output.
+Xtc and trr files compressed using gzip (e.g. 'traj.xtc.gz') are also supported. Since the trajectory readers require seeking,
+such files are first decompressed into temporary files in the directory of the output file, which requires additional disk space.",
         num_args = 0..,
         value_parser = validate_trajectory_type,
     )]
@@ -57,7 +77,10 @@ If the simulation steps coincide, only the first of these frames is centered and
         short = 'o',
         long = "output",
         help = "Output file name",
-        long_help = "Name of the output file, which can be in gro, pdb, or pqr format if no trajectory is provided, 
+        required_unless_present = "no_output",
+        default_value = "",
+        hide_default_value = true,
+        long_help = "Name of the output file, which can be in gro, pdb, pqr, or g96 format if no trajectory is provided, 
 or in xtc, trr, or gro format if a trajectory is provided."
     )]
     pub output: String,
@@ -67,10 +90,87 @@ or in xtc, trr, or gro format if a trajectory is provided."
         long = "reference",
         help = "Group to center",
         default_value = "Protein",
-        long_help = "Specify the group to be centered. Define the group using the VMD-like 'groan selection language', which also supports ndx group names."
+        long_help = "Specify the group to be centered. Define the group using the VMD-like 'groan selection language', which also supports ndx group names.
+If the group 'Protein', 'Water', 'Membrane', or 'Ion' is not found in the ndx file, the corresponding atoms are autodetected.
+Use 'none' to skip the centering and only convert the input, make molecules whole, or apply other requested modifications.
+'none' cannot be combined with `xref`, `yref`, or `zref`."
     )]
     pub reference: String,
 
+    #[arg(
+        long = "reference-serials",
+        help = "Group to center specified using atom serial numbers",
+        conflicts_with = "reference",
+        value_parser = parse_serials,
+        value_name = "SERIALS",
+        long_help = "Specify the group to be centered as a list of atom serial numbers, e.g. '3,5,7-12'. Ranges are inclusive.
+Serial numbers correspond to the positions of the atoms in the structure file, starting from 1 (as in ndx files).
+The selection language is not used to create this group. All serial numbers must correspond to atoms of the system.
+Cannot be combined with `reference`."
+    )]
+    pub reference_serials: Option<Serials>,
+
+    #[arg(
+        long = "reference-words",
+        help = "Group to center specified as unquoted words",
+        conflicts_with_all = ["reference", "reference_serials", "reference_groups"],
+        num_args = 1..,
+        value_name = "WORD",
+        long_help = "Specify the group to be centered as a sequence of words which are joined by spaces into the reference query,
+e.g. '--reference-words resname ASN and serial 35 to 45' is the same as \"-r 'resname ASN and serial 35 to 45'\".
+This avoids quoting the query, e.g. in Makefiles. All words following this option up to the next option are part of the query.
+Cannot be combined with `reference`, `reference-serials`, or `reference-group`."
+    )]
+    pub reference_words: Option<Vec<String>>,
+
+    #[arg(
+        long = "reference-group",
+        help = "Group contributing to the weighted reference position",
+        conflicts_with_all = ["reference", "reference_serials", "invert_selection"],
+        value_parser = parse_weighted_group,
+        value_name = "QUERY[:WEIGHT]",
+        long_help = "Specify a group contributing to the position of the reference. Can be provided multiple times.
+The position of the reference is the weighted average of the positions of the individual groups, e.g. '--reference-group Protein:2 --reference-group resname LIG:1'.
+Weights must be positive. If no weight is provided, the group has a weight of 1.
+Note that this differs from centering the union of the groups: every group contributes to the reference position
+according to its weight, irrespective of the number of atoms it contains.
+The position of each group is calculated using the selected method (center of geometry, center of mass, median, midplane, average of residue centers of mass, or bounding box center).
+Cannot be combined with `reference` or `reference-serials`."
+    )]
+    pub reference_groups: Vec<WeightedGroup>,
+
+    #[arg(
+        long = "selection-file",
+        help = "File with named selections",
+        value_name = "FILE",
+        long_help = "Path to a file containing named selections that can be used in the reference queries as '@@name'.
+Each line of the file defines one selection in the format 'name = query', where query is written in the 'groan selection language'.
+Empty lines and lines starting with '#' are ignored. Names may only contain letters, digits, '_', '-', and '.'.
+For example, if the file contains 'membrane = resname POPC POPE', the option '-r @@membrane' is equivalent to '-r \"resname POPC POPE\"'."
+    )]
+    pub selection_file: Option<String>,
+
+    #[arg(
+        long = "invert-selection",
+        action,
+        help = "Center everything except the reference group",
+        default_value_t = false,
+        conflicts_with_all = ["xreference", "yreference", "zreference"],
+        long_help = "Invert the selection of the reference group, i.e. center all atoms that do NOT match the `reference` query.
+This is equivalent to using 'not (<REFERENCE>)' as the reference query. Cannot be combined with `xref`, `yref`, or `zref`."
+    )]
+    pub invert_selection: bool,
+
+    #[arg(
+        long = "strip",
+        help = "Atoms to remove from the output",
+        value_name = "QUERY",
+        long_help = "Remove the atoms matching the specified query from the output file, e.g. `--strip \"resname W ION\"` to remove water and ions.
+The query is written in the 'groan selection language'. Stripped atoms are still read from the input files, but they are not written into the output.
+Stripped atoms cannot be part of any reference group. Applies to both structure and trajectory output."
+    )]
+    pub strip: Option<String>,
+
     #[arg(
         short = 'b',
         long = "begin",
@@ -78,7 +178,8 @@ or in xtc, trr, or gro format if a trajectory is provided."
         requires = "trajectories",
         long_help = "Time of the first frame to read from the trajectory (in ps). All previous frames will be skipped.
 This option is only applicable when trajectory file(s) is/are provided.
-This option cannot be used when the trajectory is a gro file since gro files are not guaranteed to contain simulation time information.\n\n[default: 0.0]"
+This option cannot be used when the trajectory is a gro file since gro files are not guaranteed to contain simulation time information,
+unless the times of the frames are provided using `frame-times`.\n\n[default: 0.0]"
     )]
     pub start_time: Option<f32>,
 
@@ -89,10 +190,104 @@ This option cannot be used when the trajectory is a gro file since gro files are
         requires = "trajectories",
         long_help = "Time of the last frame to read from the trajectory (in ps). All following frames will be skipped.
 This option is only applicable when trajectory file(s) is/are provided.
-This option cannot be used when the trajectory is a gro file since gro files are not guaranteed to contain simulation time information.\n\n[default: NaN]"
+This option cannot be used when the trajectory is a gro file since gro files are not guaranteed to contain simulation time information,
+unless the times of the frames are provided using `frame-times`.\n\n[default: NaN]"
     )]
     pub end_time: Option<f32>,
 
+    #[arg(
+        long = "begin-step",
+        help = "Simulation step of the first frame to read",
+        value_name = "STEP",
+        requires = "trajectories",
+        conflicts_with_all = ["start_time", "end_time", "last"],
+        long_help = "Read only the frames of the trajectory with a simulation step equal to or higher than <STEP>.
+The step is the integer simulation step stored in each frame, which is useful if the simulation times of the frames are unreliable.
+Unlike `begin`, all frames are read and the frames with a lower step are skipped. Cannot be combined with `begin`, `end`, or `last`.
+This option cannot be used when the trajectory is a gro file since gro files do not contain simulation steps."
+    )]
+    pub begin_step: Option<u64>,
+
+    #[arg(
+        long = "end-step",
+        help = "Simulation step of the last frame to read",
+        value_name = "STEP",
+        requires = "trajectories",
+        conflicts_with_all = ["start_time", "end_time", "last"],
+        long_help = "Read only the frames of the trajectory with a simulation step equal to or lower than <STEP>.
+The step is the integer simulation step stored in each frame, which is useful if the simulation times of the frames are unreliable.
+Frames with a higher step are skipped. Cannot be combined with `begin`, `end`, or `last`.
+This option cannot be used when the trajectory is a gro file since gro files do not contain simulation steps."
+    )]
+    pub end_step: Option<u64>,
+
+    #[arg(
+        long = "last",
+        help = "Read only the last <LAST> ps of the trajectory",
+        requires = "trajectories",
+        conflicts_with = "start_time",
+        long_help = "Read only the frames from the final <LAST> ps of the trajectory, i.e. start reading at (time of the last frame - <LAST>).
+To determine the time of the last frame, the headers of all frames of the last trajectory file are scanned before centering.
+Frame data are skipped without being decompressed, so this pass is much faster than centering, but it still requires reading through the file once.
+This option cannot be combined with `begin` and cannot be used when the trajectory is a gro file."
+    )]
+    pub last: Option<f32>,
+
+    #[arg(
+        long = "xtc-index",
+        help = "Use an index of frame offsets to seek in the xtc trajectory",
+        requires = "trajectories",
+        value_name = "FILE",
+        long_help = "Path to an index file listing the simulation time and the byte offset of each frame of the input xtc trajectory.
+The index is used to jump directly to the first frame to read (specified using `begin` or `last`) instead of scanning the trajectory from its start.
+If the file does not exist or does not correspond to the trajectory, the trajectory is scanned once and a new index is written into the file,
+so that it can be reused in subsequent runs. If the index cannot be used, the trajectory is read from its start as usual.
+This option can only be used when a single xtc trajectory is provided."
+    )]
+    pub xtc_index: Option<String>,
+
+    #[arg(
+        long = "frame-times",
+        help = "Assign times to the frames of a gro trajectory",
+        requires = "trajectories",
+        value_name = "FILE",
+        long_help = "Read the simulation times (in ps) of the frames of a gro trajectory from the specified file.
+The file must list one time per line, in the order of the frames. Empty lines and lines starting with '#' are ignored.
+The times are assigned to the frames before any other processing, so they can be used with `begin` and `end`
+and are written into the output trajectory.
+The file must list at least as many times as there are frames in the trajectory.
+This option can only be used when the trajectory is a gro file."
+    )]
+    pub frame_times: Option<String>,
+
+    #[arg(
+        long = "allow-mixed",
+        action,
+        help = "Allow concatenating xtc and trr trajectories",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Allow concatenating trajectory files of different formats (xtc and trr).
+Trajectories with a different format than the first trajectory are transcoded into the format of the first trajectory
+and the transcoded files are then concatenated as usual. Transcoded files are temporarily stored in the directory of the output file.
+Transcoding trr into xtc drops velocities and forces; frames transcoded from xtc into trr contain zero velocities and forces.
+Concatenation of gro trajectories is still not supported."
+    )]
+    pub allow_mixed: bool,
+
+    #[arg(
+        long = "ignore-extra-atoms",
+        action,
+        help = "Allow trajectories with more atoms than the structure",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Allow the trajectory to contain more atoms than the structure file, e.g. virtual sites not present in the structure.
+The extra atoms are assumed to be appended after all atoms of the structure, so the first atoms of each frame correspond to the atoms of the structure.
+The extra atoms are moved together with the rest of the system but are not written into the output file.
+The structure file still cannot contain more atoms than the trajectory.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub ignore_extra_atoms: bool,
+
     #[arg(
         short = 't',
         long = "step",
@@ -104,6 +299,318 @@ This option is only applicable when trajectory file(s) is/are provided."
     )]
     pub step: usize,
 
+    #[arg(
+        long = "every",
+        visible_alias = "frame-stride-time",
+        help = "Write frames separated by at least <DT> ps",
+        value_name = "DT",
+        value_parser = parse_time_spacing,
+        requires = "trajectories",
+        conflicts_with = "step",
+        long_help = "Center and write a frame only if at least <DT> ps of simulation time have elapsed since the last written frame.
+Unlike `step`, this provides uniform sampling in time even if the spacing of the frames in the trajectory is uneven,
+e.g. after concatenating trajectories of restarted simulations. The first frame matching `begin` and `end` is always written.
+This option cannot be combined with `step` and cannot be used when the trajectory is a gro file,
+unless the times of the frames are provided using `frame-times`.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub every: Option<f32>,
+
+    #[arg(
+        long = "discard",
+        help = "Skip the first <N> frames",
+        value_name = "N",
+        requires = "trajectories",
+        long_help = "Skip the first <N> frames of the trajectory, e.g. to discard the equilibration part of a simulation.
+The frames are counted after the selection using `begin`, `end`, `begin-step`, `end-step`, and `step`,
+i.e. '-b 1000 -t 2 --discard 5' skips the first five frames of every second frame starting at 1000 ps.
+The remaining frames are then selected using `every`, `dedup`, `even-frames`, or `odd-frames`.
+Unlike `begin`, this option also works for gro trajectories and does not require knowing the times of the frames.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub discard: Option<usize>,
+
+    #[arg(
+        long = "even-frames",
+        action,
+        help = "Write only frames with an even index",
+        default_value_t = false,
+        requires = "trajectories",
+        conflicts_with = "odd_frames",
+        long_help = "Center and write only the frames with an even index (0, 2, 4, ...).
+The index is counted from 0 over the frames selected using `begin`, `end`, `step`, `discard`, `every`, and `dedup`, not over all frames of the trajectory file.
+Useful for block averaging or bootstrapping. This option cannot be combined with `odd-frames`.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub even_frames: bool,
+
+    #[arg(
+        long = "odd-frames",
+        action,
+        help = "Write only frames with an odd index",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Center and write only the frames with an odd index (1, 3, 5, ...).
+The index is counted from 0 over the frames selected using `begin`, `end`, `step`, `discard`, `every`, and `dedup`, not over all frames of the trajectory file.
+Useful for block averaging or bootstrapping. This option cannot be combined with `even-frames`.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub odd_frames: bool,
+
+    #[arg(
+        long = "dedup",
+        action,
+        help = "Skip duplicate consecutive frames",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Skip every frame with the same simulation step and time as the previously selected frame.
+Useful for trajectories concatenated from overlapping parts, where the overlapping frames may be repeated
+within a single file or across the boundaries of the files. The number of removed frames is reported.
+This option cannot be used when the trajectory is a gro file, unless the times of the frames are provided using `frame-times`.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub dedup: bool,
+
+    #[arg(
+        long = "first-frame-only",
+        action,
+        help = "Center and write only the first frame",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Center and write only the first frame of the trajectory that matches the `begin`, `end`, and `step` options. Useful for quick previews of large trajectories.
+Unlike `end`, this option also works for gro trajectories and does not require knowing the time of the first frame.
+If the output is a gro file, it will contain a single structure. If the output is an xtc or trr file, it will contain a single frame.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub first_frame_only: bool,
+
+    #[arg(
+        long = "max-frames",
+        help = "Center and write at most N frames",
+        requires = "trajectories",
+        long_help = "Center and write at most <MAX_FRAMES> frames of the trajectory to the output file.
+The `begin`, `end`, and `step` options are applied first, i.e. with `--step 5 --max-frames 10`, frames 0, 5, 10, ..., 45 are written.
+Unlike `end`, this option also works for gro trajectories and does not require knowing the simulation time.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub max_frames: Option<usize>,
+
+    #[arg(
+        long = "max-output-frames",
+        value_name = "N",
+        help = "Split the output into files of at most N frames",
+        requires = "trajectories",
+        conflicts_with_all = ["no_output", "compare"],
+        long_help = "Split the output trajectory into multiple files, each containing at most <N> frames.
+The files are numbered starting from 1 and the number is inserted before the extension of the output file,
+i.e. with '-o out.xtc', the frames are written into 'out.0001.xtc', 'out.0002.xtc', etc.
+No file is written under the name of the output file itself. Each file is a complete, independently readable trajectory.
+The files are written directly under their final names; existing files with the same names are backed up (or overwritten with '--overwrite').
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub max_output_frames: Option<usize>,
+
+    #[arg(
+        long = "reference-from-frame",
+        help = "Keep the reference at its position in frame N",
+        requires = "trajectories",
+        long_help = "Instead of placing the reference group into the center of the simulation box in every frame,
+translate each frame so that the reference group stays at the position it occupies in the Nth frame (counting from 0) of the centered trajectory.
+This removes the translation (drift) of the reference group without moving it to the box center.
+Frames are counted after applying the `begin`, `end`, and `step` options.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub reference_from_frame: Option<usize>,
+
+    #[arg(
+        long = "abs-target",
+        help = "Place the reference at the specified position",
+        value_parser = parse_target,
+        allow_hyphen_values = true,
+        conflicts_with = "reference_from_frame",
+        long_help = "Instead of placing the reference group into the center of the simulation box,
+translate the system so that the reference group is placed at the specified absolute position (in nm), e.g. '2.5,2.5,4.0'.
+Use '-' for any coordinate that should be set to the center of the simulation box, e.g. '-,-,3.0'.
+Only the coordinates of the dimensions selected for centering are applied."
+    )]
+    pub abs_target: Option<Target>,
+
+    #[arg(
+        long = "recenter-box",
+        action,
+        help = "Use the center of the simulation box as the reference",
+        default_value_t = false,
+        conflicts_with_all = ["reference", "reference_serials", "reference_words", "reference_groups", "invert_selection",
+            "xreference", "yreference", "zreference", "com", "median", "plane_fit", "by_residue_com", "bbox_center"],
+        long_help = "Use the center of the simulation box itself as the reference point instead of the position of a group of atoms.
+In combination with `abs-target`, the system is translated so that the center of the box is placed at the target position,
+which is useful for reframing the system, e.g. to align periodic images of two simulations independently of any molecule.
+Without `abs-target`, the box center is already placed at the center of the box, so the atoms are only wrapped into the box.
+Cannot be combined with options specifying the reference group or the centering method."
+    )]
+    pub recenter_box: bool,
+
+    #[arg(
+        long = "align-to",
+        help = "Place the reference at its position in another structure",
+        value_parser = validate_structure_type,
+        value_name = "STRUCTURE",
+        conflicts_with_all = ["abs_target", "reference_from_frame", "reference_serials", "reference_groups", "xreference", "yreference", "zreference"],
+        long_help = "Read the specified structure file, calculate the position of the reference group in it,
+and translate the system so that its reference group is placed at this position.
+This can be used to overlay two different simulations, e.g. of a mutant and of the wild-type protein.
+The reference query must select atoms in both structures. Groups from the ndx file are not available for the other structure.
+Only the coordinates of the dimensions selected for centering are applied."
+    )]
+    pub align_to: Option<String>,
+
+    #[arg(
+        long = "report-drift",
+        help = "Write positions of the reference into an xvg file",
+        requires = "trajectories",
+        long_help = "Write the position of the reference group before centering in each frame into the specified xvg file.
+The file contains four columns: simulation time (in ps) and the x, y, and z coordinates of the reference (in nm).
+If several references are used (see `xref`, `yref`, and `zref`), each coordinate is reported for the reference used in the corresponding dimension.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub report_drift: Option<String>,
+
+    #[arg(
+        long = "report-box",
+        help = "Write dimensions of the simulation box into an xvg file",
+        long_help = "Write the dimensions of the simulation box of each frame into the specified xvg file.
+The file contains five columns: simulation time (in ps), the x, y, and z dimensions of the box (in nm), and the volume of the box (in nm^3).
+The box is reported as read from the input, before it is modified using `keep-box` or `rescale-box`. Output coordinates are not affected.
+If no trajectory file is provided, the file contains a single line for the input structure."
+    )]
+    pub report_box: Option<String>,
+
+    #[arg(
+        long = "stats-jsonl",
+        help = "Write statistics of each frame into a JSON lines file",
+        value_name = "FILE",
+        requires = "trajectories",
+        long_help = "Write a JSON object for each centered frame into the specified file, one object per line.
+Each object contains the index of the frame, the simulation time (in ps) and step, the dimensions of the simulation box (in nm),
+and the position of the reference before centering (in nm), e.g. '{\"frame\": 0, \"time\": 0.000, \"step\": 0, \"box\": [...], \"centroid\": [...]}'.
+The box and the time are reported as read from the input. Statistics of the frames written before an error are preserved.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub stats_jsonl: Option<String>,
+
+    #[arg(
+        long = "index-out",
+        help = "Write the reference groups into an ndx file",
+        value_name = "NDX",
+        long_help = "Write the atoms of the reference group(s) used for centering into the specified ndx file.
+The groups are written after all selections have been resolved (including the autodetection of common groups)
+and are named 'CNTR-X', 'CNTR-Y', and 'CNTR-Z' depending on the references used.
+The main reference group is named according to `output-group-name`.
+This option can be used both when centering a structure and a trajectory."
+    )]
+    pub index_out: Option<String>,
+
+    #[arg(
+        long = "output-group-name",
+        help = "Name of the main reference group in the written ndx file",
+        value_name = "NAME",
+        value_parser = parse_group_name,
+        long_help = "Name under which the main reference group is written into the ndx file specified using `index-out`
+and under which it is reported in the output of the program.
+If not provided, the reference query is used as the name if it consists of a single word (e.g. 'Protein').
+Otherwise, the group is named 'Centered'. The name must not contain whitespace."
+    )]
+    pub output_group_name: Option<String>,
+
+    #[arg(
+        long = "reference-cache",
+        help = "Cache the selected reference groups in a file",
+        value_name = "FILE",
+        long_help = "Read the reference group(s) from the specified cache file instead of selecting the atoms again.
+If the file does not exist or can not be used, the reference group(s) are selected as usual and written into the file for the next run.
+The cache is used only if the system has the same atoms and the options selecting the reference atoms are the same as when it was written.
+Changes in the ndx file, the elements file, or the masses file also invalidate the cache.
+Useful when the same system is centered repeatedly, e.g. when the trajectory is processed in multiple chunks.
+The groups are stored in the ndx format, preceded by comment lines describing the system and the centering."
+    )]
+    pub reference_cache: Option<String>,
+
+    #[arg(
+        long = "print-centroid",
+        action,
+        help = "Print positions of the reference into stdout",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Print the position of the reference group before centering in each processed frame into standard output.
+Each line contains the simulation time (in ps) and the x, y, and z coordinates of the reference (in nm).
+All other messages are written into standard error, so the output can be directly used in a shell pipeline.
+Combine with `no-output` to only track the position of the reference without writing an output trajectory.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub print_centroid: bool,
+
+    #[arg(
+        long = "halt-on-jump",
+        help = "Stop if the reference jumps by more than <NM> between frames",
+        value_name = "NM",
+        value_parser = parse_jump_threshold,
+        requires = "trajectories",
+        long_help = "Compare the position of the reference group before centering with its position in the previously processed frame
+and stop with an error if the reference has moved by more than <NM> nm. A sudden jump of the reference usually indicates
+a corrupted frame or an artifact of the periodic boundary conditions. The frame index (counted from 0) and the size of the jump are reported.
+The displacement is only calculated along the centered dimensions and using the minimum image convention,
+so the reference crossing a periodic boundary (i.e. moving by approximately one box length) is not considered to be a jump.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub halt_on_jump: Option<f32>,
+
+    #[arg(
+        long = "warn-on-jump",
+        action,
+        help = "Only warn about jumps of the reference",
+        default_value_t = false,
+        requires = "halt_on_jump",
+        long_help = "Instead of stopping with an error, print a warning for each jump of the reference detected using `halt-on-jump` and continue centering."
+    )]
+    pub warn_on_jump: bool,
+
+    #[arg(
+        long = "clamp-outside",
+        action,
+        help = "Check that all atoms lie inside the simulation box",
+        default_value_t = false,
+        long_help = "After all operations have been applied to the system (or to each frame of the trajectory), check that all atoms
+lie inside the simulation box, i.e. between 0 and the box length in each dimension.
+If the last operation specified using `transform` is 'wrap', the presence of any atom outside the box is reported as an error.
+Otherwise, the number of atoms lying outside the box is reported as a warning.
+Atoms far outside the box usually indicate an invalid or non-standard simulation box of the input."
+    )]
+    pub clamp_outside: bool,
+
+    #[arg(
+        long = "fix-outside",
+        action,
+        help = "Wrap atoms lying outside the simulation box into it",
+        default_value_t = false,
+        requires = "clamp_outside",
+        long_help = "Wrap the atoms found outside the simulation box by `clamp-outside` into the box.
+Atoms lying inside the box are not moved. Note that this breaks molecules crossing the box boundary."
+    )]
+    pub fix_outside: bool,
+
+    #[arg(
+        long = "no-output",
+        action,
+        help = "Do not write the output trajectory",
+        default_value_t = false,
+        conflicts_with_all = ["output", "compare"],
+        requires = "trajectories",
+        long_help = "Process the trajectory without writing any output coordinate file.
+Useful in combination with `print-centroid` or `report-drift` when only the position of the reference is of interest.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub no_output: bool,
+
     #[arg(
         short = 'x',
         action,
@@ -131,88 +638,1256 @@ This option is only applicable when trajectory file(s) is/are provided."
     )]
     pub zdimension: bool,
 
+    #[arg(
+        long = "dim",
+        help = "Dimensions to center in",
+        conflicts_with_all = ["xdimension", "ydimension", "zdimension"],
+        value_parser = parse_dimension,
+        long_help = "Specify all the dimensions in which the centering should be performed using a single value, e.g., 'xy', 'x,z', or 'x+y+z'.
+This is an alternative to the `-x`, `-y`, and `-z` flags and cannot be combined with them. If no dimensions are selected, it defaults to 'xyz'."
+    )]
+    pub dimensions: Option<Dimension>,
+
     #[arg(
         long = "xref",
         help = "Group to center in the x dimension",
         long_help = "Center the specified selection of atoms along the x dimension. 
 This option, in conjunction with `yref` and `zref`, allows you to center multiple groups, each along a different dimension. 
 Define the group using the VMD-like 'groan selection language', which also supports ndx group names. 
-This selection acts as the reference selection for the x dimension, while the `reference` selection will still be centered in other specified dimensions."
+This selection acts as the reference selection for the x dimension, while the `reference` selection will still be centered in other specified dimensions.
+Append ':com' or ':geom' to the selection to center this group using its center of mass or center of geometry, respectively,
+regardless of the method used for the other groups, e.g. '--xref=Protein:com'."
     )]
     pub xreference: Option<String>,
 
+    /// Method requested for the x-dimension reference using a suffix of `xref`.
+    #[arg(skip)]
+    pub xweighting: Option<Weighting>,
+
     #[arg(
         long = "yref",
         help = "Group to center in the y dimension",
         long_help = "Center the specified selection of atoms along the y dimension. 
 This option, in conjunction with `xref` and `zref`, allows you to center multiple groups, each along a different dimension. 
 Define the group using the VMD-like 'groan selection language', which also supports ndx group names. 
-This selection acts as the reference selection for the y dimension, while the `reference` selection will still be centered in other specified dimensions."
+This selection acts as the reference selection for the y dimension, while the `reference` selection will still be centered in other specified dimensions.
+Append ':com' or ':geom' to the selection to center this group using its center of mass or center of geometry, respectively,
+regardless of the method used for the other groups, e.g. '--yref=Protein:com'."
     )]
     pub yreference: Option<String>,
 
+    /// Method requested for the y-dimension reference using a suffix of `yref`.
+    #[arg(skip)]
+    pub yweighting: Option<Weighting>,
+
     #[arg(
         long = "zref",
         help = "Group to center in the z dimension",
         long_help = "Center the specified selection of atoms along the z dimension. 
 This option, in conjunction with `xref` and `yref`, allows you to center multiple groups, each along a different dimension. 
 Define the group using the VMD-like 'groan selection language', which also supports ndx group names. 
-This selection acts as the reference selection for the z dimension, while the `reference` selection will still be centered in other specified dimensions."
+This selection acts as the reference selection for the z dimension, while the `reference` selection will still be centered in other specified dimensions.
+Append ':com' or ':geom' to the selection to center this group using its center of mass or center of geometry, respectively,
+regardless of the method used for the other groups, e.g. '--zref=Protein:com'."
     )]
     pub zreference: Option<String>,
 
+    /// Method requested for the z-dimension reference using a suffix of `zref`.
+    #[arg(skip)]
+    pub zweighting: Option<Weighting>,
+
+    #[arg(
+        long = "xref-invert",
+        action,
+        help = "Center the complement of the reference in the x dimension",
+        default_value_t = false,
+        conflicts_with_all = ["invert_selection", "recenter_box", "align_to"],
+        long_help = "Invert the reference group used for the x dimension, i.e. center all atoms that do NOT match it along the x dimension.
+If `xref` is provided, its selection is inverted. Otherwise, the main `reference` group is inverted for the x dimension only,
+while the other dimensions still use the non-inverted reference, e.g. '-r Protein --yref-invert --zref-invert' centers the protein along x
+and everything except the protein along y and z. Has no effect if the x dimension is not centered. Cannot be combined with `invert-selection`."
+    )]
+    pub xref_invert: bool,
+
+    #[arg(
+        long = "yref-invert",
+        action,
+        help = "Center the complement of the reference in the y dimension",
+        default_value_t = false,
+        conflicts_with_all = ["invert_selection", "recenter_box", "align_to"],
+        long_help = "Invert the reference group used for the y dimension, i.e. center all atoms that do NOT match it along the y dimension.
+If `yref` is provided, its selection is inverted. Otherwise, the main `reference` group is inverted for the y dimension only,
+while the other dimensions still use the non-inverted reference, e.g. '-r Protein --yref-invert --zref-invert' centers the protein along x
+and everything except the protein along y and z. Has no effect if the y dimension is not centered. Cannot be combined with `invert-selection`."
+    )]
+    pub yref_invert: bool,
+
+    #[arg(
+        long = "zref-invert",
+        action,
+        help = "Center the complement of the reference in the z dimension",
+        default_value_t = false,
+        conflicts_with_all = ["invert_selection", "recenter_box", "align_to"],
+        long_help = "Invert the reference group used for the z dimension, i.e. center all atoms that do NOT match it along the z dimension.
+If `zref` is provided, its selection is inverted. Otherwise, the main `reference` group is inverted for the z dimension only,
+while the other dimensions still use the non-inverted reference, e.g. '-r Protein --yref-invert --zref-invert' centers the protein along x
+and everything except the protein along y and z. Has no effect if the z dimension is not centered. Cannot be combined with `invert-selection`."
+    )]
+    pub zref_invert: bool,
+
+    #[arg(
+        long = "allow-empty-reference",
+        action,
+        help = "Do not fail if a reference group is empty",
+        default_value_t = false,
+        long_help = "Print a warning instead of failing if a reference group is empty.
+If a dimension-specific reference group (`xref`, `yref`, or `zref`) is empty, the main `reference` group is used in its dimension instead,
+provided that the main reference group is used in another dimension and is not empty. Otherwise, no centering is performed in this dimension.
+If the main `reference` group is empty, no centering is performed in the dimensions it is used for.
+Failure to autodetect the atoms of a common group (e.g. protein atoms) is still an error."
+    )]
+    pub allow_empty_reference: bool,
+
+    #[arg(
+        long = "box",
+        help = "Override the simulation box of the structure",
+        value_parser = parse_box,
+        value_name = "X,Y,Z",
+        long_help = "Replace the simulation box of the input structure with an orthogonal box with the specified dimensions (in nm), e.g. '10.0,10.0,12.5'.
+All dimensions must be positive. This allows centering structure files which lack a valid simulation box.
+Frames of the trajectory still use their own simulation boxes unless `keep-box` is also provided."
+    )]
+    pub simbox: Option<BoxSize>,
+
+    #[arg(
+        long = "keep-box",
+        action,
+        help = "Use the provided box in all trajectory frames",
+        default_value_t = false,
+        requires = "simbox",
+        requires = "trajectories",
+        long_help = "Replace the simulation box of every trajectory frame with the box specified using `box`.
+The box is replaced before the frame is centered and is also written into the output trajectory.
+This option requires `box` and is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub keep_box: bool,
+
+    #[arg(
+        long = "strict-box",
+        action,
+        help = "Treat an invalid box of the input structure as an error",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Fail if the simulation box of the input structure file is undefined, invalid, or non-orthogonal.
+By default, such issues only produce a warning when centering a trajectory since the frames of the trajectory have their own simulation boxes.
+Use this option to make sure that bad data are not processed, e.g. in automated pipelines.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub strict_box: bool,
+
+    #[arg(
+        long = "quiet-box-warnings",
+        action,
+        help = "Suppress warnings about the box of the input structure",
+        default_value_t = false,
+        requires = "trajectories",
+        conflicts_with = "strict_box",
+        long_help = "Do not print the warnings about an undefined, invalid, or non-orthogonal simulation box of the input structure file.
+These warnings are printed when centering a trajectory since the frames of the trajectory have their own simulation boxes.
+Other warnings and output are not affected. Cannot be combined with `strict-box`.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub quiet_box_warnings: bool,
+
+    #[arg(
+        long = "rescale-box",
+        help = "Rescale all trajectory frames to the specified box",
+        value_parser = parse_box,
+        value_name = "X,Y,Z",
+        requires = "trajectories",
+        conflicts_with = "keep_box",
+        long_help = "Rescale every trajectory frame to an orthogonal box with the specified dimensions (in nm), e.g. '10.0,10.0,12.5'.
+Coordinates of all atoms are scaled by the ratio of the target and the current box size in each dimension and the box of the frame is replaced with the target box.
+This removes the fluctuations of the box volume, e.g. from NPT simulations. Velocities are not modified.
+Rescaling is performed before the frame is centered and before molecules are made whole, so the reference position is calculated in the rescaled frame.
+A warning is printed if any scaling factor deviates from 1 by more than 10%.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub rescale_box: Option<BoxSize>,
+
+    #[arg(
+        long = "output-template-box",
+        help = "Write the output structure with a cubic box",
+        value_parser = parse_template_box,
+        value_name = "EDGE",
+        conflicts_with = "trajectories",
+        long_help = "Replace the simulation box of the output structure with a cubic box. The edge of the box is either specified (in nm), e.g. '12.0',
+or set to the longest edge of the current box if 'max' is provided. All atoms are shifted so that the center of the old box
+becomes the center of the new box, i.e. the centered reference stays in the center of the output box.
+This is distinct from `box` which overrides the simulation box of the input structure and affects centering.
+A warning is printed if the new box is smaller than the current box in any dimension as some atoms may end up outside of it.
+This option is only applicable when no trajectory file is provided."
+    )]
+    pub output_template_box: Option<TemplateBox>,
+
     #[arg(
         long = "com",
         action,
         help = "Use center of mass",
         default_value_t = false,
         long_help = "Use center of mass instead of center of geometry when centering the reference group. This requires information about atom masses. 
-If they are not explicitly provided using a tpr file, the masses are guessed."
+If they are not explicitly provided using a tpr file, the masses are guessed.
+Can also be enabled by setting the environment variable `GCENTER_COM=1`.
+This is ignored if `median`, `plane-fit`, `by-residue-com`, `bbox-center`, or `recenter-box` is requested."
     )]
     pub com: bool,
 
+    /// Center of mass has been enabled using the `GCENTER_COM` environment variable.
+    #[arg(skip)]
+    pub com_from_env: bool,
+
     #[arg(
-        long = "whole",
+        long = "median",
         action,
-        help = "Keep molecules whole",
+        help = "Use coordinate-wise median",
         default_value_t = false,
-        long_help = "Do not wrap all atoms into the simulation box but keep molecules whole. This requires providing a tpr file as an input structure file."
+        conflicts_with = "com",
+        long_help = "Use the coordinate-wise median of atom positions instead of center of geometry when centering the reference group.
+The median is less sensitive to outlier atoms (e.g., a stray ion captured by a loose selection) than the mean.
+If the reference group contains an even number of atoms, the average of the two middle coordinates is used.
+The result is deterministic and the output is identical over repeated runs with the same input. Cannot be combined with `com`."
     )]
-    pub whole: bool,
+    pub median: bool,
 
     #[arg(
-        long = "silent",
+        long = "plane-fit",
         action,
-        help = "Suppress standard output",
+        help = "Center the midplane of a membrane along z",
         default_value_t = false,
-        long_help = "Suppress all standard output generated by the 'gcenter' tool, except for error messages written to stderr."
+        conflicts_with_all = ["com", "median"],
+        long_help = "Along the z dimension, center the midplane between the two leaflets of a membrane instead of the center of geometry of the reference group.
+The atoms of the reference group are assigned to the upper or lower leaflet depending on whether they lie above or below the center of geometry of the reference along z.
+The midplane is then placed at the midpoint between the mean z-coordinates of the two leaflets. If one of the leaflets is empty, the center of geometry is used.
+This heuristic assumes that the reference is a membrane oriented perpendicular to the z-axis. The x and y dimensions are centered using the center of geometry.
+Cannot be combined with `com` or `median`."
     )]
-    pub silent: bool,
+    pub plane_fit: bool,
 
     #[arg(
-        long = "overwrite",
+        long = "by-residue-com",
+        visible_alias = "center-by-residue-com",
         action,
-        help = "Overwrite existing files with the same name",
+        help = "Use the average of residue centers of mass",
         default_value_t = false,
-        long_help = "Enable this option to overwrite existing files with the same name as the output file. No backup copies will be created."
+        conflicts_with_all = ["com", "median", "plane_fit"],
+        long_help = "Calculate the center of mass of each residue of the reference group and use the average of these centers as the position of the reference.
+Unlike `com`, every residue contributes equally, irrespective of its mass, so the two methods differ if the residues have different masses.
+This better represents the center of a membrane whose leaflets are composed of lipids of different sizes. This requires information about atom masses.
+If they are not explicitly provided using a tpr file, the masses are guessed. Cannot be combined with `com`, `median`, or `plane-fit`."
     )]
-    pub overwrite: bool,
-}
+    pub by_residue_com: bool,
 
-/// Validate that the structure is gro or pdb file.
-fn validate_structure_type(s: &str) -> Result<String, String> {
-    match FileType::from_name(s) {
-        FileType::GRO | FileType::PDB | FileType::TPR | FileType::PQR => Ok(s.to_owned()),
-        _ => Err(String::from("unsupported file extension")),
-    }
-}
+    #[arg(
+        long = "bbox-center",
+        action,
+        help = "Use the center of the bounding box",
+        default_value_t = false,
+        conflicts_with_all = ["com", "median", "plane_fit", "by_residue_com"],
+        long_help = "Use the center of the axis-aligned bounding box of the reference group instead of its center of geometry.
+For each dimension, the minimal and maximal coordinates of the atoms of the reference group are found and the reference is placed at their midpoint.
+The reference group is unwrapped relative to its center of geometry before the bounding box is calculated, so the box never spans the periodic boundary.
+Unlike the center of geometry (the mean of the positions) and the median, the bounding box center only depends on the outermost atoms,
+which is useful for placing an elongated or irregularly shaped molecule so that it is equally far from both sides of the simulation box.
+Ties between atoms with the same extreme coordinate do not matter and the output is identical over repeated runs with the same input.
+Cannot be combined with `com`, `median`, `plane-fit`, or `by-residue-com`."
+    )]
+    pub bbox_center: bool,
 
-/// Validate that the trajectories are xtc or trr files.
+    #[arg(
+        long = "elements",
+        help = "Custom elements definition file",
+        long_help = "Path to a YAML file defining the elements to use when guessing elements and assigning masses, instead of the built-in definitions.
+Useful for coarse-grained systems with bead names that do not correspond to standard elements.
+For the format of the file, see the `elements.yaml` file distributed with the `groan_rs` library.
+This option has no effect if a tpr file is provided as the input structure file."
+    )]
+    pub elements: Option<String>,
+
+    #[arg(
+        long = "masses",
+        value_name = "FILE",
+        help = "File with masses of atoms",
+        long_help = "Path to a file providing the masses of atoms, used instead of guessing the elements and assigning their masses.
+Each line of the file contains either an atom name and the mass of all atoms with this name (e.g. 'BB 72.0'),
+or only a mass, in which case the file must list the masses of all atoms of the system in order.
+Text following ';' or '#' is ignored. Masses from the file replace masses read from a tpr file.
+Useful for calculating the center of mass of coarse-grained systems without a tpr file."
+    )]
+    pub masses: Option<String>,
+
+    #[arg(
+        long = "weights-from-bfactor",
+        visible_alias = "center-weights-from-bfactor",
+        action,
+        help = "Use B-factors of atoms as centering weights",
+        default_value_t = false,
+        conflicts_with_all = ["masses", "median", "plane_fit", "by_residue_com", "bbox_center", "recenter_box"],
+        long_help = "Use the B-factor (temperature factor) of each atom of a pdb input structure file as its weight when centering the reference group.
+The B-factor is read from columns 61-66 of each ATOM and HETATM record until the first END or ENDMDL record, so the N-th record provides the weight of the N-th atom.
+The weights replace the masses of atoms and the weighted center is calculated in the same way as the center of mass, i.e. this option implies `com`.
+Keywords selecting atoms based on their mass also use the weights. B-factors must be non-negative and at least one of them must be positive.
+Only available for pdb input structure files. Cannot be combined with `masses`, `median`, `plane-fit`, `by-residue-com`, or `bbox-center`."
+    )]
+    pub weights_from_bfactor: bool,
+
+    #[arg(
+        long = "require-masses",
+        action,
+        help = "Fail instead of guessing masses of atoms",
+        default_value_t = false,
+        long_help = "Fail with an error if the masses of atoms would have to be guessed from their names,
+e.g. when calculating the center of mass of a system read from a gro or pdb file.
+Masses must then be read from a tpr file, assigned from the element symbols of a cif file, or provided using `masses`. By default, masses are guessed if they are not available."
+    )]
+    pub require_masses: bool,
+
+    #[arg(
+        long = "center-iterations",
+        help = "Number of centering passes per frame",
+        default_value_t = 1,
+        long_help = "Repeat the centering of the reference group(s) <CENTER_ITERATIONS> times for each frame.
+Additional iterations may help with pathological references consisting of several molecules that each span the simulation box.
+Use '--center-iterations 2' to center in two passes: after the first pass, the position of the reference is recalculated
+in the translated frame and the frame is centered again, which helps with large references such as membranes.
+Each iteration costs approximately as much time as the centering itself, so this option should only be used when needed."
+    )]
+    pub center_iterations: usize,
+
+    #[arg(
+        long = "whole",
+        action,
+        help = "Keep molecules whole",
+        default_value_t = false,
+        long_help = "Do not wrap all atoms into the simulation box but keep molecules whole. This requires providing a tpr file as an input structure file.
+Can also be enabled by setting the environment variable `GCENTER_WHOLE=1`. This is ignored if the input structure file is not a tpr file."
+    )]
+    pub whole: bool,
+
+    /// Keeping molecules whole has been enabled using the `GCENTER_WHOLE` environment variable.
+    #[arg(skip)]
+    pub whole_from_env: bool,
+
+    #[arg(
+        long = "transform",
+        help = "Order of operations applied to each frame",
+        value_parser = parse_transform,
+        value_name = "STEPS",
+        conflicts_with = "whole",
+        long_help = "Comma-separated list of operations applied to each frame in the specified order, e.g. 'whole,center,wrap'.
+Supported operations are 'center' (center the reference group),
+'whole' (make molecules whole; requires a tpr file), and 'wrap' (wrap all atoms into the simulation box).
+Each operation can only be used once and 'center' must always be included.
+By default, 'center' is performed, followed by 'whole' if `whole` is requested.
+Making molecules whole before centering is useful if the reference molecule is split across the periodic boundary."
+    )]
+    pub transform: Option<Transform>,
+
+    #[arg(
+        long = "wrap-dims",
+        help = "Dimensions in which atoms are wrapped into the box",
+        value_name = "DIMS",
+        default_value = "xyz",
+        value_parser = parse_wrap_dimension,
+        long_help = "Specify the dimensions in which atoms are wrapped into the simulation box after they are shifted, e.g. 'xyz', 'z', 'x,y', or 'none'.
+This is independent of the dimensions in which the reference is centered. By default, atoms are wrapped in all dimensions, even if the reference is only centered in some of them.
+In the other dimensions, atoms keep their positions relative to the image of the box they were shifted into, e.g. '--dim=z --wrap-dims=z' centers a slab along z
+without folding atoms that lie outside the box along x and y. This also applies to the 'wrap' operation of `transform`."
+    )]
+    pub wrap_dims: Dimension,
+
+    #[arg(
+        long = "whole-threshold",
+        visible_alias = "pbc-molecule-threshold",
+        help = "Do not follow bonds longer than <NM> when making molecules whole",
+        value_name = "NM",
+        value_parser = parse_jump_threshold,
+        long_help = "Make molecules whole by following the bonds between atoms, placing each atom at the periodic image closest to the atom it is bonded to.
+Bonds longer than <NM> nm (using the minimum image convention) are not followed, so a molecule containing such bonds
+(e.g. long artificial bonds) is made whole separately for each of its fragments. By default, all atoms of a molecule
+are placed at the periodic image closest to the first atom of the molecule.
+This option only applies when molecules are made whole using `whole` or the 'whole' operation of `transform`, which requires a tpr file."
+    )]
+    pub whole_threshold: Option<f32>,
+
+    #[arg(
+        long = "mirror",
+        help = "Mirror the system across a plane through the box center",
+        value_parser = parse_dimension,
+        value_name = "DIM",
+        long_help = "Reflect the positions of all atoms across the plane passing through the center of the simulation box after centering, e.g. 'z' mirrors the system across the xy-plane.
+Multiple dimensions can be specified, e.g. 'xy', in which case the system is mirrored in each of them.
+The corresponding components of velocities and forces are negated. Mirroring is applied to the output structure and to every trajectory frame.
+Note that the mirror plane always passes through the box center, even in dimensions in which no centering is performed."
+    )]
+    pub mirror: Option<Dimension>,
+
+    #[arg(
+        long = "no-velocities",
+        action,
+        help = "Do not write velocities",
+        default_value_t = false,
+        long_help = "Do not write atom velocities into the output file even if they are present in the input.
+For gro output, the velocity columns are omitted. For trr output, the velocities are written as zeros.
+Use `positions-only` to omit velocities and forces from trr output completely."
+    )]
+    pub no_velocities: bool,
+
+    #[arg(
+        long = "positions-only",
+        action,
+        help = "Only write positions of atoms",
+        default_value_t = false,
+        long_help = "Write only positions of atoms (and the simulation box) into the output file, dropping velocities and forces.
+Unlike with `no-velocities`, trr output frames contain no velocity and force blocks at all, which substantially reduces the size of the file.
+For other output formats, this is equivalent to `no-velocities`."
+    )]
+    pub positions_only: bool,
+
+    #[arg(
+        long = "output-precision",
+        help = "Precision of the output xtc coordinates",
+        requires = "trajectories",
+        value_name = "PRECISION",
+        long_help = "Precision used to compress coordinates written into an output xtc file.
+The coordinates are stored as integer multiples of 1/PRECISION nm, i.e. the default precision of 1000 corresponds to a resolution of 0.001 nm.
+Higher values produce larger files with finer resolution, lower values produce smaller files.
+By default, the precision of the input trajectory is used. This option is ignored for trr and gro output."
+    )]
+    pub output_precision: Option<u64>,
+
+    #[arg(
+        long = "time-offset",
+        visible_alias = "output-time-offset",
+        help = "Shift the times of the output frames (in ps)",
+        requires = "trajectories",
+        value_name = "PS",
+        allow_negative_numbers = true,
+        long_help = "Add the specified offset (in ps) to the simulation time of each frame written into the output trajectory.
+With `time-reset`, the times of the output frames start at this offset instead of zero.
+Useful when splitting and recombining trajectories. Frames are still selected using their original times.
+Only applies to xtc and trr output. This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub time_offset: Option<f32>,
+
+    #[arg(
+        long = "time-reset",
+        action,
+        help = "Renumber the times of the output frames from zero",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Set the simulation time of each frame written into the output trajectory to its index multiplied by the time step.
+The time step is the difference between the original times of the first two written frames.
+The times start at zero or at the value of `time-offset`, if provided. Frames are still selected using their original times.
+Only applies to xtc and trr output. This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub time_reset: bool,
+
+    #[arg(
+        long = "renumber",
+        action,
+        help = "Renumber atoms and residues in the output",
+        default_value_t = false,
+        long_help = "Assign contiguous atom and residue numbers starting from 1 to the atoms written into the output file.
+A new residue is started whenever the residue number changes between two consecutive atoms.
+Numbers larger than 99999 wrap around to 0 in gro files, as in Gromacs.
+Only applies to gro, pdb, pqr, and g96 output. This option is ignored for xtc and trr output which do not contain atom numbering."
+    )]
+    pub renumber: bool,
+
+    #[arg(
+        long = "pdb-ter",
+        action,
+        help = "Write TER records between chains of the output pdb",
+        default_value_t = false,
+        conflicts_with = "trajectories",
+        long_help = "Insert a TER record after the last atom of every chain in the output pdb file.
+Chain boundaries are identified using the chain identifiers of the input structure; atoms without a chain identifier do not start a new chain.
+The CRYST1 record is always written into the output pdb file since the simulation box must be valid for centering.
+Only applies to pdb output. This option is only applicable when no trajectory file is provided."
+    )]
+    pub pdb_ter: bool,
+
+    #[arg(
+        long = "title",
+        value_name = "TEXT",
+        help = "Title of the output structure file",
+        conflicts_with = "trajectories",
+        value_parser = validate_title,
+        long_help = "Set the title of the output structure file.
+The title is written as the first line of a gro file or as the TITLE record of a pdb or pqr file.
+If this option is not provided but '--verbose' is used, the title is generated automatically
+from the version of gcenter and the performed operations.
+This option is only applicable when no trajectory file is provided."
+    )]
+    pub title: Option<String>,
+
+    #[arg(
+        long = "silent",
+        action,
+        help = "Suppress standard output",
+        default_value_t = false,
+        long_help = "Suppress all standard output generated by the 'gcenter' tool, except for error messages written to stderr."
+    )]
+    pub silent: bool,
+
+    #[arg(
+        long = "quiet",
+        action,
+        help = "Suppress notes",
+        default_value_t = false,
+        long_help = "Suppress the notes about guessing elements and masses and about backups.
+Warnings, the progress of the centering, and the final result are still written. `silent` takes precedence over this option."
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        long = "verbose",
+        action,
+        help = "Print information about each frame",
+        default_value_t = false,
+        conflicts_with_all = ["silent", "quiet"],
+        long_help = "Print the simulation step and time of each centered trajectory frame instead of the progress of the centering."
+    )]
+    pub verbose: bool,
+
+    #[arg(
+        long = "error-format",
+        value_name = "FORMAT",
+        help = "Format of error messages [text, json]",
+        default_value = "text",
+        value_parser = parse_error_format,
+        long_help = "Format of the error message written to stderr if 'gcenter' fails. Use 'text' for a colored human-readable message
+or 'json' for a single-line JSON object with the kind of the error and the message without colors,
+e.g. '{\"kind\": \"OutputUnsupported\", \"message\": \"invalid value 'out.txt' for '--output <OUTPUT>': unsupported file extension\"}'.
+Errors in the provided arguments are also reported in the requested format. The exit code of 'gcenter' is not affected."
+    )]
+    pub error_format: ErrorFormat,
+
+    #[arg(
+        long = "progress-json",
+        action,
+        help = "Report progress as JSON lines",
+        default_value_t = false,
+        requires = "trajectories",
+        long_help = "Write the progress of the centering into the standard error output as one JSON object per centered frame,
+e.g. '{\"frame\": 10, \"time\": 1000.000, \"percent\": 50.0}', instead of printing the progress bar.
+The frame is counted from 0. The percentage is estimated from the time range of the centered frames
+(given by `begin` and `end` or by the time of the last frame of the trajectory) and is omitted if the time range is not known,
+e.g. for gro trajectories. The progress is reported even if `silent` is provided.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub progress_json: bool,
+
+    #[arg(
+        long = "progress-interval",
+        value_name = "N",
+        help = "Update the progress every N frames",
+        requires = "trajectories",
+        long_help = "Update the progress of the centering only every <N> frames. Applies both to the progress bar and to the JSON lines of `progress-json`.
+Formatting the progress for every frame can slow down the centering of very long trajectories with small systems; a larger <N> reduces this overhead.
+By default, the progress bar is updated every 100 read frames and a JSON line is written for every centered frame.
+This option is only applicable when trajectory file(s) is/are provided."
+    )]
+    pub progress_interval: Option<usize>,
+
+    #[arg(
+        long = "benchmark",
+        action,
+        help = "Report time spent in the individual stages",
+        default_value_t = false,
+        long_help = "Measure the time spent loading the structure, guessing elements, selecting the reference atoms, and centering,
+and print a breakdown at the end of the run. For trajectories, the time spent reading, centering, and writing the frames is reported separately.
+The breakdown is not printed if `silent` is provided."
+    )]
+    pub benchmark: bool,
+
+    #[arg(
+        long = "overwrite",
+        action,
+        help = "Overwrite existing files with the same name",
+        default_value_t = false,
+        long_help = "Enable this option to overwrite existing files with the same name as the output file. No backup copies will be created."
+    )]
+    pub overwrite: bool,
+
+    #[arg(
+        long = "clean-backups",
+        action,
+        help = "Remove backups of the output file",
+        default_value_t = false,
+        requires = "overwrite",
+        conflicts_with = "no_output",
+        long_help = "When overwriting the output file, also remove the backup copies of the output file created by previous runs,
+i.e. files named '#<OUTPUT>-<TIMESTAMP>#' located in the directory of the output file. Other files are never removed.
+This prevents the accumulation of backups in directories that are processed repeatedly. Requires `overwrite`."
+    )]
+    pub clean_backups: bool,
+
+    #[arg(
+        long = "keep-existing",
+        action,
+        help = "Do nothing if the output file already exists",
+        default_value_t = false,
+        conflicts_with_all = ["overwrite", "no_output"],
+        long_help = "If the output file already exists, exit successfully without performing the centering or modifying the existing output.
+Useful for resumable batch jobs where re-running 'gcenter' should only process the missing outputs.
+If the output is split into multiple files using '--max-output-frames', the first of these files is checked."
+    )]
+    pub keep_existing: bool,
+
+    #[arg(
+        long = "backup-dir",
+        help = "Directory for backups of existing output files",
+        conflicts_with = "overwrite",
+        long_help = "Move existing files with the same name as the output file into the specified directory instead of backing them up in place.
+The directory is created if it does not exist. Backup copies are named using the name of the original file and a timestamp."
+    )]
+    pub backup_dir: Option<String>,
+
+    #[arg(
+        long = "no-atomic",
+        action,
+        help = "Write the output file directly",
+        default_value_t = false,
+        long_help = "By default, the output is first written into a temporary file named '<OUTPUT>.tmp' which is renamed to <OUTPUT>
+only after it has been completely written, so an incomplete output file is never left under the final name if 'gcenter' fails.
+If the centering of a trajectory is interrupted, the frames written so far are still moved to <OUTPUT>.
+Use this option to write directly into the output file instead, e.g. if the directory of the output file does not allow creating other files."
+    )]
+    pub no_atomic: bool,
+
+    #[arg(
+        long = "gzip-output",
+        action,
+        help = "Compress the output file using gzip",
+        default_value_t = false,
+        conflicts_with_all = ["no_output", "max_output_frames", "compare"],
+        long_help = "Compress the output file using gzip and write it into '<OUTPUT>.gz'.
+The format of the output is determined from the extension preceding '.gz', so both '-o out.gro' and '-o out.gro.gz' produce 'out.gro.gz'.
+Text output formats (gro, pdb, pqr, g96) can be compressed. Binary xtc and trr trajectories are already compressed
+and this option is ignored with a warning when writing them.
+The uncompressed output is always written into a temporary file first, even if '--no-atomic' is requested.
+Existing files named '<OUTPUT>.gz' are backed up (or overwritten with '--overwrite')."
+    )]
+    pub gzip_output: bool,
+
+    #[arg(
+        long = "stream",
+        action,
+        help = "Stream the output frames directly into the output file",
+        default_value_t = false,
+        conflicts_with_all = ["no_output", "max_output_frames", "gzip_output", "compare", "keep_existing"],
+        long_help = "Write the output directly into the output file instead of using a temporary file.
+Each frame is flushed into the output as soon as it has been centered, so it is immediately available to the reader of the output.
+When centering a trajectory, only trr output can be streamed; other trajectory writers buffer the frames and streaming them is rejected.
+Existing named pipes are not backed up, other existing output files are backed up as usual.
+Useful for streaming the centered trajectory into another program through a named pipe (FIFO), e.g. 'mkfifo live.trr'.
+If the output file is a named pipe, this option is enabled automatically.
+The format of the output is still determined from the extension of the output file.
+Use together with '--silent' to keep the output of 'gcenter' from interleaving with the output of the receiving program."
+    )]
+    pub stream: bool,
+
+    /// Path to the temporary file into which the output is written before being renamed to `output`.
+    #[arg(skip)]
+    pub output_tmp: Option<String>,
+
+    /// Path to the gzip-compressed output file, if the output is compressed.
+    #[arg(skip)]
+    pub output_gz: Option<String>,
+
+    /// Paths to the files from which the trajectories are read, if some trajectories are decompressed or transcoded.
+    #[arg(skip)]
+    pub trajectories_tmp: Option<Vec<String>>,
+
+    /// Number of atoms present in the trajectory but not in the structure file.
+    #[arg(skip)]
+    pub extra_atoms: usize,
+
+    #[arg(
+        long = "compare",
+        help = "Compare the output file with an expected file",
+        value_name = "EXPECTED",
+        long_help = "After writing the output file, compare it byte-by-byte with the provided file.
+If the files differ, 'gcenter' exits with an error and reports the offset of the first differing byte.
+Useful for checking the reproducibility of the centering, e.g. across different versions of 'gcenter'."
+    )]
+    pub compare: Option<String>,
+
+    #[arg(
+        long = "config",
+        help = "Configuration file with default values of options",
+        long_help = "Path to a TOML file providing default values of options, e.g. to share standard settings across job scripts.
+If not provided, the file 'gcenter.toml' in the current working directory is read, if it exists.
+Only the following options can be set in the file, using their long names as keys: 'reference', 'index', 'xref', 'yref', 'zref', 'dim',
+'com', 'median', 'plane-fit', 'by-residue-com', 'bbox-center', 'elements', 'center-iterations', 'whole', 'quiet', 'overwrite', and 'backup-dir',
+e.g. 'reference = \"Membrane\"' or 'com = true'. Any other key is reported as an error. Paths are interpreted relative to the current working directory.
+Options provided on the command line take precedence over the configuration file, which takes precedence over the built-in defaults.
+Related options are taken from the configuration file only if none of them is provided on the command line,
+e.g. `com` from the file is ignored if `median` is provided on the command line."
+    )]
+    pub config: Option<String>,
+
+    #[arg(
+        long = "capabilities",
+        action,
+        exclusive = true,
+        help = "Print supported formats and features as JSON",
+        default_value_t = false,
+        long_help = "Print a JSON object containing the version of 'gcenter', the supported input and output file formats, and the available optional features, then exit.
+This is intended for tools that need to check the capabilities of the installed 'gcenter' before invoking it. Cannot be combined with any other option."
+    )]
+    pub capabilities: bool,
+
+    #[arg(
+        long = "list-formats",
+        action,
+        exclusive = true,
+        help = "Print supported file formats",
+        default_value_t = false,
+        long_help = "Print the file formats supported for the input structure, coordinates, trajectory, index, and elements files,
+and for the output files when centering a structure or a trajectory, then exit.
+Files are recognized by their extensions, ignoring case. Cannot be combined with any other option."
+    )]
+    pub list_formats: bool,
+}
+
+/// Supported types of input structure files (PDBx/mmCIF and g96 files are handled separately).
+pub(crate) const STRUCTURE_TYPES: [FileType; 4] =
+    [FileType::GRO, FileType::PDB, FileType::PQR, FileType::TPR];
+
+/// Supported types of input coordinates files.
+pub(crate) const COORDINATES_TYPES: [FileType; 1] = [FileType::GRO];
+
+/// Supported types of input trajectory files.
+pub(crate) const TRAJECTORY_TYPES: [FileType; 3] = [FileType::XTC, FileType::TRR, FileType::GRO];
+
+/// Supported types of output files when centering a structure (g96 files are handled separately).
+pub(crate) const STRUCTURE_OUTPUT_TYPES: [FileType; 3] =
+    [FileType::GRO, FileType::PDB, FileType::PQR];
+
+/// Supported types of output files when centering a trajectory.
+pub(crate) const TRAJECTORY_OUTPUT_TYPES: [FileType; 3] =
+    [FileType::XTC, FileType::TRR, FileType::GRO];
+
+/// Amount of information written by `gcenter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors are written.
+    Silent,
+    /// Notes are suppressed.
+    Quiet,
+    /// Everything except for information about individual frames is written.
+    Normal,
+    /// Everything is written, including information about individual frames.
+    Verbose,
+}
+
+/// Absolute position at which the reference group should be placed.
+/// Coordinates set to `None` correspond to the center of the simulation box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target(pub [Option<f32>; 3]);
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let coordinates: Vec<String> = self
+            .0
+            .iter()
+            .map(|c| match c {
+                Some(value) => value.to_string(),
+                None => String::from("-"),
+            })
+            .collect();
+
+        write!(f, "{}", coordinates.join(","))
+    }
+}
+
+/// Group contributing to the position of the reference with the specified weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedGroup {
+    pub query: String,
+    pub weight: f32,
+}
+
+impl std::fmt::Display for WeightedGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.query, self.weight)
+    }
+}
+
+/// Dimensions of an orthogonal simulation box provided by the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxSize(pub [f32; 3]);
+
+impl std::fmt::Display for BoxSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.0[0], self.0[1], self.0[2])
+    }
+}
+
+/// Format of the error messages written by `gcenter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// Colored human-readable text.
+    Text,
+    /// JSON object with the kind of the error and the message.
+    Json,
+}
+
+/// Method used to calculate the position of a dimension-specific reference group, overriding the global method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    /// Center of mass.
+    Mass,
+    /// Center of geometry.
+    Geometry,
+}
+
+impl std::fmt::Display for Weighting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Weighting::Mass => write!(f, "center of mass"),
+            Weighting::Geometry => write!(f, "center of geometry"),
+        }
+    }
+}
+
+/// Edge of the cubic box written into the output structure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemplateBox {
+    /// Use the longest edge of the current simulation box.
+    Max,
+    /// Use the specified edge (in nm).
+    Edge(f32),
+}
+
+impl std::fmt::Display for TemplateBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateBox::Max => write!(f, "cubic, longest edge"),
+            TemplateBox::Edge(edge) => write!(f, "cubic, {} nm", edge),
+        }
+    }
+}
+
+/// Operation applied to each frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformStep {
+    /// Center the reference group(s).
+    Center,
+    /// Make molecules whole.
+    Whole,
+    /// Wrap atoms into the simulation box.
+    Wrap,
+}
+
+impl std::fmt::Display for TransformStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformStep::Center => write!(f, "center"),
+            TransformStep::Whole => write!(f, "whole"),
+            TransformStep::Wrap => write!(f, "wrap"),
+        }
+    }
+}
+
+/// Sequence of operations applied to each frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transform(pub Vec<TransformStep>);
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let steps: Vec<String> = self.0.iter().map(|step| step.to_string()).collect();
+        write!(f, "{}", steps.join(","))
+    }
+}
+
+/// Atom serial numbers selected for the reference group.
+/// Stored as inclusive ranges of serial numbers (starting from 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Serials(pub Vec<(usize, usize)>);
+
+impl Serials {
+    /// Get the indices of the selected atoms (starting from 0).
+    pub fn indices(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .flat_map(|&(start, end)| (start - 1)..end)
+            .collect()
+    }
+
+    /// Get the highest selected serial number.
+    pub fn max(&self) -> usize {
+        self.0.iter().map(|&(_, end)| end).max().unwrap_or(0)
+    }
+}
+
+impl std::fmt::Display for Serials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranges: Vec<String> = self
+            .0
+            .iter()
+            .map(|&(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, end)
+                }
+            })
+            .collect();
+
+        write!(f, "{}", ranges.join(","))
+    }
+}
+
+impl Args {
+    /// Get the verbosity level requested by the user.
+    pub fn verbosity(&self) -> Verbosity {
+        if self.silent {
+            Verbosity::Silent
+        } else if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+
+    /// Get the parity of the indices of the frames to write (0 for even, 1 for odd frames).
+    /// Returns `None` if all frames should be written.
+    pub fn frame_parity(&self) -> Option<usize> {
+        match (self.even_frames, self.odd_frames) {
+            (true, _) => Some(0),
+            (_, true) => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Get the path to the file into which the output is written.
+    /// This is a temporary file unless `no-atomic` is requested.
+    pub fn output_path(&self) -> &str {
+        self.output_tmp.as_deref().unwrap_or(&self.output)
+    }
+
+    /// Get the paths to the files from which the trajectories are read.
+    /// These differ from `trajectories` if some trajectories are decompressed or transcoded into temporary files.
+    /// The original names should be used when reporting to the user.
+    pub fn trajectory_paths(&self) -> &[String] {
+        self.trajectories_tmp
+            .as_deref()
+            .unwrap_or(&self.trajectories)
+    }
+
+    /// Get the final name of the output file.
+    /// This is the name of the compressed file if `gzip-output` is requested.
+    pub fn final_output(&self) -> &str {
+        self.output_gz.as_deref().unwrap_or(&self.output)
+    }
+
+    /// Get the sequence of operations to apply to each frame.
+    pub fn transform_steps(&self) -> Vec<TransformStep> {
+        match &self.transform {
+            Some(transform) => transform.0.clone(),
+            None if self.whole => vec![TransformStep::Center, TransformStep::Whole],
+            None => vec![TransformStep::Center],
+        }
+    }
+}
+
+/// Validate that the title of the output file fits on a single line.
+fn validate_title(s: &str) -> Result<String, String> {
+    if s.contains(['\n', '\r']) {
+        Err(String::from("title must not contain line breaks"))
+    } else {
+        Ok(s.to_owned())
+    }
+}
+
+/// Validate that the structure is gro, pdb, pqr, tpr, mmCIF, or g96 file.
+fn validate_structure_type(s: &str) -> Result<String, String> {
+    if crate::cif::is_cif(s) || crate::g96::is_g96(s) {
+        return Ok(s.to_owned());
+    }
+
+    if STRUCTURE_TYPES.contains(&crate::file_type(s)) {
+        Ok(s.to_owned())
+    } else {
+        Err(String::from("unsupported file extension"))
+    }
+}
+
+/// Validate the input structure file.
+/// An empty value is used if the structure file is not provided; it is resolved by `resolve_structure`.
+fn validate_structure(s: &str) -> Result<String, String> {
+    if s.is_empty() {
+        Ok(String::new())
+    } else {
+        validate_structure_type(s)
+    }
+}
+
+/// Parse dimensions specified as a string, e.g. 'xy', 'x,z', or 'x+y+z'.
+pub(crate) fn parse_dimension(s: &str) -> Result<Dimension, String> {
+    let mut dims = [false; 3];
+
+    for c in s
+        .chars()
+        .filter(|c| !matches!(c, ',' | '+') && !c.is_whitespace())
+    {
+        let index = match c.to_ascii_lowercase() {
+            'x' => 0,
+            'y' => 1,
+            'z' => 2,
+            _ => return Err(format!("unknown dimension '{}'", c)),
+        };
+
+        if dims[index] {
+            return Err(format!("dimension '{}' specified multiple times", c));
+        }
+
+        dims[index] = true;
+    }
+
+    match dims.into() {
+        Dimension::None => Err(String::from("no dimension specified")),
+        dim => Ok(dim),
+    }
+}
+
+/// Parse target position specified as a string, e.g. '2.5,2.5,4.0' or '-,-,3.0'.
+fn parse_target(s: &str) -> Result<Target, String> {
+    let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
+    if parts.len() != 3 {
+        return Err(format!("expected 3 coordinates, got {}", parts.len()));
+    }
+
+    let mut target = [None; 3];
+    for (coordinate, part) in target.iter_mut().zip(parts) {
+        if part == "-" {
+            continue;
+        }
+
+        match part.parse::<f32>() {
+            Ok(value) if value.is_finite() => *coordinate = Some(value),
+            _ => return Err(format!("invalid coordinate '{}'", part)),
+        }
+    }
+
+    Ok(Target(target))
+}
+
+/// Parse a sequence of operations specified as a string, e.g. 'whole,center,wrap'.
+fn parse_transform(s: &str) -> Result<Transform, String> {
+    let mut steps = Vec::new();
+    for part in s.split(',').map(|p| p.trim()) {
+        let step = match part {
+            "center" => TransformStep::Center,
+            "whole" => TransformStep::Whole,
+            "wrap" => TransformStep::Wrap,
+            _ => {
+                return Err(format!(
+                    "unknown operation '{}' (expected 'center', 'whole', or 'wrap')",
+                    part
+                ))
+            }
+        };
+
+        if steps.contains(&step) {
+            return Err(format!("operation '{}' is specified multiple times", part));
+        }
+
+        steps.push(step);
+    }
+
+    if !steps.contains(&TransformStep::Center) {
+        return Err(String::from("operation 'center' must be included"));
+    }
+
+    Ok(Transform(steps))
+}
+
+/// Parse a group with an optional weight specified as a string, e.g. 'resname LIG:2.5'.
+fn parse_weighted_group(s: &str) -> Result<WeightedGroup, String> {
+    let (query, weight) = match s.rsplit_once(':') {
+        None => (s, 1.0),
+        Some((query, weight)) => match weight.trim().parse::<f32>() {
+            Ok(value) if value.is_finite() && value > 0.0 => (query, value),
+            Ok(_) => {
+                return Err(format!(
+                    "weight '{}' is not a positive number",
+                    weight.trim()
+                ))
+            }
+            Err(_) => return Err(format!("invalid weight '{}'", weight.trim())),
+        },
+    };
+
+    if query.trim().is_empty() {
+        return Err(String::from("empty query"));
+    }
+
+    Ok(WeightedGroup {
+        query: query.trim().to_owned(),
+        weight,
+    })
+}
+
+/// Parse dimensions of an orthogonal simulation box specified as a string, e.g. '10.0,10.0,12.5'.
+fn parse_box(s: &str) -> Result<BoxSize, String> {
+    let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
+    if parts.len() != 3 {
+        return Err(format!("expected 3 box dimensions, got {}", parts.len()));
+    }
+
+    let mut dimensions = [0.0; 3];
+    for (dimension, part) in dimensions.iter_mut().zip(parts) {
+        match part.parse::<f32>() {
+            Ok(value) if value.is_finite() && value > 0.0 => *dimension = value,
+            Ok(_) => return Err(format!("box dimension '{}' is not a positive number", part)),
+            Err(_) => return Err(format!("invalid box dimension '{}'", part)),
+        }
+    }
+
+    Ok(BoxSize(dimensions))
+}
+
+/// Parse the edge of the cubic output box specified either as a positive number or as 'max'.
+fn parse_template_box(s: &str) -> Result<TemplateBox, String> {
+    let s = s.trim();
+    if s == "max" {
+        return Ok(TemplateBox::Max);
+    }
+
+    match s.parse::<f32>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(TemplateBox::Edge(value)),
+        Ok(_) => Err(format!("box edge '{}' is not a positive number", s)),
+        Err(_) => Err(format!(
+            "invalid box edge '{}' (expected a number or 'max')",
+            s
+        )),
+    }
+}
+
+/// Parse the dimensions in which atoms are wrapped into the simulation box. 'none' disables wrapping.
+fn parse_wrap_dimension(s: &str) -> Result<Dimension, String> {
+    match s.trim() {
+        "none" => Ok(Dimension::None),
+        _ => parse_dimension(s),
+    }
+}
+
+/// Parse the format of error messages.
+fn parse_error_format(s: &str) -> Result<ErrorFormat, String> {
+    match s.trim() {
+        "text" => Ok(ErrorFormat::Text),
+        "json" => Ok(ErrorFormat::Json),
+        _ => Err(format!(
+            "invalid error format '{}' (expected 'text' or 'json')",
+            s.trim()
+        )),
+    }
+}
+
+/// Parse the minimal spacing of written frames (in ps).
+fn parse_time_spacing(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+        Ok(_) => Err(format!("time spacing '{}' is not a positive number", s)),
+        Err(_) => Err(format!("invalid time spacing '{}' (expected a number)", s)),
+    }
+}
+
+/// Parse the maximal allowed displacement of the reference between frames (in nm).
+fn parse_jump_threshold(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+        Ok(_) => Err(format!("distance '{}' is not a positive number", s)),
+        Err(_) => Err(format!("invalid distance '{}' (expected a number)", s)),
+    }
+}
+
+/// Parse the name of a group written into an ndx file.
+fn parse_group_name(s: &str) -> Result<String, String> {
+    if s.is_empty() {
+        Err(String::from("group name must not be empty"))
+    } else if s.chars().any(char::is_whitespace) {
+        Err(format!("group name '{}' must not contain whitespace", s))
+    } else {
+        Ok(s.to_owned())
+    }
+}
+
+/// Parse a list of atom serial numbers, e.g. '3,5,7-12'.
+fn parse_serials(s: &str) -> Result<Serials, String> {
+    let parse_serial = |x: &str| match x.trim().parse::<usize>() {
+        Ok(0) => Err(String::from("serial numbers start from 1")),
+        Ok(value) => Ok(value),
+        Err(_) => Err(format!("invalid serial number '{}'", x.trim())),
+    };
+
+    let mut ranges = Vec::new();
+    for part in s.split(',') {
+        let range = match part.split_once('-') {
+            None => {
+                let serial = parse_serial(part)?;
+                (serial, serial)
+            }
+            Some((start, end)) => {
+                let (start, end) = (parse_serial(start)?, parse_serial(end)?);
+                if start > end {
+                    return Err(format!("invalid range '{}'", part.trim()));
+                }
+                (start, end)
+            }
+        };
+
+        ranges.push(range);
+    }
+
+    Ok(Serials(ranges))
+}
+
+/// Validate that the coordinates file is a gro file.
+fn validate_coordinates_type(s: &str) -> Result<String, String> {
+    if COORDINATES_TYPES.contains(&crate::file_type(s)) {
+        Ok(s.to_owned())
+    } else {
+        Err(String::from("unsupported file extension"))
+    }
+}
+
+/// Validate that the trajectories are xtc or trr files.
 /// Validate that no trajectory is provided multiple times.
 fn validate_trajectory_type(s: &str) -> Result<String, String> {
-    match FileType::from_name(s) {
-        FileType::XTC | FileType::TRR | FileType::GRO => Ok(s.to_owned()),
-        _ => Err(String::from("unsupported file extension")),
+    if crate::gzip::is_gzip(s) {
+        return match crate::gzip::trajectory_type(s) {
+            FileType::XTC | FileType::TRR => Ok(s.to_owned()),
+            _ => Err(String::from(
+                "only xtc and trr trajectories can be compressed using gzip",
+            )),
+        };
+    }
+
+    if TRAJECTORY_TYPES.contains(&crate::file_type(s)) {
+        Ok(s.to_owned())
+    } else if crate::g96::is_g96(s) {
+        Err(String::from(
+            "g96 files are only supported as structure files",
+        ))
+    } else {
+        Err(String::from("unsupported file extension"))
     }
 }
 
@@ -245,6 +1920,24 @@ fn validate_queries(args: &Args, input_type: FileType) -> Result<(), RunError> {
         }
     }
 
+    if let Some(strip) = &args.strip {
+        if query_contains_molecule_with(strip) {
+            return Err(RunError::UnsupportedQuery(
+                strip.to_owned(),
+                "--strip <QUERY>".to_owned(),
+            ));
+        }
+    }
+
+    for group in args.reference_groups.iter() {
+        if query_contains_molecule_with(&group.query) {
+            return Err(RunError::UnsupportedQuery(
+                group.query.to_owned(),
+                "--reference-group <QUERY[:WEIGHT]>".to_owned(),
+            ));
+        }
+    }
+
     if query_contains_molecule_with(&args.reference) {
         return Err(RunError::UnsupportedQuery(
             args.reference.to_owned(),
@@ -265,7 +1958,46 @@ fn sanity_check_inputs(args: &Args) -> Result<(), RunError> {
         return Err(RunError::InputStructureNotFound(args.structure.to_string()));
     }
 
-    let input_type = FileType::from_name(&args.structure);
+    // check that the input coordinates exist and do not match the output
+    if let Some(coordinates) = &args.coordinates {
+        if !Path::new(coordinates).exists() {
+            return Err(RunError::InputCoordinatesNotFound(coordinates.to_owned()));
+        }
+
+        if coordinates == &args.output {
+            return Err(RunError::IOMatch(coordinates.to_owned()));
+        }
+    }
+
+    // check that the expected file exists and does not match the output
+    if let Some(expected) = &args.compare {
+        if !Path::new(expected).exists() {
+            return Err(RunError::CompareNotFound(expected.to_owned()));
+        }
+
+        if expected == &args.output {
+            return Err(RunError::CompareMatchesOutput(expected.to_owned()));
+        }
+    }
+
+    let input_type = crate::file_type(&args.structure);
+
+    // check that the B-factors can be read from the input structure
+    if args.weights_from_bfactor && input_type != FileType::PDB {
+        return Err(RunError::BfactorsRequirePdb(args.structure.to_owned()));
+    }
+
+    // check that centering is not skipped if dimension-specific references are provided
+    if args.reference == crate::NO_REFERENCE {
+        for (reference, name) in [&args.xreference, &args.yreference, &args.zreference]
+            .into_iter()
+            .zip(["--xref", "--yref", "--zref"])
+        {
+            if reference.is_some() {
+                return Err(RunError::NoReferenceWithDimensionReference(name.to_owned()));
+            }
+        }
+    }
 
     // validate that the GSL queries do not contain any unsupported keywords
     validate_queries(args, input_type)?;
@@ -275,6 +2007,14 @@ fn sanity_check_inputs(args: &Args) -> Result<(), RunError> {
         return Err(RunError::WholeRequiresTprFile);
     }
 
+    if args.transform_steps().contains(&TransformStep::Whole) && input_type != FileType::TPR {
+        return Err(RunError::TransformWholeRequiresTprFile);
+    }
+
+    if args.whole_threshold.is_some() && !args.transform_steps().contains(&TransformStep::Whole) {
+        return Err(RunError::WholeThresholdWithoutWhole);
+    }
+
     // check for input-output matches
     if args.trajectories.is_empty() {
         if args.structure == args.output {
@@ -293,7 +2033,7 @@ fn sanity_check_inputs(args: &Args) -> Result<(), RunError> {
             }
 
             // check that if there is multiple trajectories, none are GRO files
-            if t > 0 && FileType::from_name(traj) == FileType::GRO {
+            if t > 0 && crate::gzip::trajectory_type(traj) == FileType::GRO {
                 return Err(RunError::OnlyOneGroTrajectory(traj.to_owned()));
             }
 
@@ -307,7 +2047,16 @@ fn sanity_check_inputs(args: &Args) -> Result<(), RunError> {
                 }
 
                 // check that all the trajectories have the same type
-                if FileType::from_name(traj) != FileType::from_name(traj2) {
+                // xtc and trr trajectories can be mixed if requested
+                let mixable = |file| {
+                    matches!(
+                        crate::gzip::trajectory_type(file),
+                        FileType::XTC | FileType::TRR
+                    )
+                };
+                if crate::gzip::trajectory_type(traj) != crate::gzip::trajectory_type(traj2)
+                    && !(args.allow_mixed && mixable(traj) && mixable(traj2))
+                {
                     return Err(RunError::InconsistentTrajectoryFiles(
                         traj.to_owned(),
                         traj2.to_owned(),
@@ -317,11 +2066,66 @@ fn sanity_check_inputs(args: &Args) -> Result<(), RunError> {
         }
     }
 
-    // check that if `start_time` or `end_time` is provided, trajectory is not a gro file
+    // check that the drift file does not match any input or output file
+    if let Some(drift) = &args.report_drift {
+        if drift == &args.output
+            || drift == &args.structure
+            || args.trajectories.iter().any(|traj| traj == drift)
+        {
+            return Err(RunError::DriftFileMatch(drift.to_owned()));
+        }
+    }
+
+    // check that the box report file does not match any input or output file
+    if let Some(report) = &args.report_box {
+        if report == &args.output
+            || report == &args.structure
+            || args.trajectories.iter().any(|traj| traj == report)
+        {
+            return Err(RunError::BoxReportFileMatch(report.to_owned()));
+        }
+    }
+
+    // check that the statistics file does not match any input or output file
+    if let Some(stats) = &args.stats_jsonl {
+        if stats == &args.output
+            || stats == &args.structure
+            || args.trajectories.iter().any(|traj| traj == stats)
+        {
+            return Err(RunError::StatsFileMatch(stats.to_owned()));
+        }
+    }
+
+    // check that at least one centering iteration is performed
+    if args.center_iterations == 0 {
+        return Err(RunError::ZeroCenterIterations);
+    }
+
+    // check that the maximal number of frames is not zero
+    if args.max_frames == Some(0) {
+        return Err(RunError::ZeroMaxFrames);
+    }
+
+    // check that the progress is updated
+    if args.progress_interval == Some(0) {
+        return Err(RunError::ZeroProgressInterval);
+    }
+
+    // check that each output file contains at least one frame
+    if args.max_output_frames == Some(0) {
+        return Err(RunError::ZeroMaxOutputFrames);
+    }
+
+    // check that the output precision is not zero
+    if args.output_precision == Some(0) {
+        return Err(RunError::ZeroOutputPrecision);
+    }
+
+    // check that if `start_time`, `end_time`, or `every` is provided, trajectory is not a gro file without frame times
     if let Some(file) = args.trajectories.first() {
-        let file_type = FileType::from_name(file);
+        let file_type = crate::gzip::trajectory_type(file);
 
-        if file_type == FileType::GRO {
+        if file_type == FileType::GRO && args.frame_times.is_none() {
             if args.start_time.is_some() {
                 return Err(RunError::BeginNotSupportedForGro(
                     args.start_time.unwrap().to_string(),
@@ -333,22 +2137,526 @@ fn sanity_check_inputs(args: &Args) -> Result<(), RunError> {
                     args.end_time.unwrap().to_string(),
                 ));
             }
+
+            if let Some(every) = args.every {
+                return Err(RunError::EveryNotSupportedForGro(every.to_string()));
+            }
+
+            if args.dedup {
+                return Err(RunError::DedupNotSupportedForGro);
+            }
+        }
+
+        if file_type == FileType::GRO {
+            if let Some(last) = args.last {
+                return Err(RunError::LastNotSupportedForGro(last.to_string()));
+            }
+
+            if let Some(step) = args.begin_step {
+                return Err(RunError::StepRangeNotSupportedForGro(
+                    step.to_string(),
+                    String::from("--begin-step <STEP>"),
+                ));
+            }
+
+            if let Some(step) = args.end_step {
+                return Err(RunError::StepRangeNotSupportedForGro(
+                    step.to_string(),
+                    String::from("--end-step <STEP>"),
+                ));
+            }
+        }
+
+        // check that frame times are only assigned to a gro trajectory
+        if let Some(times) = &args.frame_times {
+            if file_type != FileType::GRO {
+                return Err(RunError::FrameTimesRequireGro(file.to_owned()));
+            }
+
+            if !Path::new(times).exists() {
+                return Err(RunError::FrameTimesNotFound(times.to_owned()));
+            }
         }
     }
 
+    // no output file is written
+    if args.no_output {
+        return Ok(());
+    }
+
     // check the extension of the output file
-    let output_type = FileType::from_name(&args.output);
-    match (args.trajectories.is_empty(), output_type) {
-        (true, FileType::GRO | FileType::PDB | FileType::PQR) => Ok(()),
-        (true, _) => Err(RunError::OutputUnsupported(args.output.clone())),
-        (false, FileType::XTC | FileType::TRR | FileType::GRO) => Ok(()),
-        (false, _) => Err(RunError::OutputUnsupported(args.output.clone())),
+    let output_type = crate::file_type(&args.output);
+    let supported = if args.trajectories.is_empty() {
+        STRUCTURE_OUTPUT_TYPES.as_slice()
+    } else {
+        TRAJECTORY_OUTPUT_TYPES.as_slice()
+    };
+
+    if supported.contains(&output_type)
+        || (args.trajectories.is_empty() && crate::g96::is_g96(&args.output))
+    {
+        Ok(())
+    } else {
+        Err(RunError::OutputUnsupported(args.output.clone()))
+    }
+}
+
+/// Returns true if the value of an environment variable enables an option.
+fn env_enables(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// Returns true if the environment variable is set and enables an option.
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|x| env_enables(&x))
+}
+
+/// Split the method suffix (':com' or ':geom') from the query.
+fn split_weighting(query: &str) -> (&str, Option<Weighting>) {
+    let trimmed = query.trim_end();
+    if let Some(stripped) = trimmed.strip_suffix(":com") {
+        (stripped, Some(Weighting::Mass))
+    } else if let Some(stripped) = trimmed.strip_suffix(":geom") {
+        (stripped, Some(Weighting::Geometry))
+    } else {
+        (query, None)
+    }
+}
+
+/// Move the method suffixes of the dimension-specific references into separate fields.
+fn apply_weighting_suffixes(args: &mut Args) {
+    for (reference, weighting) in [
+        (&mut args.xreference, &mut args.xweighting),
+        (&mut args.yreference, &mut args.yweighting),
+        (&mut args.zreference, &mut args.zweighting),
+    ] {
+        if let Some(query) = reference {
+            let (stripped, method) = split_weighting(query);
+            *weighting = method;
+            *query = stripped.to_owned();
+        }
+    }
+}
+
+/// Use the first frame of the trajectory as the structure if no structure file is provided.
+/// This is only possible for gro trajectories since xtc and trr files contain no topology.
+fn resolve_structure(args: &mut Args) -> Result<(), RunError> {
+    if !args.structure.is_empty() {
+        return Ok(());
+    }
+
+    match args.trajectories.first() {
+        Some(traj) if args.trajectories.len() == 1 && crate::file_type(traj) == FileType::GRO => {
+            if !Path::new(traj).exists() {
+                return Err(RunError::InputTrajectoryNotFound(traj.to_owned()));
+            }
+
+            args.structure = traj.to_owned();
+            Ok(())
+        }
+        _ => Err(RunError::StructureRequired),
+    }
+}
+
+/// Join the words of the reference query provided using `--reference-words` into the reference query.
+fn join_reference_words(args: &mut Args) {
+    if let Some(words) = &args.reference_words {
+        args.reference = words.join(" ");
+    }
+}
+
+/// Remove the '.gz' extension from the name of the output file if `--gzip-output` is requested.
+/// The format of the output is then determined from the preceding extension.
+fn strip_gzip_output(args: &mut Args) {
+    if args.gzip_output && crate::gzip::is_gzip(&args.output) {
+        args.output = Path::new(&args.output)
+            .with_extension("")
+            .to_string_lossy()
+            .into_owned();
+    }
+}
+
+/// Calculate the center of mass if the B-factors of atoms are used as their weights.
+fn bfactor_weighting(args: &mut Args) {
+    if args.weights_from_bfactor {
+        args.com = true;
+    }
+}
+
+/// Use all atoms of the system as the reference group if the center of the box is the reference point.
+/// The atoms of the reference group are not used to calculate the reference point in that case.
+fn box_reference(args: &mut Args) {
+    if args.recenter_box {
+        args.reference = String::from("all");
+    }
+}
+
+/// Set defaults of flags that can be enabled using environment variables.
+/// Flags provided on the command line always take precedence.
+fn apply_env_defaults(args: &mut Args) {
+    if !args.com
+        && !args.median
+        && !args.plane_fit
+        && !args.bbox_center
+        && !args.by_residue_com
+        && !args.recenter_box
+        && env_flag("GCENTER_COM")
+    {
+        args.com = true;
+        args.com_from_env = true;
+    }
+
+    if !args.whole
+        && args.transform.is_none()
+        && crate::file_type(&args.structure) == FileType::TPR
+        && env_flag("GCENTER_WHOLE")
+    {
+        args.whole = true;
+        args.whole_from_env = true;
+    }
+}
+
+/// Get the format of error messages from the command line arguments without parsing the other arguments,
+/// so that errors in the arguments themselves can be reported in this format.
+pub fn error_format() -> ErrorFormat {
+    let args: Vec<String> = std::env::args().collect();
+    let mut format = ErrorFormat::Text;
+
+    for (i, arg) in args.iter().enumerate() {
+        let value = match arg.strip_prefix("--error-format") {
+            Some("") => args.get(i + 1).map(String::as_str),
+            Some(value) => value.strip_prefix('='),
+            None => None,
+        };
+
+        if let Some(Ok(requested)) = value.map(parse_error_format) {
+            format = requested;
+        }
+    }
+
+    format
+}
+
+/// Report an error in the arguments and exit. Help and version are not errors and are always printed as text.
+fn exit_with_clap_error(error: clap::Error) -> ! {
+    if error.use_stderr() && error_format() == ErrorFormat::Json {
+        let kind = format!("{:?}", error.kind());
+        eprintln!("{}", crate::errors::error_to_json(&kind, &error));
+        std::process::exit(error.exit_code());
     }
+
+    error.exit()
 }
 
 pub fn parse() -> Result<Args, Box<dyn std::error::Error + Send + Sync>> {
-    let args = Args::parse();
+    let matches = Args::command()
+        .try_get_matches()
+        .unwrap_or_else(|e| exit_with_clap_error(e));
+
+    // print the capabilities and exit before the other arguments are validated
+    if matches.get_flag("capabilities") {
+        println!("{}", crate::capabilities::capabilities());
+        std::process::exit(0);
+    }
+
+    // print the supported formats and exit before the other arguments are validated
+    if matches.get_flag("list_formats") {
+        println!("{}", crate::capabilities::formats());
+        std::process::exit(0);
+    }
+
+    let mut args = Args::from_arg_matches(&matches)
+        .unwrap_or_else(|e| exit_with_clap_error(e.format(&mut Args::command())));
+    resolve_structure(&mut args)?;
+    join_reference_words(&mut args);
+    crate::config::apply_config(&mut args, &matches)?;
+    box_reference(&mut args);
+    bfactor_weighting(&mut args);
+    strip_gzip_output(&mut args);
+    apply_env_defaults(&mut args);
+    apply_weighting_suffixes(&mut args);
+    crate::reference::expand_selections(&mut args)?;
     sanity_check_inputs(&args)?;
 
     Ok(args)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verbosity() {
+        let base = ["gcenter", "-s=tests/test_files/input.gro", "-o=output.gro"];
+
+        let args = Args::parse_from(base);
+        assert_eq!(args.verbosity(), Verbosity::Normal);
+
+        let args = Args::parse_from(base.into_iter().chain(["--quiet"]));
+        assert_eq!(args.verbosity(), Verbosity::Quiet);
+
+        let args = Args::parse_from(base.into_iter().chain(["--silent"]));
+        assert_eq!(args.verbosity(), Verbosity::Silent);
+
+        let args = Args::parse_from(base.into_iter().chain(["--silent", "--quiet"]));
+        assert_eq!(args.verbosity(), Verbosity::Silent);
+
+        let args = Args::parse_from(base.into_iter().chain(["--verbose"]));
+        assert_eq!(args.verbosity(), Verbosity::Verbose);
+    }
+
+    #[test]
+    fn env_enables_values() {
+        for value in ["1", "true", "TRUE", "yes", "on", " 1 "] {
+            assert!(env_enables(value));
+        }
+
+        for value in ["0", "false", "no", "off", "", "2"] {
+            assert!(!env_enables(value));
+        }
+    }
+
+    #[test]
+    fn parse_dimension_plain() {
+        assert_eq!(parse_dimension("x"), Ok(Dimension::X));
+        assert_eq!(parse_dimension("yz"), Ok(Dimension::YZ));
+        assert_eq!(parse_dimension("zyx"), Ok(Dimension::XYZ));
+        assert_eq!(parse_dimension("XZ"), Ok(Dimension::XZ));
+    }
+
+    #[test]
+    fn parse_dimension_separated() {
+        assert_eq!(parse_dimension("x,z"), Ok(Dimension::XZ));
+        assert_eq!(parse_dimension("x+y"), Ok(Dimension::XY));
+        assert_eq!(parse_dimension("x, y, z"), Ok(Dimension::XYZ));
+    }
+
+    #[test]
+    fn parse_dimension_invalid() {
+        assert!(parse_dimension("").is_err());
+        assert!(parse_dimension(",").is_err());
+        assert!(parse_dimension("xa").is_err());
+        assert!(parse_dimension("xx").is_err());
+        assert!(parse_dimension("x;y").is_err());
+    }
+
+    #[test]
+    fn parse_target_values() {
+        assert_eq!(
+            parse_target("2.5,3,-1.0"),
+            Ok(Target([Some(2.5), Some(3.0), Some(-1.0)]))
+        );
+        assert_eq!(
+            parse_target("-, -,4.2"),
+            Ok(Target([None, None, Some(4.2)]))
+        );
+        assert_eq!(parse_target("-,-,-"), Ok(Target([None, None, None])));
+    }
+
+    #[test]
+    fn parse_transform_values() {
+        assert_eq!(
+            parse_transform("center"),
+            Ok(Transform(vec![TransformStep::Center]))
+        );
+        assert_eq!(
+            parse_transform("whole, center,wrap"),
+            Ok(Transform(vec![
+                TransformStep::Whole,
+                TransformStep::Center,
+                TransformStep::Wrap
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_transform_invalid() {
+        assert!(parse_transform("").is_err());
+        assert!(parse_transform("center,fit").is_err());
+        assert!(parse_transform("center,center").is_err());
+        assert!(parse_transform("whole,wrap").is_err());
+        assert!(parse_transform("center,").is_err());
+    }
+
+    #[test]
+    fn parse_weighted_group_values() {
+        assert_eq!(
+            parse_weighted_group("Protein"),
+            Ok(WeightedGroup {
+                query: String::from("Protein"),
+                weight: 1.0
+            })
+        );
+        assert_eq!(
+            parse_weighted_group("resname LIG : 2.5"),
+            Ok(WeightedGroup {
+                query: String::from("resname LIG"),
+                weight: 2.5
+            })
+        );
+    }
+
+    #[test]
+    fn parse_weighted_group_invalid() {
+        assert!(parse_weighted_group("").is_err());
+        assert!(parse_weighted_group(":2").is_err());
+        assert!(parse_weighted_group("Protein:").is_err());
+        assert!(parse_weighted_group("Protein:a").is_err());
+        assert!(parse_weighted_group("Protein:0").is_err());
+        assert!(parse_weighted_group("Protein:-1").is_err());
+    }
+
+    #[test]
+    fn parse_box_values() {
+        assert_eq!(parse_box("10,10.5, 12.0"), Ok(BoxSize([10.0, 10.5, 12.0])));
+    }
+
+    #[test]
+    fn parse_template_box_values() {
+        assert_eq!(parse_template_box("max"), Ok(TemplateBox::Max));
+        assert_eq!(parse_template_box(" 12.5"), Ok(TemplateBox::Edge(12.5)));
+        assert!(parse_template_box("-3.0").is_err());
+        assert!(parse_template_box("0").is_err());
+        assert!(parse_template_box("min").is_err());
+    }
+
+    #[test]
+    fn parse_wrap_dimension_values() {
+        assert_eq!(parse_wrap_dimension("none"), Ok(Dimension::None));
+        assert_eq!(parse_wrap_dimension("xy"), Ok(Dimension::XY));
+        assert_eq!(parse_wrap_dimension("z"), Ok(Dimension::Z));
+        assert!(parse_wrap_dimension("").is_err());
+        assert!(parse_wrap_dimension("w").is_err());
+    }
+
+    #[test]
+    fn parse_error_format_values() {
+        assert_eq!(parse_error_format("text"), Ok(ErrorFormat::Text));
+        assert_eq!(parse_error_format(" json"), Ok(ErrorFormat::Json));
+        assert!(parse_error_format("JSON").is_err());
+        assert!(parse_error_format("yaml").is_err());
+    }
+
+    #[test]
+    fn parse_time_spacing_values() {
+        assert_eq!(parse_time_spacing("100"), Ok(100.0));
+        assert_eq!(parse_time_spacing(" 2.5 "), Ok(2.5));
+        assert!(parse_time_spacing("0").is_err());
+        assert!(parse_time_spacing("-10").is_err());
+        assert!(parse_time_spacing("ten").is_err());
+    }
+
+    #[test]
+    fn parse_jump_threshold_values() {
+        assert_eq!(parse_jump_threshold("1.5"), Ok(1.5));
+        assert!(parse_jump_threshold("0.0").is_err());
+        assert!(parse_jump_threshold("-1").is_err());
+        assert!(parse_jump_threshold("inf").is_err());
+    }
+
+    #[test]
+    fn split_weighting_suffix() {
+        assert_eq!(
+            split_weighting("Protein:com"),
+            ("Protein", Some(Weighting::Mass))
+        );
+        assert_eq!(
+            split_weighting("resname POPC:geom "),
+            ("resname POPC", Some(Weighting::Geometry))
+        );
+        assert_eq!(split_weighting("name CA"), ("name CA", None));
+        assert_eq!(split_weighting("Protein:comx"), ("Protein:comx", None));
+    }
+
+    #[test]
+    fn parse_group_name_values() {
+        assert_eq!(parse_group_name("Peptide"), Ok(String::from("Peptide")));
+        assert!(parse_group_name("").is_err());
+        assert!(parse_group_name("my peptide").is_err());
+    }
+
+    #[test]
+    fn parse_box_invalid() {
+        assert!(parse_box("").is_err());
+        assert!(parse_box("10.0,10.0").is_err());
+        assert!(parse_box("10.0,10.0,10.0,10.0").is_err());
+        assert!(parse_box("10.0,a,10.0").is_err());
+        assert!(parse_box("10.0,0.0,10.0").is_err());
+        assert!(parse_box("10.0,-5.0,10.0").is_err());
+        assert!(parse_box("10.0,inf,10.0").is_err());
+    }
+
+    #[test]
+    fn parse_target_invalid() {
+        assert!(parse_target("").is_err());
+        assert!(parse_target("1.0,2.0").is_err());
+        assert!(parse_target("1.0,2.0,3.0,4.0").is_err());
+        assert!(parse_target("1.0,a,3.0").is_err());
+        assert!(parse_target("1.0,NaN,3.0").is_err());
+    }
+
+    #[test]
+    fn parse_serials_values() {
+        assert_eq!(parse_serials("3"), Ok(Serials(vec![(3, 3)])));
+        assert_eq!(
+            parse_serials("3, 5,7-12"),
+            Ok(Serials(vec![(3, 3), (5, 5), (7, 12)]))
+        );
+        assert_eq!(parse_serials("4-4"), Ok(Serials(vec![(4, 4)])));
+    }
+
+    #[test]
+    fn parse_serials_invalid() {
+        assert!(parse_serials("").is_err());
+        assert!(parse_serials("0").is_err());
+        assert!(parse_serials("3,,5").is_err());
+        assert!(parse_serials("12-7").is_err());
+        assert!(parse_serials("1-a").is_err());
+        assert!(parse_serials("-5").is_err());
+        assert!(parse_serials("1-3-5").is_err());
+    }
+
+    #[test]
+    fn serials_indices() {
+        let serials = Serials(vec![(3, 3), (5, 5), (7, 9)]);
+        assert_eq!(serials.indices(), vec![2, 4, 6, 7, 8]);
+        assert_eq!(serials.max(), 9);
+    }
+
+    #[test]
+    fn serials_display() {
+        assert_eq!(
+            Serials(vec![(3, 3), (5, 5), (7, 12)]).to_string(),
+            "3,5,7-12"
+        );
+    }
+
+    #[test]
+    fn target_display() {
+        assert_eq!(Target([Some(2.5), None, Some(4.0)]).to_string(), "2.5,-,4");
+    }
+
+    #[test]
+    fn reference_words_joined() {
+        let mut args = Args::parse_from([
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "--reference-words",
+            "resname",
+            "ASN",
+            "and",
+            "serial",
+            "35",
+            "to",
+            "45",
+            "-o=output.gro",
+        ]);
+
+        join_reference_words(&mut args);
+        assert_eq!(args.reference, "resname ASN and serial 35 to 45");
+        assert_eq!(args.output, "output.gro");
+    }
+}
@@ -0,0 +1,77 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of measuring the time spent in the individual stages of the centering.
+
+use std::time::Duration;
+
+use colored::Colorize;
+use log::info;
+
+/// Time spent in the individual stages of the run.
+#[derive(Debug, Default)]
+pub struct Benchmark {
+    /// Names of the stages and the time spent in them, in the order in which they were first recorded.
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl Benchmark {
+    /// Add time spent in the specified stage.
+    /// Time of a stage that has already been recorded is accumulated.
+    pub fn add(&mut self, stage: &'static str, duration: Duration) {
+        match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += duration,
+            None => self.stages.push((stage, duration)),
+        }
+    }
+
+    /// Get the total time spent in all stages.
+    fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Log a table with the time spent in the individual stages.
+    pub fn print(&self) {
+        let total = self.total().as_secs_f64();
+
+        let mut table = format!("{}\n", "[BENCHMARK]".bold());
+        for (stage, duration) in self.stages.iter() {
+            let seconds = duration.as_secs_f64();
+            let percent = if total > 0.0 {
+                100.0 * seconds / total
+            } else {
+                0.0
+            };
+
+            table.push_str(&format!(
+                "  {:<24} {:>10.3} s {:>7.1} %\n",
+                stage, seconds, percent
+            ));
+        }
+        table.push_str(&format!("  {:<24} {:>10.3} s", "total", total));
+
+        info!("{}\n", table);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_accumulates() {
+        let mut benchmark = Benchmark::default();
+        benchmark.add("reading", Duration::from_millis(10));
+        benchmark.add("centering", Duration::from_millis(5));
+        benchmark.add("reading", Duration::from_millis(20));
+
+        assert_eq!(
+            benchmark.stages,
+            vec![
+                ("reading", Duration::from_millis(30)),
+                ("centering", Duration::from_millis(5))
+            ]
+        );
+        assert_eq!(benchmark.total(), Duration::from_millis(35));
+    }
+}
@@ -0,0 +1,419 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of caching the resolved reference groups across repeated runs of `gcenter`.
+
+use colored::Colorize;
+use groan_rs::structures::dimension::Dimension;
+use groan_rs::system::System;
+use log::debug;
+
+use crate::argparse::{Args, Weighting};
+use crate::errors::RunError;
+use crate::reference::Operation;
+
+/// First line of each reference cache file.
+const CACHE_HEADER: &str = "; gcenter reference cache";
+
+/// 64-bit FNV-1a hash used to calculate the cache key.
+/// Unlike the hashers of the standard library, the algorithm is fixed,
+/// so the key calculated for the same system and options does not change between builds of `gcenter`.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    fn new() -> Self {
+        Fnv1a(Self::OFFSET_BASIS)
+    }
+
+    /// Add the bytes to the hash.
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Add a string to the hash. The string is terminated so that consecutive strings can not be merged.
+    fn write_str(&mut self, s: &str) {
+        // 0xff never occurs in a valid UTF-8 string
+        self.update(s.as_bytes());
+        self.update(&[0xff]);
+    }
+
+    /// Add the content of the file to the hash, if the file exists.
+    fn write_file(&mut self, filename: Option<&str>) {
+        if let Some(content) = filename.and_then(|file| std::fs::read(file).ok()) {
+            self.write_str(&content.len().to_string());
+            self.update(&content);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Get the key identifying the selection of the reference atoms in the system.
+/// The key changes if the atoms of the system, the options selecting the reference atoms,
+/// or the content of the files used when selecting the atoms change.
+fn cache_key(system: &System, dim: Dimension, args: &Args) -> String {
+    let mut hasher = Fnv1a::new();
+
+    for atom in system.atoms_iter() {
+        hasher.write_str(atom.get_atom_name());
+        hasher.write_str(atom.get_residue_name());
+        hasher.write_str(&atom.get_residue_number().to_string());
+    }
+
+    let options = format!(
+        "{:?}",
+        (
+            (
+                &args.reference,
+                &args.reference_serials,
+                &args.reference_groups
+            ),
+            (&args.xreference, &args.yreference, &args.zreference),
+            (args.xweighting, args.yweighting, args.zweighting),
+            (args.xref_invert, args.yref_invert, args.zref_invert),
+            (args.invert_selection, args.allow_empty_reference, dim),
+            (&args.elements, &args.masses, args.weights_from_bfactor),
+        )
+    );
+    hasher.write_str(&options);
+
+    hasher.write_file(Some(args.index.as_deref().unwrap_or("index.ndx")));
+    hasher.write_file(args.elements.as_deref());
+    hasher.write_file(args.masses.as_deref());
+
+    format!("{:016x}", hasher.finish())
+}
+
+/// Convert dimensions to a string, e.g. 'xz'.
+fn dimension2string(dim: Dimension) -> String {
+    [(dim.is_x(), 'x'), (dim.is_y(), 'y'), (dim.is_z(), 'z')]
+        .into_iter()
+        .filter_map(|(used, c)| used.then_some(c))
+        .collect()
+}
+
+/// Parse dimensions from a string written by `dimension2string`.
+fn string2dimension(s: &str) -> Option<Dimension> {
+    if s.is_empty() || s.chars().any(|c| !"xyz".contains(c)) {
+        return None;
+    }
+
+    Some([s.contains('x'), s.contains('y'), s.contains('z')].into())
+}
+
+/// Convert the weighting of an operation to a string.
+fn weighting2string(weighting: Option<Weighting>) -> &'static str {
+    match weighting {
+        None => "-",
+        Some(Weighting::Mass) => "mass",
+        Some(Weighting::Geometry) => "geometry",
+    }
+}
+
+/// Parse the weighting of an operation from a string written by `weighting2string`.
+fn string2weighting(s: &str) -> Option<Option<Weighting>> {
+    match s {
+        "-" => Some(None),
+        "mass" => Some(Some(Weighting::Mass)),
+        "geometry" => Some(Some(Weighting::Geometry)),
+        _ => None,
+    }
+}
+
+/// Get the names of all groups required for centering, i.e. the reference groups of the operations
+/// and the groups contributing to the weighted reference.
+fn required_groups(operations: &[Operation], args: &Args) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in operations
+        .iter()
+        .map(|(name, _, _)| name.to_owned())
+        .chain((0..args.reference_groups.len()).map(crate::reference::weighted_group_name))
+    {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+/// Write the resolved reference groups and the centering operations into the cache file.
+/// The groups are written in the ndx format, preceded by comment lines describing the system and the operations.
+pub fn write_cache(
+    system: &System,
+    dim: Dimension,
+    operations: &[Operation],
+    args: &Args,
+    filename: &str,
+) -> Result<(), RunError> {
+    let mut content = format!(
+        "{}\n; atoms {}\n; key {}\n",
+        CACHE_HEADER,
+        system.get_n_atoms(),
+        cache_key(system, dim, args)
+    )
+    .into_bytes();
+
+    for (name, dimension, weighting) in operations {
+        content.extend(
+            format!(
+                "; operation {} {} {}\n",
+                name,
+                dimension2string(*dimension),
+                weighting2string(*weighting)
+            )
+            .bytes(),
+        );
+    }
+
+    let groups = system.get_groups_copy();
+    for name in required_groups(operations, args) {
+        match groups.get(&name) {
+            Some(group) => group
+                .write_ndx(&mut content, &name)
+                .map_err(|_| RunError::ReferenceCacheNotWritten(filename.to_owned()))?,
+            None => panic!(
+                "\ngcenter: Fatal Error. Reference group '{}' does not exist.",
+                name
+            ),
+        }
+    }
+
+    std::fs::write(filename, content)
+        .map_err(|_| RunError::ReferenceCacheNotWritten(filename.to_owned()))
+}
+
+/// Content of a parsed cache file.
+#[derive(Debug, Clone, PartialEq)]
+struct Cache {
+    /// Centering operations.
+    operations: Vec<Operation>,
+    /// Names of the groups and the indices of their atoms (starting from 0).
+    groups: Vec<(String, Vec<usize>)>,
+}
+
+/// Parse the content of a cache file and check that it belongs to a system with `n_atoms` atoms and the provided key.
+fn parse_cache(content: &str, n_atoms: usize, key: &str) -> Result<Cache, String> {
+    let mut lines = content.lines();
+    if lines.next() != Some(CACHE_HEADER) {
+        return Err(String::from("file is not a reference cache"));
+    }
+
+    let mut operations = Vec::new();
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    let (mut atoms, mut cached_key) = (None, None);
+
+    for line in lines {
+        let line = line.trim();
+
+        if let Some(comment) = line.strip_prefix(';') {
+            match comment.split_whitespace().collect::<Vec<&str>>()[..] {
+                ["atoms", n] => atoms = n.parse::<usize>().ok(),
+                ["key", k] => cached_key = Some(k.to_owned()),
+                ["operation", name, dims, weighting] => operations.push((
+                    name.to_owned(),
+                    string2dimension(dims).ok_or("invalid dimensions of an operation")?,
+                    string2weighting(weighting).ok_or("invalid weighting of an operation")?,
+                )),
+                _ => return Err(format!("could not parse line '{}'", line)),
+            }
+        } else if let Some(name) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            groups.push((name.trim().to_owned(), Vec::new()));
+        } else if !line.is_empty() {
+            let (_, indices) = groups.last_mut().ok_or("atom indices outside of a group")?;
+            for index in line.split_whitespace() {
+                match index.parse::<usize>() {
+                    Ok(i) if (1..=n_atoms).contains(&i) => indices.push(i - 1),
+                    _ => return Err(format!("invalid atom index '{}'", index)),
+                }
+            }
+        }
+    }
+
+    if atoms != Some(n_atoms) {
+        return Err(String::from("number of atoms does not match the system"));
+    }
+
+    if cached_key.as_deref() != Some(key) {
+        return Err(String::from(
+            "selection of the reference atoms or the system has changed",
+        ));
+    }
+
+    if let Some((name, _, _)) = operations
+        .iter()
+        .find(|(name, _, _)| groups.iter().all(|(group, _)| group != name))
+    {
+        return Err(format!("reference group '{}' is missing", name));
+    }
+
+    Ok(Cache { operations, groups })
+}
+
+/// Read the reference groups and the centering operations from the cache file and create the groups in the system.
+/// Returns `None` if the cache does not exist or does not match the current system and options,
+/// in which case the reference groups must be selected again.
+pub fn read_cache(
+    system: &mut System,
+    dim: Dimension,
+    args: &Args,
+    filename: &str,
+) -> Option<Vec<Operation>> {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(x) => x,
+        Err(_) => {
+            debug!(
+                "{} reference cache '{}' not found; reference groups will be selected and cached\n",
+                "note:".purple().bold(),
+                filename.yellow()
+            );
+            return None;
+        }
+    };
+
+    let key = cache_key(system, dim, args);
+    let cache = match parse_cache(&content, system.get_n_atoms(), &key) {
+        Ok(x) => x,
+        Err(e) => {
+            debug!(
+                "{} reference cache '{}' can not be used ({}); reference groups will be selected again\n",
+                "note:".purple().bold(),
+                filename.yellow(),
+                e
+            );
+            return None;
+        }
+    };
+
+    let required = required_groups(&cache.operations, args);
+    for (name, indices) in cache.groups {
+        if required.contains(&name) {
+            // the groups do not exist yet, so no group is overwritten
+            system
+                .group_create_from_indices(&name, indices)
+                .expect("\ngcenter: Fatal Error. Cached group could not be created.");
+        }
+    }
+
+    debug!(
+        "{} reference groups read from the cache '{}'\n",
+        "note:".purple().bold(),
+        filename.yellow()
+    );
+
+    Some(cache.operations)
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+
+    fn args(command_line: &[&str]) -> Args {
+        let base = ["gcenter", "-s=tests/test_files/input.gro", "-o=output.gro"];
+        Args::parse_from(base.iter().chain(command_line.iter()))
+    }
+
+    #[test]
+    fn dimension_strings() {
+        for dim in [Dimension::X, Dimension::YZ, Dimension::XZ, Dimension::XYZ] {
+            assert_eq!(string2dimension(&dimension2string(dim)), Some(dim));
+        }
+
+        assert_eq!(string2dimension(""), None);
+        assert_eq!(string2dimension("xw"), None);
+    }
+
+    #[test]
+    fn fnv1a_reference_values() {
+        let mut hasher = Fnv1a::new();
+        assert_eq!(hasher.finish(), 0xcbf29ce484222325);
+
+        hasher.update(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+        let mut hasher = Fnv1a::new();
+        hasher.update(b"foobar");
+        assert_eq!(hasher.finish(), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn cache_roundtrip() {
+        let mut args = args(&["--zref=@membrane"]);
+        args.zweighting = Some(Weighting::Mass);
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let operations =
+            crate::reference::create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        let file = tempfile::Builder::new().suffix(".ndx").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+        write_cache(&system, Dimension::XYZ, &operations, &args, path).unwrap();
+
+        let mut cached = System::from_file("tests/test_files/input.gro").unwrap();
+        assert_eq!(
+            read_cache(&mut cached, Dimension::XYZ, &args, path),
+            Some(operations.clone())
+        );
+
+        for (name, _, _) in operations {
+            let original: Vec<usize> = system
+                .group_iter(&name)
+                .unwrap()
+                .map(|atom| atom.get_index())
+                .collect();
+            let read: Vec<usize> = cached
+                .group_iter(&name)
+                .unwrap()
+                .map(|atom| atom.get_index())
+                .collect();
+            assert_eq!(original, read);
+        }
+    }
+
+    #[test]
+    fn cache_mismatch() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let original = args(&[]);
+        let operations =
+            crate::reference::create_references(&mut system, Dimension::XYZ, &original).unwrap();
+
+        let file = tempfile::Builder::new().suffix(".ndx").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+        write_cache(&system, Dimension::XYZ, &operations, &original, path).unwrap();
+
+        // different reference
+        let mut cached = System::from_file("tests/test_files/input.gro").unwrap();
+        assert_eq!(
+            read_cache(&mut cached, Dimension::XYZ, &args(&["-r=@membrane"]), path),
+            None
+        );
+
+        // different dimensions
+        assert_eq!(
+            read_cache(&mut cached, Dimension::XY, &original, path),
+            None
+        );
+        assert!(!cached.group_exists(crate::MAIN_REFERENCE));
+    }
+
+    #[test]
+    fn parse_cache_invalid() {
+        let content = "; gcenter reference cache\n; atoms 3\n; key abc\n; operation CNTR-Main xyz -\n[ CNTR-Main ]\n1 2\n";
+        assert!(parse_cache(content, 3, "abc").is_ok());
+        assert!(parse_cache(content, 4, "abc").is_err());
+        assert!(parse_cache(content, 3, "abd").is_err());
+        assert!(parse_cache(&content.replace("[ CNTR-Main ]\n", ""), 3, "abc").is_err());
+        assert!(parse_cache(&content.replace("1 2", "1 4"), 3, "abc").is_err());
+        assert!(parse_cache(&content.replace(" xyz ", " xyw "), 3, "abc").is_err());
+        assert!(parse_cache(&content.replace("; gcenter", "; other"), 3, "abc").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of a machine-readable description of the capabilities of `gcenter`.
+
+use std::fmt::Display;
+
+use groan_rs::files::FileType;
+
+use crate::argparse::{
+    COORDINATES_TYPES, STRUCTURE_OUTPUT_TYPES, STRUCTURE_TYPES, TRAJECTORY_OUTPUT_TYPES,
+    TRAJECTORY_TYPES,
+};
+
+/// Optional features and whether they are supported by this build of `gcenter`.
+const FEATURES: [(&str, bool); 5] = [
+    ("cif", true),
+    ("xtc_precision", true),
+    ("tng", false),
+    ("gzip", true),
+    ("threads", false),
+];
+
+/// Convert a list of items into a JSON array of strings.
+fn json_array(items: impl IntoIterator<Item = impl Display>) -> String {
+    let items = items
+        .into_iter()
+        .map(|item| format!("\"{}\"", item))
+        .collect::<Vec<String>>();
+
+    format!("[{}]", items.join(", "))
+}
+
+/// Get the supported formats of input structure files, including those not handled by `groan_rs`.
+fn structure_formats() -> Vec<String> {
+    STRUCTURE_TYPES
+        .iter()
+        .map(|t| t.to_string())
+        .chain([String::from("cif"), String::from("g96")])
+        .collect()
+}
+
+/// Get the supported formats of output files when centering a structure, including those not handled by `groan_rs`.
+fn structure_output_formats() -> Vec<String> {
+    STRUCTURE_OUTPUT_TYPES
+        .iter()
+        .map(|t| t.to_string())
+        .chain([String::from("g96")])
+        .collect()
+}
+
+/// Convert a list of items into a comma-separated list.
+fn plain_list(items: impl IntoIterator<Item = impl Display>) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Get a human-readable list of the file formats supported by `gcenter`.
+pub fn formats() -> String {
+    format!(
+        "Input structure:      {}
+Input coordinates:    {}
+Input trajectory:     {}
+Input compressed:     {}
+Input index:          {}
+Input elements:       {}
+Output (structure):   {}
+Output (trajectory):  {}",
+        plain_list(structure_formats()),
+        plain_list(COORDINATES_TYPES),
+        plain_list(TRAJECTORY_TYPES),
+        plain_list(["xtc.gz", "trr.gz"]),
+        plain_list([FileType::NDX]),
+        plain_list([FileType::YAML]),
+        plain_list(structure_output_formats()),
+        plain_list(TRAJECTORY_OUTPUT_TYPES),
+    )
+}
+
+/// Get a JSON object describing the version, supported file formats, and features of `gcenter`.
+pub fn capabilities() -> String {
+    let features = FEATURES
+        .iter()
+        .map(|(name, supported)| format!("    \"{}\": {}", name, supported))
+        .collect::<Vec<String>>()
+        .join(",\n");
+
+    format!(
+        "{{
+  \"name\": \"gcenter\",
+  \"version\": \"{}\",
+  \"input\": {{
+    \"structure\": {},
+    \"coordinates\": {},
+    \"trajectory\": {},
+    \"index\": {},
+    \"elements\": {}
+  }},
+  \"output\": {{
+    \"structure\": {},
+    \"trajectory\": {}
+  }},
+  \"features\": {{
+{}
+  }}
+}}",
+        env!("CARGO_PKG_VERSION"),
+        json_array(structure_formats()),
+        json_array(COORDINATES_TYPES),
+        json_array(TRAJECTORY_TYPES),
+        json_array([FileType::NDX]),
+        json_array([FileType::YAML]),
+        json_array(structure_output_formats()),
+        json_array(TRAJECTORY_OUTPUT_TYPES),
+        features,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_array_formats() {
+        assert_eq!(json_array(["xtc", "trr"]), "[\"xtc\", \"trr\"]");
+        assert_eq!(json_array(Vec::<String>::new()), "[]");
+    }
+
+    #[test]
+    fn formats_list() {
+        let output = formats();
+
+        assert!(output.contains("Input structure:      gro, pdb, pqr, tpr, cif, g96\n"));
+        assert!(output.contains("Input trajectory:     xtc, trr, gro\n"));
+        assert!(output.contains("Output (structure):   gro, pdb, pqr, g96\n"));
+        assert!(output.ends_with("Output (trajectory):  xtc, trr, gro"));
+    }
+
+    #[test]
+    fn capabilities_formats() {
+        let output = capabilities();
+
+        assert!(output.contains(&format!("\"version\": \"{}\"", env!("CARGO_PKG_VERSION"))));
+        assert!(output
+            .contains("\"structure\": [\"gro\", \"pdb\", \"pqr\", \"tpr\", \"cif\", \"g96\"]"));
+        assert!(output.contains("\"trajectory\": [\"xtc\", \"trr\", \"gro\"]"));
+        assert!(output.contains("\"tng\": false"));
+        assert!(output.starts_with('{'));
+        assert!(output.ends_with('}'));
+    }
+}
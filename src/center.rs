@@ -3,217 +3,3326 @@
 
 //! Implementation of the centering procedure.
 
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
 use colored::Colorize;
-use groan_rs::errors::ReadTrajError;
+use groan_rs::errors::{
+    AtomError, GroupError, MassError, PositionError, ReadTrajError, SimBoxError, WriteTrajError,
+};
 use groan_rs::files::FileType;
 use groan_rs::io::traj_read::{
-    FrameDataTime, TrajMasterRead, TrajRangeRead, TrajRead, TrajReader, TrajStepRead,
+    FrameDataTime, ProgressPrintable, TrajMasterRead, TrajRangeRead, TrajRead, TrajReadOpen,
+    TrajReader, TrajStepRead,
 };
+use groan_rs::io::xtc_io::XtcReader;
 use groan_rs::prelude::{TrajRangeStepReader, TrajStepReader};
 use groan_rs::progress::ProgressPrinter;
+use groan_rs::structures::atom::Atom;
 use groan_rs::structures::dimension::Dimension;
+use groan_rs::structures::simbox::SimBox;
+use groan_rs::structures::vector3d::Vector3D;
 use groan_rs::system::System;
+use log::{debug, trace, warn};
 
-use crate::argparse::Args;
+use crate::argparse::{Args, BoxSize, Target, TemplateBox, TransformStep, Verbosity, Weighting};
+use crate::benchmark::Benchmark;
 use crate::errors::RunError;
+use crate::header;
+use crate::interrupt::Interrupt;
+use crate::reference::Operation;
+use crate::xtc_index::{load_index, seek_to_start, XtcIndex};
 
-/// Check that the simulation is valid (defined, non-zero and orthogonal).
-fn check_simulation_box(system: &System) -> Result<(), RunError> {
-    match system.get_box() {
-        None => return Err(RunError::BoxNotDefined),
-        Some(x) => {
-            if !x.is_orthogonal() {
-                return Err(RunError::BoxNotOrthogonal);
-            }
+/// Method used to calculate the position of a reference group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    /// Center of geometry.
+    Geometry,
+    /// Center of mass.
+    Mass,
+    /// Coordinate-wise median.
+    Median,
+    /// Center of geometry along x and y, midplane between the membrane leaflets along z.
+    PlaneFit,
+    /// Average of the centers of mass of the individual residues.
+    ResidueMass,
+    /// Center of the axis-aligned bounding box of the group.
+    BoundingBox,
+    /// Center of the simulation box, independent of the group.
+    BoxCenter,
+}
 
-            if x.x <= 0.0 || x.y <= 0.0 || x.z <= 0.0 {
-                return Err(RunError::BoxNotValid);
-            }
+impl Method {
+    /// Get the method requested by the user.
+    fn from_args(args: &Args) -> Self {
+        if args.com {
+            Method::Mass
+        } else if args.median {
+            Method::Median
+        } else if args.plane_fit {
+            Method::PlaneFit
+        } else if args.by_residue_com {
+            Method::ResidueMass
+        } else if args.bbox_center {
+            Method::BoundingBox
+        } else if args.recenter_box {
+            Method::BoxCenter
+        } else {
+            Method::Geometry
+        }
+    }
+
+    /// Get the method used for a centering operation with the specified weighting.
+    /// Operations without weighting use this method.
+    fn with_weighting(self, weighting: Option<Weighting>) -> Self {
+        match weighting {
+            Some(Weighting::Mass) => Method::Mass,
+            Some(Weighting::Geometry) => Method::Geometry,
+            None => self,
+        }
+    }
+}
+
+/// Get the median of the provided values. The values are sorted in place.
+/// For an even number of values, the median is the average of the two middle values.
+/// The values are sorted using their total order, so the result does not depend on the order of the values.
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let n = values.len();
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    }
+}
+
+/// Calculate the coordinate-wise median of atom positions of the group.
+/// The positions are unwrapped relative to the center of geometry of the group
+/// so that the median respects periodic boundary conditions.
+fn group_get_median(
+    system: &System,
+    group: &str,
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let center = system.group_get_center(group)?;
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?;
+
+    let mut coordinates: [Vec<f32>; 3] = Default::default();
+    for atom in system.group_iter(group)? {
+        let position =
+            atom.get_position()
+                .ok_or(GroupError::InvalidPosition(PositionError::NoPosition(
+                    atom.get_index(),
+                )))?;
+
+        let relative = center.vector_to(position, simbox);
+        coordinates[0].push(relative.x);
+        coordinates[1].push(relative.y);
+        coordinates[2].push(relative.z);
+    }
+
+    let mut result = Vector3D::new(
+        center.x + median(&mut coordinates[0]),
+        center.y + median(&mut coordinates[1]),
+        center.z + median(&mut coordinates[2]),
+    );
+    result.wrap(simbox);
+
+    Ok(result)
+}
+
+/// Calculate the center of the axis-aligned bounding box of the group.
+/// The positions are unwrapped relative to the center of geometry of the group
+/// so that the bounding box does not span the periodic boundary.
+/// Only the extreme coordinates are used, so ties between atoms with the same extreme coordinate do not matter.
+fn group_get_bbox_center(
+    system: &System,
+    group: &str,
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let center = system.group_get_center(group)?;
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?;
+
+    let mut min = Vector3D::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut max = Vector3D::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for atom in system.group_iter(group)? {
+        let position =
+            atom.get_position()
+                .ok_or(GroupError::InvalidPosition(PositionError::NoPosition(
+                    atom.get_index(),
+                )))?;
+
+        let relative = center.vector_to(position, simbox);
+        min = Vector3D::new(
+            min.x.min(relative.x),
+            min.y.min(relative.y),
+            min.z.min(relative.z),
+        );
+        max = Vector3D::new(
+            max.x.max(relative.x),
+            max.y.max(relative.y),
+            max.z.max(relative.z),
+        );
+    }
+
+    let mut result = Vector3D::new(
+        center.x + (min.x + max.x) / 2.0,
+        center.y + (min.y + max.y) / 2.0,
+        center.z + (min.z + max.z) / 2.0,
+    );
+    result.wrap(simbox);
+
+    Ok(result)
+}
+
+/// Calculate the center of geometry of the group with the z-coordinate placed at the midplane between the membrane leaflets.
+/// Atoms are assigned to the upper or lower leaflet based on their position relative to the center of geometry along z.
+/// If one of the leaflets is empty, the center of geometry is returned.
+fn group_get_midplane(
+    system: &System,
+    group: &str,
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let center = system.group_get_center(group)?;
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?;
+
+    // sums of relative z-coordinates and numbers of atoms in the upper and lower leaflet
+    let mut upper = (0.0, 0);
+    let mut lower = (0.0, 0);
+    for atom in system.group_iter(group)? {
+        let position =
+            atom.get_position()
+                .ok_or(GroupError::InvalidPosition(PositionError::NoPosition(
+                    atom.get_index(),
+                )))?;
+
+        let z = center.vector_to(position, simbox).z;
+        let leaflet = if z > 0.0 { &mut upper } else { &mut lower };
+        leaflet.0 += z;
+        leaflet.1 += 1;
+    }
+
+    let offset = match (upper, lower) {
+        ((upper_sum, upper_n), (lower_sum, lower_n)) if upper_n > 0 && lower_n > 0 => {
+            (upper_sum / upper_n as f32 + lower_sum / lower_n as f32) / 2.0
         }
+        _ => 0.0,
     };
 
-    Ok(())
+    let mut result = Vector3D::new(center.x, center.y, center.z + offset);
+    result.wrap(simbox);
+
+    Ok(result)
 }
 
-/// Ignore error returned by `check_simulation_box` and print a warning instead.
-/// Used when centering a trajectory.
-fn simbox_error_to_warning(error: Result<(), RunError>, silent: bool) {
-    if !silent {
-        match error {
-            Ok(_) => (),
-            Err(RunError::BoxNotDefined) => eprintln!("{} input structure file has an undefined simulation box.\n", "warning:".yellow().bold()),
-            Err(RunError::BoxNotValid) => eprintln!("{} input structure file has an invalid simulation box (some dimensions are not positive).\n", "warning:".yellow().bold()),
-            Err(RunError::BoxNotOrthogonal) => eprintln!("{} input structure file has a non-orthogonal simulation box.\n", "warning:".yellow().bold()),
-            Err(_) => panic!("\ngcenter: Fatal Error. Unexpected error type returned when checking the simulation box."),
+/// Calculate the center of the points using the Bai & Breen algorithm, i.e. respecting periodic boundary conditions.
+/// All points contribute equally.
+fn periodic_mean(points: &[Vector3D], simbox: &SimBox) -> Vector3D {
+    let mut result = Vector3D::default();
+    for (dim, length) in [simbox.x, simbox.y, simbox.z].into_iter().enumerate() {
+        let (mut sin, mut cos) = (0.0, 0.0);
+        for point in points {
+            let theta = point[dim] / length * 2.0 * std::f32::consts::PI;
+            sin += theta.sin();
+            cos += theta.cos();
         }
+
+        let n = points.len() as f32;
+        let theta = (-sin / n).atan2(-cos / n) + std::f32::consts::PI;
+        result[dim] = length * theta / (2.0 * std::f32::consts::PI);
     }
+
+    result.wrap(simbox);
+    result
 }
 
-/// Center the reference group and write an output gro or pdb file.
-fn center_structure_file(
-    system: &mut System,
-    output: &str,
-    output_type: FileType,
-    operations: Vec<(String, Dimension)>,
-    com: bool,
-    whole: bool,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    check_simulation_box(system)?;
+/// Calculate the average of the centers of mass of the individual residues of the group.
+/// Every residue contributes equally, irrespective of the number of its atoms.
+/// The center of mass of each residue is calculated from atom positions unwrapped relative to the first atom of the residue
+/// and the centers of the residues are averaged using the Bai & Breen algorithm.
+fn group_get_residue_com(
+    system: &System,
+    group: &str,
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?;
 
-    for (group, dims) in operations.iter() {
-        if com {
-            system.atoms_center_mass(group, *dims)?
-        } else {
-            system.atoms_center(group, *dims)?
+    // residue number and name, position of the first atom, mass-weighted sum of relative positions, and total mass
+    let mut current: Option<((usize, &str), Vector3D, Vector3D, f32)> = None;
+    let mut centers = Vec::new();
+    for atom in system.group_iter(group)? {
+        let position =
+            atom.get_position()
+                .ok_or(GroupError::InvalidPosition(PositionError::NoPosition(
+                    atom.get_index(),
+                )))?;
+        let mass = atom
+            .get_mass()
+            .ok_or(GroupError::InvalidMass(MassError::NoMass(atom.get_index())))?;
+
+        let residue = (atom.get_residue_number(), atom.get_residue_name().as_str());
+        if current.as_ref().map(|x| x.0) != Some(residue) {
+            if let Some((_, origin, sum, total)) = current.take() {
+                centers.push(origin + sum * (1.0 / total));
+            }
+            current = Some((residue, position.clone(), Vector3D::default(), 0.0));
+        }
+
+        if let Some((_, origin, sum, total)) = current.as_mut() {
+            let relative = origin.vector_to(position, simbox);
+            *sum = &*sum + relative * mass;
+            *total += mass;
         }
     }
 
-    if whole {
-        system.make_molecules_whole()?;
+    if let Some((_, origin, sum, total)) = current {
+        centers.push(origin + sum * (1.0 / total));
     }
 
-    match output_type {
-        FileType::GRO => system.write_gro(output, system.has_velocities())?,
-        FileType::PDB => system.write_pdb(output, system.has_bonds())?,
-        FileType::PQR => system.write_pqr(output, None)?,
-        _ => panic!("\ngcenter: Fatal Error. Output file has unsupported file extension but this should have been handled before."),
+    if centers.is_empty() {
+        return Err(Box::new(GroupError::EmptyGroup(group.to_owned())));
     }
 
-    Ok(())
+    Ok(periodic_mean(&centers, simbox))
 }
 
-/// Select range to read (with steps).
-fn read_range_step<'a, Read>(
-    reader: TrajReader<'a, Read>,
-    args: &Args,
-) -> Result<TrajRangeStepReader<'a, Read>, ReadTrajError>
-where
-    Read: TrajRead<'a> + TrajStepRead<'a> + TrajRangeRead<'a>,
-    <Read as TrajRead<'a>>::FrameData: FrameDataTime,
-{
-    let reader = match (args.start_time, args.end_time) {
-        (None, None) => reader.with_range(0.0, f32::MAX),
-        (Some(start), None) => reader.with_range(start, f32::MAX),
-        (None, Some(end)) => reader.with_range(0.0, end),
-        (Some(start), Some(end)) => reader.with_range(start, end),
-    }?;
+/// Get the names and weights of the groups contributing to the weighted main reference.
+/// Returns an empty vector if the main reference is not weighted.
+fn weighted_groups(args: &Args) -> Vec<(String, f32)> {
+    args.reference_groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| (crate::reference::weighted_group_name(i), group.weight))
+        .collect()
+}
 
-    reader.with_step(args.step)
+/// Returns true if the position of the group is the weighted average of the positions of the `weighted` groups.
+fn is_weighted(group: &str, weighted: &[(String, f32)]) -> bool {
+    group == crate::MAIN_REFERENCE && !weighted.is_empty()
 }
 
-/// Specify step of the trajectory reading.
-fn read_step<'a, Read>(
-    reader: TrajReader<'a, Read>,
-    args: &Args,
-) -> Result<TrajStepReader<'a, Read>, ReadTrajError>
-where
-    Read: TrajRead<'a> + TrajStepRead<'a>,
-{
-    reader.with_step(args.step)
+/// Calculate the weighted average of the positions of the groups using the specified method.
+/// The positions are unwrapped relative to the position of the first group so that the average respects periodic boundary conditions.
+fn weighted_point(
+    system: &System,
+    weighted: &[(String, f32)],
+    method: Method,
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?;
+    let total: f32 = weighted.iter().map(|(_, weight)| weight).sum();
+
+    let mut origin = None;
+    let mut offset = Vector3D::default();
+    for (group, weight) in weighted {
+        let position = reference_point(system, group, method, &[])?;
+        let origin = origin.get_or_insert_with(|| position.clone());
+
+        let relative = origin.vector_to(&position, simbox);
+        offset.x += relative.x * weight / total;
+        offset.y += relative.y * weight / total;
+        offset.z += relative.z * weight / total;
+    }
+
+    let origin = origin.unwrap_or_default();
+    let mut result = Vector3D::new(
+        origin.x + offset.x,
+        origin.y + offset.y,
+        origin.z + offset.z,
+    );
+    result.wrap(simbox);
+
+    Ok(result)
 }
 
-/// Center a trajectory.
-fn center_trajectory<'a>(
-    mut reader: impl TrajMasterRead<'a>,
-    args: &Args,
-    operations: Vec<(String, Dimension)>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    if !args.silent {
-        reader = reader.print_progress(
-            ProgressPrinter::new()
-                .with_running_msg("CENTERING".yellow())
-                .with_newline_at_end(false),
-        );
+/// Calculate the position of the reference group using the specified method.
+/// The position of the weighted main reference is the weighted average of the positions of the `weighted` groups.
+fn reference_point(
+    system: &System,
+    group: &str,
+    method: Method,
+    weighted: &[(String, f32)],
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    if is_weighted(group, weighted) {
+        return weighted_point(system, weighted, method);
     }
 
-    for frame in reader {
-        let frame = frame?;
+    match method {
+        Method::Geometry => Ok(system.group_get_center(group)?),
+        Method::Mass => Ok(system.group_get_com(group)?),
+        Method::Median => group_get_median(system, group),
+        Method::PlaneFit => group_get_midplane(system, group),
+        Method::ResidueMass => group_get_residue_com(system, group),
+        Method::BoundingBox => group_get_bbox_center(system, group),
+        Method::BoxCenter => Ok(system.get_box_center()?),
+    }
+}
 
-        for (group, dims) in operations.iter() {
-            if args.com {
-                frame.atoms_center_mass(group, *dims)?
-            } else {
-                frame.atoms_center(group, *dims)?
-            }
+/// Wrap the position into the simulation box in the specified dimensions.
+fn wrap_position(position: &mut Vector3D, simbox: &SimBox, dims: Dimension) {
+    let mut wrapped = position.clone();
+    wrapped.wrap(simbox);
+
+    if dims.is_x() {
+        position.x = wrapped.x;
+    }
+    if dims.is_y() {
+        position.y = wrapped.y;
+    }
+    if dims.is_z() {
+        position.z = wrapped.z;
+    }
+}
+
+/// Wrap all atoms of the system into the simulation box in the specified dimensions.
+fn wrap_atoms(
+    system: &mut System,
+    wrap: Dimension,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if wrap == Dimension::XYZ {
+        system.atoms_wrap()?;
+        return Ok(());
+    }
+
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?.clone();
+    for atom in system.atoms_iter_mut() {
+        if let Some(position) = atom.get_position_mut() {
+            wrap_position(position, &simbox, wrap);
         }
+    }
+
+    Ok(())
+}
 
-        if args.whole {
-            frame.make_molecules_whole()?;
+/// Translate all atoms of the system by `shift` and wrap them into the simulation box in the `wrap` dimensions.
+fn translate_atoms(
+    system: &mut System,
+    shift: &Vector3D,
+    wrap: Dimension,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // groan wraps the atoms in all dimensions
+    if wrap == Dimension::XYZ {
+        system.atoms_translate(shift)?;
+        return Ok(());
+    }
+
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?.clone();
+    for atom in system.atoms_iter_mut() {
+        atom.translate_nopbc(shift)?;
+        if let Some(position) = atom.get_position_mut() {
+            wrap_position(position, &simbox, wrap);
         }
+    }
+
+    Ok(())
+}
 
-        frame.traj_write_frame()?;
+/// Place the reference group into the center of the simulation box in the specified dimensions.
+/// After the shift, atoms are wrapped into the simulation box in the `wrap` dimensions.
+fn center_group(
+    system: &mut System,
+    group: &str,
+    dims: Dimension,
+    method: Method,
+    weighted: &[(String, f32)],
+    wrap: Dimension,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match method {
+        Method::Geometry if !is_weighted(group, weighted) && wrap == Dimension::XYZ => {
+            system.atoms_center(group, dims)?
+        }
+        Method::Mass if !is_weighted(group, weighted) && wrap == Dimension::XYZ => {
+            system.atoms_center_mass(group, dims)?
+        }
+        _ => {
+            let point = reference_point(system, group, method, weighted)?;
+            let mut shift = system.get_box_center()? - point;
+            shift.filter(dims);
+            translate_atoms(system, &shift, wrap)?;
+        }
     }
 
     Ok(())
 }
 
-/// Center all the provided trajectories.
-fn center_trajectories(
+/// Translate the system so that the reference group is placed at the target position in the specified dimensions.
+fn translate_group_to(
     system: &mut System,
-    args: &Args,
-    operations: Vec<(String, Dimension)>,
+    group: &str,
+    dims: Dimension,
+    target: &Vector3D,
+    method: Method,
+    weighted: &[(String, f32)],
+    wrap: Dimension,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    simbox_error_to_warning(check_simulation_box(system), args.silent);
+    let current = reference_point(system, group, method, weighted)?;
 
-    if args.trajectories.len() == 1 {
-        match FileType::from_name(&args.trajectories[0]) {
-            FileType::XTC => {
-                let reader = read_range_step(system.xtc_iter(&args.trajectories[0])?, args)?;
-                center_trajectory(reader, args, operations)
-            }
-            FileType::TRR => {
-                let reader = read_range_step(system.trr_iter(&args.trajectories[0])?, args)?;
-                center_trajectory(reader, args, operations)
+    let mut shift = target - &current;
+    shift.filter(dims);
+    translate_atoms(system, &shift, wrap)?;
+
+    Ok(())
+}
+
+/// Get the absolute position corresponding to the target.
+/// Unspecified coordinates of the target are set to the center of the simulation box.
+fn resolve_target(
+    system: &System,
+    target: &Target,
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let center = system.get_box_center()?;
+
+    Ok(Vector3D::new(
+        target.0[0].unwrap_or(center.x),
+        target.0[1].unwrap_or(center.y),
+        target.0[2].unwrap_or(center.z),
+    ))
+}
+
+/// Place the reference groups of all centering operations into the center of the simulation box
+/// or at the absolute target position, if it is provided.
+/// The placement is repeated `iterations` times.
+fn place_groups(
+    system: &mut System,
+    operations: &[Operation],
+    method: Method,
+    weighted: &[(String, f32)],
+    target: Option<&Target>,
+    iterations: usize,
+    wrap: Dimension,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for _ in 0..iterations {
+        match target {
+            None => {
+                for (group, dims, weighting) in operations.iter() {
+                    let method = method.with_weighting(*weighting);
+                    center_group(system, group, *dims, method, weighted, wrap)?;
+                }
             }
-            FileType::GRO => {
-                let reader = read_step(system.gro_iter(&args.trajectories[0])?, args)?;
-                center_trajectory(reader, args, operations)
+            Some(target) => {
+                let position = resolve_target(system, target)?;
+                for (group, dims, weighting) in operations.iter() {
+                    let method = method.with_weighting(*weighting);
+                    translate_group_to(system, group, *dims, &position, method, weighted, wrap)?;
+                }
             }
-            _ => panic!("\ngcenter: Fatal Error. Input file has unsupported file extension but this should have been handled before."),
         }
+    }
+
+    Ok(())
+}
+
+/// Calculate the position of the main reference group in the structure specified using `--align-to`.
+/// Returns the target at which the reference group of the centered system should be placed.
+pub fn align_target(
+    args: &Args,
+) -> Result<Option<Target>, Box<dyn std::error::Error + Send + Sync>> {
+    let structure = match &args.align_to {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let mut system = crate::read_system(structure)?;
+
+    check_simulation_box(&system)?;
+
+    let method = Method::from_args(args);
+    if method == Method::Mass && crate::file_type(structure) != FileType::TPR {
+        if args.require_masses {
+            return Err(Box::new(RunError::MassesRequired(structure.to_owned())));
+        }
+
+        let elements = match &args.elements {
+            Some(file) => Some(groan_rs::structures::element::Elements::from_file(file)?),
+            None => None,
+        };
+        crate::guess_elements(&mut system, elements)?;
+    }
+
+    crate::reference::create_reference(
+        &mut system,
+        crate::MAIN_REFERENCE,
+        &args.reference,
+        args.invert_selection,
+        false,
+    )?;
+
+    let point = reference_point(&system, crate::MAIN_REFERENCE, method, &[])?;
+
+    debug!(
+        "{} reference position in '{}' is {}\n",
+        "note:".purple().bold(),
+        structure.yellow(),
+        format!("{:.3},{:.3},{:.3}", point.x, point.y, point.z).bright_blue()
+    );
+
+    Ok(Some(Target([Some(point.x), Some(point.y), Some(point.z)])))
+}
+
+/// Print a warning if the absolute target lies outside of the simulation box of the system.
+fn check_target(system: &System, target: &Target) {
+    let simbox = match system.get_box() {
+        Some(x) => x,
+        None => return,
+    };
+
+    let outside = target
+        .0
+        .iter()
+        .zip([simbox.x, simbox.y, simbox.z])
+        .any(|(coordinate, size)| coordinate.is_some_and(|c| c < 0.0 || c > size));
+
+    if outside {
+        warn!(
+            "{} target position '{}' lies outside of the simulation box; atoms will be wrapped into the box.\n",
+            "warning:".yellow().bold(),
+            target.to_string().yellow()
+        );
+    }
+}
+
+/// Returns true if the output trr file is written by the writer of `gcenter` instead of the writer attached to the system.
+/// This is required for trr files without velocities and forces and for streamed trr files, the frames of which are flushed.
+fn own_trr_output(args: &Args) -> bool {
+    (args.positions_only || args.stream)
+        && !args.no_output
+        && crate::file_type(&args.output) == FileType::TRR
+}
+
+/// Create the writer of trr frames, if the output trr file is written by the writer of `gcenter`.
+/// Other output trajectories are written using the writer attached to the system.
+/// If the output is split into chunks, the writer is created when the first chunk is opened.
+fn trr_writer(args: &Args) -> Result<Option<crate::trr::TrrWriter>, RunError> {
+    if own_trr_output(args) && args.max_output_frames.is_none() {
+        Ok(Some(crate::trr::TrrWriter::new(
+            args.output_path(),
+            args.positions_only,
+        )?))
     } else {
-        match FileType::from_name(&args.trajectories[0]) {
-            FileType::XTC => {
-                let reader = read_range_step(system.xtc_cat_iter(&args.trajectories)?, args)?;
-                center_trajectory(reader, args, operations)
-            },
-            FileType::TRR => {
-                let reader = read_range_step(system.trr_cat_iter(&args.trajectories)?, args)?;
-                center_trajectory(reader, args, operations)
-            }
-            _ => panic!("\ngcenter: Fatal Error. Input file has unsupported file extension but this should have been handled before."),
+        Ok(None)
+    }
+}
+
+/// Get the precision that should be used for the output trajectory.
+/// Precision can only be applied to xtc files; for other formats, a warning is printed and the option is ignored.
+fn output_precision(args: &Args) -> Option<u64> {
+    let precision = args.output_precision?;
+
+    if args.no_output {
+        return None;
+    }
+
+    if crate::file_type(&args.output) == FileType::XTC {
+        Some(precision)
+    } else {
+        warn!(
+            "{} output precision is only supported for xtc files; option '{}' will be ignored.\n",
+            "warning:".yellow().bold(),
+            "--output-precision".yellow()
+        );
+
+        None
+    }
+}
+
+/// Shifts the simulation times of the frames written into the output trajectory.
+struct TimeShift {
+    /// Time added to the time of each frame (or the time of the first frame if `reset` is used).
+    offset: f32,
+    /// Replace the times of the frames with their index multiplied by the time step.
+    reset: bool,
+    /// Original time of the first written frame.
+    first: Option<f32>,
+    /// Difference between the original times of the first two written frames.
+    step: Option<f32>,
+}
+
+impl TimeShift {
+    /// Get the time shift requested by the user.
+    /// Returns `None` if no shift has been requested or if the output file does not store times.
+    fn from_args(args: &Args) -> Option<Self> {
+        if (args.time_offset.is_none() && !args.time_reset) || args.no_output {
+            return None;
+        }
+
+        if !matches!(
+            crate::file_type(&args.output),
+            FileType::XTC | FileType::TRR
+        ) {
+            warn!(
+                "{} shifting output times is only supported for xtc and trr files; options '{}' and '{}' will be ignored.\n",
+                "warning:".yellow().bold(),
+                "--time-offset".yellow(),
+                "--time-reset".yellow()
+            );
+            return None;
+        }
+
+        Some(TimeShift {
+            offset: args.time_offset.unwrap_or(0.0),
+            reset: args.time_reset,
+            first: None,
+            step: None,
+        })
+    }
+
+    /// Get the new time of the `index`th written frame with the original `time`.
+    fn shift(&mut self, time: f32, index: usize) -> f32 {
+        if !self.reset {
+            return time + self.offset;
         }
+
+        let first = *self.first.get_or_insert(time);
+        if index == 1 {
+            self.step = Some(time - first);
+        }
+
+        self.offset + index as f32 * self.step.unwrap_or(0.0)
     }
 }
 
-/// Center the structure or trajectory file.
-pub fn center(
-    system: &mut System,
-    args: &Args,
-    operations: Vec<(String, Dimension)>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // determine type of the output file
-    let output_type = FileType::from_name(&args.output);
+/// Assign contiguous atom and residue numbers to the provided atoms.
+/// A new residue is started whenever the residue number changes between two consecutive atoms.
+fn renumber_atoms<'a>(atoms: impl Iterator<Item = &'a mut Atom>) {
+    let mut current_residue = None;
+    let mut residue = 0;
+
+    for (i, atom) in atoms.enumerate() {
+        atom.set_atom_number(i + 1);
+
+        if current_residue != Some(atom.get_residue_number()) {
+            current_residue = Some(atom.get_residue_number());
+            residue += 1;
+        }
+
+        atom.set_residue_number(residue);
+    }
+}
+
+/// Assign contiguous atom and residue numbers to the atoms written into the output.
+/// Numbering is only written into gro, pdb, pqr, and g96 files; for other formats, a warning is printed and the system is not modified.
+fn renumber(system: &mut System, output_group: Option<&str>, output: &str) {
+    match crate::file_type(output) {
+        FileType::GRO | FileType::PDB | FileType::PQR => (),
+        _ if crate::g96::is_g96(output) => (),
+        _ => {
+            warn!(
+                "{} output file does not contain atom numbering; option '{}' will be ignored.\n",
+                "warning:".yellow().bold(),
+                "--renumber".yellow()
+            );
+            return;
+        }
+    }
+
+    match output_group {
+        Some(group) => match system.group_iter_mut(group) {
+            Ok(atoms) => renumber_atoms(atoms),
+            Err(_) => panic!("\ngcenter: Fatal Error. Output group does not exist."),
+        },
+        None => renumber_atoms(system.atoms_iter_mut()),
+    }
+}
+
+/// Print a warning about data of the input file that cannot be written into the output file.
+fn lost_data_warning(input: &str, data: &str, output_type: FileType) {
+    warn!(
+        "{} input {} contains {} which cannot be written into the {} output file; {} will be dropped.\n",
+        "warning:".yellow().bold(),
+        input,
+        data.yellow(),
+        output_type.to_string().yellow(),
+        data
+    );
+}
 
+/// Print a warning for each type of data carried by the input that the output file format cannot represent.
+/// Also warns if charges are missing in the input but have to be written into the output.
+fn check_lost_data(system: &System, args: &Args, output_type: FileType) {
     if args.trajectories.is_empty() {
-        // trajectory file not provided, center the structure file
-        center_structure_file(
-            system,
-            &args.output,
-            output_type,
-            operations,
-            args.com,
-            args.whole,
-        )?;
-    } else {
-        // attach trajectory writer
-        system.traj_writer_auto_init(&args.output)?;
-        center_trajectories(system, args, operations)?;
+        if !args.no_velocities
+            && !args.positions_only
+            && system.has_velocities()
+            && matches!(output_type, FileType::PDB | FileType::PQR)
+        {
+            lost_data_warning("structure", "velocities", output_type);
+        }
+    } else if let Some((velocities, forces)) =
+        header::trajectory_velocities_forces(&args.trajectory_paths()[0])
+    {
+        if !args.no_velocities && !args.positions_only && velocities && output_type == FileType::XTC
+        {
+            lost_data_warning("trajectory", "velocities", output_type);
+        }
 
-        if !args.silent {
-            println!("\n");
+        if forces && output_type != FileType::TRR && !args.positions_only {
+            lost_data_warning("trajectory", "forces", output_type);
         }
     }
 
+    if output_type == FileType::PQR && system.atoms_iter().any(|atom| atom.get_charge().is_none()) {
+        warn!(
+            "{} input structure does not contain {}; missing charges will be written as zero.\n",
+            "warning:".yellow().bold(),
+            "charges".yellow()
+        );
+    }
+}
+
+/// Check that the simulation is valid (defined, non-zero and orthogonal).
+fn check_simulation_box(system: &System) -> Result<(), RunError> {
+    match system.get_box() {
+        None => return Err(RunError::BoxNotDefined),
+        Some(x) => {
+            if !x.is_orthogonal() {
+                return Err(RunError::BoxNotOrthogonal);
+            }
+
+            if x.x <= 0.0 || x.y <= 0.0 || x.z <= 0.0 {
+                return Err(RunError::BoxNotValid);
+            }
+        }
+    };
+
     Ok(())
 }
+
+/// Ignore error returned by `check_simulation_box` and log a warning instead.
+/// No warning is logged if `quiet` is `true`.
+/// Used when centering a trajectory.
+fn simbox_error_to_warning(error: Result<(), RunError>, quiet: bool) {
+    match error {
+        Ok(_) => (),
+        Err(RunError::BoxNotDefined | RunError::BoxNotValid | RunError::BoxNotOrthogonal) if quiet => (),
+        Err(RunError::BoxNotDefined) => warn!("{} input structure file has an undefined simulation box.\n", "warning:".yellow().bold()),
+        Err(RunError::BoxNotValid) => warn!("{} input structure file has an invalid simulation box (some dimensions are not positive).\n", "warning:".yellow().bold()),
+        Err(RunError::BoxNotOrthogonal) => warn!("{} input structure file has a non-orthogonal simulation box.\n", "warning:".yellow().bold()),
+        Err(_) => panic!("\ngcenter: Fatal Error. Unexpected error type returned when checking the simulation box."),
+    }
+}
+
+/// Check whether the position lies inside the simulation box (including its boundaries).
+fn is_inside_box(position: &Vector3D, simbox: &SimBox) -> bool {
+    [simbox.x, simbox.y, simbox.z]
+        .iter()
+        .enumerate()
+        .all(|(dim, &size)| position[dim] >= 0.0 && position[dim] <= size)
+}
+
+/// Count the atoms lying outside the simulation box. Atoms without positions are ignored.
+/// If `fix` is `true`, the atoms lying outside the box are wrapped into it.
+fn atoms_outside_box(system: &mut System, fix: bool) -> Result<usize, RunError> {
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?.clone();
+
+    let mut n_outside = 0;
+    for atom in system.atoms_iter_mut() {
+        if let Some(position) = atom.get_position_mut() {
+            if !is_inside_box(position, &simbox) {
+                n_outside += 1;
+                if fix {
+                    position.wrap(&simbox);
+                }
+            }
+        }
+    }
+
+    Ok(n_outside)
+}
+
+/// Check the atoms lying outside the simulation box after all operations have been applied, if requested.
+/// If the atoms have been wrapped into the box by the last operation, any atom outside the box is an error.
+/// Returns the number of atoms lying outside the box (before fixing them).
+fn check_outside_box(system: &mut System, args: &Args) -> Result<usize, RunError> {
+    if !args.clamp_outside {
+        return Ok(0);
+    }
+
+    // atoms are only guaranteed to lie inside the box if they have been wrapped in all dimensions
+    if args.transform_steps().last() == Some(&TransformStep::Wrap)
+        && args.wrap_dims == Dimension::XYZ
+    {
+        let n_outside = atoms_outside_box(system, false)?;
+        if n_outside > 0 {
+            return Err(RunError::AtomsOutsideBox(
+                n_outside,
+                format!("{:.3}", system.get_simulation_time()),
+            ));
+        }
+
+        return Ok(0);
+    }
+
+    atoms_outside_box(system, args.fix_outside)
+}
+
+/// Report the atoms found outside the simulation box by `check_outside_box`.
+/// `frames` is the number of frames containing atoms outside the box and `max` is the highest number of such atoms in a single frame.
+fn report_outside_box(args: &Args, frames: Option<usize>, max: usize) {
+    if !args.clamp_outside {
+        return;
+    }
+
+    let location = match frames {
+        Some(n) => format!(
+            " in {} frame(s) (at most {} atom(s) in a single frame)",
+            n.to_string().yellow(),
+            max.to_string().yellow()
+        ),
+        None => String::new(),
+    };
+
+    let count = match frames {
+        Some(_) => String::from("atoms"),
+        None => format!("{} atom(s)", max.to_string().yellow()),
+    };
+
+    if max == 0 {
+        debug!(
+            "{} all atoms lie inside the simulation box\n",
+            "note:".purple().bold()
+        );
+    } else if args.fix_outside {
+        debug!(
+            "{} {} lying outside the simulation box have been wrapped into it{}\n",
+            "note:".purple().bold(),
+            count,
+            location
+        );
+    } else {
+        warn!(
+            "{} {} lie outside the simulation box after centering{}; use '{}' to wrap them into the box.\n",
+            "warning:".yellow().bold(),
+            count,
+            location,
+            "--fix-outside".yellow()
+        );
+    }
+}
+
+/// Get the position of the atom with the specified index.
+fn atom_position(system: &System, index: usize) -> Result<Vector3D, AtomError> {
+    system
+        .get_atom(index)?
+        .get_position()
+        .cloned()
+        .ok_or(AtomError::InvalidPosition(PositionError::NoPosition(index)))
+}
+
+/// Make molecules whole.
+/// If `threshold` is provided, the bonds between atoms are followed and each atom is placed at the periodic image
+/// closest to the atom it is bonded to. Bonds longer than `threshold` are not followed, so a molecule containing
+/// such bonds is made whole separately for each of its fragments.
+fn make_molecules_whole(system: &mut System, threshold: Option<f32>) -> Result<(), AtomError> {
+    let threshold = match threshold {
+        Some(x) => x,
+        None => return system.make_molecules_whole(),
+    };
+
+    let simbox = system
+        .get_box()
+        .ok_or(AtomError::InvalidSimBox(SimBoxError::DoesNotExist))?
+        .clone();
+    let mut placed = vec![false; system.get_n_atoms()];
+    let mut queue = VecDeque::new();
+
+    for start in 0..system.get_n_atoms() {
+        if placed[start] {
+            continue;
+        }
+
+        // the first atom of each molecule (or fragment) is wrapped into the simulation box
+        system.get_atom_mut(start)?.wrap(&simbox)?;
+        placed[start] = true;
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            let position = atom_position(system, current)?;
+            let bonded: Vec<usize> = system.get_atom(current)?.get_bonded().iter().collect();
+
+            for neighbor in bonded {
+                if placed[neighbor] {
+                    continue;
+                }
+
+                let vector = position.vector_to(&atom_position(system, neighbor)?, &simbox);
+                if vector.len() > threshold {
+                    continue;
+                }
+
+                system
+                    .get_atom_mut(neighbor)?
+                    .set_position(&position + vector);
+                placed[neighbor] = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Center the reference group and write an output gro or pdb file.
+fn center_structure_file(
+    system: &mut System,
+    args: &Args,
+    output_type: FileType,
+    output_group: Option<&str>,
+    operations: Vec<Operation>,
+) -> Result<CenterStats, Box<dyn std::error::Error + Send + Sync>> {
+    check_simulation_box(system)?;
+
+    if let Some(report) = &args.report_box {
+        write_box_report(report, &[box_entry(system)?])?;
+
+        debug!(
+            "{} dimensions of the simulation box written into '{}'\n",
+            "note:".purple().bold(),
+            report.yellow()
+        );
+    }
+
+    let method = Method::from_args(args);
+    let weighted = weighted_groups(args);
+    let centroid = drift_point(system, &operations, method, &weighted)?;
+    for step in args.transform_steps() {
+        match step {
+            TransformStep::Center => place_groups(
+                system,
+                &operations,
+                method,
+                &weighted,
+                args.abs_target.as_ref(),
+                args.center_iterations,
+                args.wrap_dims,
+            )?,
+            TransformStep::Whole => make_molecules_whole(system, args.whole_threshold)?,
+            TransformStep::Wrap => wrap_atoms(system, args.wrap_dims)?,
+        }
+    }
+
+    if let Some(dimension) = args.mirror {
+        mirror(system, dimension);
+    }
+
+    let n_outside = check_outside_box(system, args)?;
+    report_outside_box(args, None, n_outside);
+
+    if let Some(template) = args.output_template_box {
+        apply_template_box(system, template);
+    }
+
+    let output = args.output_path();
+    let velocities = !args.no_velocities && !args.positions_only && system.has_velocities();
+    let title = output_title(args);
+
+    // g96 files are not supported by groan and are written separately
+    if crate::g96::is_g96(&args.output) {
+        crate::g96::write_g96(system, output_group, output, velocities, title.as_deref())?;
+    } else {
+        write_structure(system, output, output_type, output_group, velocities)?;
+
+        if let Some(title) = title {
+            write_title(output, output_type, &title)?;
+        }
+    }
+
+    if args.pdb_ter {
+        match output_type {
+            FileType::PDB => insert_ter_records(output)?,
+            _ => warn!(
+                "{} output file is not a pdb file; option '{}' will be ignored.\n",
+                "warning:".yellow().bold(),
+                "--pdb-ter".yellow()
+            ),
+        }
+    }
+
+    Ok(CenterStats {
+        frames: 1,
+        skipped: 0,
+        duplicates: 0,
+        first_centroid: [centroid.x, centroid.y, centroid.z],
+    })
+}
+
+/// Write the system (or the output group) into a gro, pdb, or pqr file.
+fn write_structure(
+    system: &System,
+    output: &str,
+    output_type: FileType,
+    output_group: Option<&str>,
+    velocities: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match (output_type, output_group) {
+        (FileType::GRO, None) => system.write_gro(output, velocities)?,
+        (FileType::GRO, Some(group)) => system.group_write_gro(group, output, velocities)?,
+        (FileType::PDB, None) => system.write_pdb(output, system.has_bonds())?,
+        (FileType::PDB, Some(group)) => {
+            system.group_write_pdb(group, output, system.has_bonds())?
+        }
+        (FileType::PQR, None) => system.write_pqr(output, None)?,
+        (FileType::PQR, Some(group)) => system.group_write_pqr(group, output, None)?,
+        _ => panic!("\ngcenter: Fatal Error. Output file has unsupported file extension but this should have been handled before."),
+    }
+
+    Ok(())
+}
+
+/// Get the title of the output structure file.
+/// If no title has been provided but verbose output has been requested, the title is generated automatically.
+fn output_title(args: &Args) -> Option<String> {
+    if let Some(title) = &args.title {
+        return Some(title.to_owned());
+    }
+
+    if !args.verbose {
+        return None;
+    }
+
+    let transform = args
+        .transform_steps()
+        .iter()
+        .map(|step| step.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    Some(format!(
+        "Centered on {} ({}) by gcenter {}; transform: {}",
+        args.reference,
+        crate::construct_dimensions(args),
+        env!("CARGO_PKG_VERSION"),
+        transform
+    ))
+}
+
+/// Replace the title of the output structure file.
+fn write_title(filename: &str, output_type: FileType, title: &str) -> Result<(), RunError> {
+    let content = std::fs::read_to_string(filename)
+        .map_err(|_| RunError::TitleNotWritten(filename.to_owned()))?;
+
+    std::fs::write(filename, replace_title(&content, output_type, title))
+        .map_err(|_| RunError::TitleNotWritten(filename.to_owned()))
+}
+
+/// Replace the first line of a gro file or the TITLE record of a pdb or pqr file.
+/// If the pdb or pqr file contains no TITLE record, it is added at the start of the file.
+fn replace_title(content: &str, output_type: FileType, title: &str) -> String {
+    let mut output = String::with_capacity(content.len() + title.len());
+    let mut replaced = false;
+
+    if output_type != FileType::GRO && !content.lines().any(|line| line.starts_with("TITLE")) {
+        output.push_str(&format!("TITLE     {}\n", title));
+        replaced = true;
+    }
+
+    for line in content.lines() {
+        if !replaced && output_type == FileType::GRO {
+            output.push_str(title);
+            replaced = true;
+        } else if !replaced && line.starts_with("TITLE") {
+            output.push_str(&format!("TITLE     {}", title));
+            replaced = true;
+        } else {
+            output.push_str(line);
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Insert a TER record after the last atom of every chain of the pdb file.
+/// The final TER record written by groan is kept, so no TER record is inserted after the last atom of the file.
+fn insert_ter_records(filename: &str) -> Result<(), RunError> {
+    let content = std::fs::read_to_string(filename)
+        .map_err(|_| RunError::TerNotWritten(filename.to_owned()))?;
+
+    std::fs::write(filename, add_chain_ters(&content))
+        .map_err(|_| RunError::TerNotWritten(filename.to_owned()))
+}
+
+/// Add TER lines into the content of a pdb file whenever the chain identifier changes between two consecutive atoms.
+/// Atoms without a chain identifier do not start a new chain.
+fn add_chain_ters(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut previous_chain = None;
+
+    for line in content.lines() {
+        if line.starts_with("ATOM") || line.starts_with("HETATM") {
+            let chain = line.chars().nth(21).filter(|c| !c.is_whitespace());
+
+            if previous_chain.is_some() && chain != previous_chain {
+                output.push_str("TER\n");
+            }
+
+            previous_chain = chain;
+        } else if line.starts_with("TER") {
+            previous_chain = None;
+        }
+
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Select range to read (with steps).
+fn read_range_step<'a, Read>(
+    reader: TrajReader<'a, Read>,
+    start_time: Option<f32>,
+    args: &Args,
+) -> Result<TrajRangeStepReader<'a, Read>, ReadTrajError>
+where
+    Read: TrajRead<'a> + TrajStepRead<'a> + TrajRangeRead<'a>,
+    <Read as TrajRead<'a>>::FrameData: FrameDataTime,
+{
+    let reader = match (start_time, args.end_time) {
+        (None, None) => reader.with_range(0.0, f32::MAX),
+        (Some(start), None) => reader.with_range(start, f32::MAX),
+        (None, Some(end)) => reader.with_range(0.0, end),
+        (Some(start), Some(end)) => reader.with_range(start, end),
+    }?;
+
+    reader.with_step(args.step)
+}
+
+/// Get the time of the first frame to read.
+/// If `last` is provided, the time is calculated from the time of the last frame of the (last) trajectory.
+fn start_time(args: &Args) -> Result<Option<f32>, RunError> {
+    let last = match args.last {
+        Some(x) => x,
+        None => return Ok(args.start_time),
+    };
+
+    let (trajectory, path) = match (args.trajectories.last(), args.trajectory_paths().last()) {
+        (Some(x), Some(y)) => (x, y),
+        _ => panic!("\ngcenter: Fatal Error. No trajectory provided but `last` was specified."),
+    };
+
+    let final_time = header::trajectory_last_time(path)
+        .ok_or_else(|| RunError::LastTimeNotRead(trajectory.to_owned()))?;
+
+    let start = final_time - last;
+    debug!(
+        "{} last frame found at {} ps, reading from {} ps\n",
+        "note:".purple().bold(),
+        final_time,
+        start
+    );
+
+    Ok(Some(start))
+}
+
+/// Specify step of the trajectory reading.
+fn read_step<'a, Read>(
+    reader: TrajReader<'a, Read>,
+    args: &Args,
+) -> Result<TrajStepReader<'a, Read>, ReadTrajError>
+where
+    Read: TrajRead<'a> + TrajStepRead<'a>,
+{
+    reader.with_step(args.step)
+}
+
+/// Read the simulation times of the frames of a gro trajectory from a file.
+/// The file lists one time (in ps) per line; empty lines and lines starting with '#' are ignored.
+fn read_frame_times(file: &str) -> Result<Vec<f32>, RunError> {
+    let content =
+        std::fs::read_to_string(file).map_err(|_| RunError::FrameTimesNotRead(file.to_owned()))?;
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            line.parse::<f32>()
+                .map_err(|_| RunError::FrameTimesInvalid(file.to_owned(), i + 1))
+        })
+        .collect()
+}
+
+/// Read the times of the frames requested using `--frame-times` and check that a time is listed for every frame.
+/// Returns `None` if no frame times have been requested.
+fn frame_times(args: &Args) -> Result<Option<Vec<f32>>, RunError> {
+    let file = match &args.frame_times {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let times = read_frame_times(file)?;
+
+    // unreadable trajectories are reported when the trajectory is actually read
+    if let Some(n_frames) = header::gro_n_frames(&args.trajectory_paths()[0]) {
+        if times.len() < n_frames {
+            return Err(RunError::FrameTimesTooShort(
+                file.to_owned(),
+                times.len(),
+                args.trajectories[0].to_owned(),
+                n_frames,
+            ));
+        }
+    }
+
+    Ok(Some(times))
+}
+
+/// Tolerance used when comparing the spacing of frames with the spacing requested using `every` (in ps).
+/// Accounts for the limited precision of simulation times stored in trajectory files.
+const TIME_SPACING_TOLERANCE: f32 = 1e-3;
+
+/// Select only frames separated by at least `every` ps from the previously selected frame.
+/// All frames are selected if `every` is `None`.
+fn spaced_frames<'a, E>(
+    frames: impl Iterator<Item = Result<&'a mut System, E>>,
+    every: Option<f32>,
+) -> impl Iterator<Item = Result<&'a mut System, E>> {
+    let mut last_time: Option<f32> = None;
+
+    frames.filter(move |frame| {
+        let (Some(every), Ok(frame)) = (every, frame) else {
+            return true;
+        };
+
+        let time = frame.get_simulation_time();
+        match last_time {
+            Some(last) if time - last + TIME_SPACING_TOLERANCE < every => false,
+            _ => {
+                last_time = Some(time);
+                true
+            }
+        }
+    })
+}
+
+/// Select only frames with a simulation step in the range from `begin` to `end` (both inclusive).
+/// All frames are selected if neither of the limits is provided.
+fn step_range_frames<'a, E>(
+    frames: impl Iterator<Item = Result<&'a mut System, E>>,
+    begin: Option<u64>,
+    end: Option<u64>,
+) -> impl Iterator<Item = Result<&'a mut System, E>> {
+    frames.filter(move |frame| {
+        let Ok(frame) = frame else {
+            return true;
+        };
+
+        let step = frame.get_simulation_step();
+        begin.is_none_or(|begin| step >= begin) && end.is_none_or(|end| step <= end)
+    })
+}
+
+/// Skip the first `n` frames. All frames are selected if `n` is not provided.
+/// Frames that could not be read are not counted and are always passed on so that the error is reported.
+fn discarded_frames<'a, E>(
+    frames: impl Iterator<Item = Result<&'a mut System, E>>,
+    n: Option<usize>,
+) -> impl Iterator<Item = Result<&'a mut System, E>> {
+    let mut remaining = n.unwrap_or(0);
+
+    frames.filter(move |frame| {
+        if remaining == 0 || frame.is_err() {
+            return true;
+        }
+
+        remaining -= 1;
+        false
+    })
+}
+
+/// Skip frames with the same simulation step and time as the previously selected frame.
+/// The number of skipped frames is counted in `removed`. All frames are selected if `dedup` is `false`.
+fn unique_frames<'a, 'b, E: 'b>(
+    frames: impl Iterator<Item = Result<&'a mut System, E>> + 'b,
+    dedup: bool,
+    removed: &'b Cell<usize>,
+) -> impl Iterator<Item = Result<&'a mut System, E>> + 'b
+where
+    'a: 'b,
+{
+    let mut last: Option<(u64, f32)> = None;
+
+    frames.filter(move |frame| {
+        let (true, Ok(frame)) = (dedup, frame) else {
+            return true;
+        };
+
+        let step = frame.get_simulation_step();
+        let time = frame.get_simulation_time();
+        match last {
+            Some((last_step, last_time))
+                if step == last_step && (time - last_time).abs() < TIME_SPACING_TOLERANCE =>
+            {
+                removed.set(removed.get() + 1);
+                false
+            }
+            _ => {
+                last = Some((step, time));
+                true
+            }
+        }
+    })
+}
+
+/// Select only frames with an even (`parity` is 0) or odd (`parity` is 1) index.
+/// The index is counted from 0 over the frames passing the previous selection. All frames are selected if `parity` is `None`.
+/// Errors are always passed through.
+fn parity_frames<'a, E>(
+    frames: impl Iterator<Item = Result<&'a mut System, E>>,
+    parity: Option<usize>,
+) -> impl Iterator<Item = Result<&'a mut System, E>> {
+    frames
+        .enumerate()
+        .filter(move |(index, frame)| {
+            frame.is_err() || parity.is_none_or(|parity| index % 2 == parity)
+        })
+        .map(|(_, frame)| frame)
+}
+
+/// Trajectory reader assigning times to the frames of a trajectory without time information.
+/// Frames are selected according to the assigned times and the `begin`, `end`, and `step` options.
+struct TimedReader<'a, R: TrajMasterRead<'a>> {
+    reader: R,
+    times: Vec<f32>,
+    start: f32,
+    end: f32,
+    step: usize,
+    /// Index of the next frame read from the trajectory.
+    index: usize,
+    /// Number of frames in the time range to skip before the next frame is selected.
+    skip: usize,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a, R: TrajMasterRead<'a>> TimedReader<'a, R> {
+    fn new(reader: R, times: &[f32], start_time: Option<f32>, args: &Args) -> Self {
+        TimedReader {
+            reader,
+            times: times.to_vec(),
+            start: start_time.unwrap_or(0.0),
+            end: args.end_time.unwrap_or(f32::MAX),
+            step: args.step,
+            index: 0,
+            skip: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, R: TrajMasterRead<'a>> Iterator for TimedReader<'a, R> {
+    type Item = Result<&'a mut System, ReadTrajError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let frame = match self.reader.next()? {
+                Ok(x) => x,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let time = match self.times.get(self.index) {
+                Some(&x) => x,
+                None => panic!("\ngcenter: Fatal Error. Frame has no assigned time but this should have been handled before."),
+            };
+            self.index += 1;
+
+            if time < self.start || time > self.end {
+                continue;
+            }
+
+            if self.skip > 0 {
+                self.skip -= 1;
+                continue;
+            }
+            self.skip = self.step - 1;
+
+            frame.set_simulation_time(time);
+            return Some(Ok(frame));
+        }
+    }
+}
+
+impl<'a, R: TrajMasterRead<'a>> ProgressPrintable for TimedReader<'a, R> {
+    fn get_progress_printer_mut(&mut self) -> Option<&mut ProgressPrinter> {
+        self.reader.get_progress_printer_mut()
+    }
+
+    fn set_progress_printer(&mut self, printer: ProgressPrinter) {
+        self.reader.set_progress_printer(printer);
+    }
+}
+
+impl<'a, R: TrajMasterRead<'a>> TrajMasterRead<'a> for TimedReader<'a, R> {}
+
+/// Replace the simulation box of a trajectory frame with the box provided using `--box`, if `--keep-box` is requested.
+fn keep_box(frame: &mut System, args: &Args) {
+    if let (true, Some(simbox)) = (args.keep_box, &args.simbox) {
+        frame.set_box(SimBox::from(simbox.0));
+    }
+}
+
+/// Maximal deviation of a scaling factor from 1 that does not trigger a warning when rescaling the box.
+const RESCALE_WARNING_THRESHOLD: f32 = 0.1;
+
+/// Scale the positions of all atoms and the simulation box of the frame so that the box matches the `target` box.
+/// Returns the scaling factors applied in the individual dimensions.
+fn rescale_box(frame: &mut System, target: &BoxSize) -> Result<[f32; 3], RunError> {
+    check_simulation_box(frame)?;
+
+    let factors = match frame.get_box() {
+        Some(simbox) => [
+            target.0[0] / simbox.x,
+            target.0[1] / simbox.y,
+            target.0[2] / simbox.z,
+        ],
+        None => panic!("\ngcenter: Fatal Error. Simulation box is not defined but this should have been handled before."),
+    };
+
+    for atom in frame.atoms_iter_mut() {
+        if let Some(position) = atom.get_position() {
+            let scaled = Vector3D::new(
+                position.x * factors[0],
+                position.y * factors[1],
+                position.z * factors[2],
+            );
+            atom.set_position(scaled);
+        }
+    }
+
+    frame.set_box(SimBox::from(target.0));
+
+    Ok(factors)
+}
+
+/// Reflect positions of all atoms across the planes passing through the center of the simulation box
+/// in the specified dimensions. The corresponding components of velocities and forces are negated.
+fn mirror(system: &mut System, dimension: Dimension) {
+    let simbox = match system.get_box() {
+        Some(simbox) => [simbox.x, simbox.y, simbox.z],
+        None => panic!("\ngcenter: Fatal Error. Simulation box is not defined but this should have been handled before."),
+    };
+
+    let mirrored = [dimension.is_x(), dimension.is_y(), dimension.is_z()];
+    let reflect = |vector: &Vector3D, origin: [f32; 3]| {
+        let mut components = [vector.x, vector.y, vector.z];
+        for (i, component) in components.iter_mut().enumerate() {
+            if mirrored[i] {
+                *component = origin[i] - *component;
+            }
+        }
+        Vector3D::new(components[0], components[1], components[2])
+    };
+
+    for atom in system.atoms_iter_mut() {
+        // position `p` is reflected to `box - p`, i.e. across the center of the box
+        if let Some(position) = atom.get_position() {
+            let reflected = reflect(position, simbox);
+            atom.set_position(reflected);
+        }
+
+        if let Some(velocity) = atom.get_velocity() {
+            let reflected = reflect(velocity, [0.0; 3]);
+            atom.set_velocity(reflected);
+        }
+
+        if let Some(force) = atom.get_force() {
+            let reflected = reflect(force, [0.0; 3]);
+            atom.set_force(reflected);
+        }
+    }
+}
+
+/// Replace the simulation box of the system with a cubic box and shift all atoms
+/// so that the center of the original box is placed at the center of the new box.
+/// Returns the edge of the new box.
+fn apply_template_box(system: &mut System, template: TemplateBox) -> f32 {
+    let old = match system.get_box() {
+        Some(simbox) => [simbox.x, simbox.y, simbox.z],
+        None => panic!("\ngcenter: Fatal Error. Simulation box is not defined but this should have been handled before."),
+    };
+
+    let edge = match template {
+        TemplateBox::Max => old.iter().copied().fold(f32::MIN, f32::max),
+        TemplateBox::Edge(edge) => edge,
+    };
+
+    if old.iter().any(|&x| edge < x) {
+        warn!(
+            "{} output box edge ({} nm) is smaller than the current simulation box ({} nm); some atoms may end up outside of the output box.\n",
+            "warning:".yellow().bold(),
+            edge.to_string().yellow(),
+            BoxSize(old).to_string().yellow()
+        );
+    }
+
+    let shift = Vector3D::new(
+        (edge - old[0]) / 2.0,
+        (edge - old[1]) / 2.0,
+        (edge - old[2]) / 2.0,
+    );
+
+    // atoms are shifted without wrapping so that no molecule is broken
+    for atom in system.atoms_iter_mut() {
+        if let Some(position) = atom.get_position() {
+            let shifted = Vector3D::new(
+                position.x + shift.x,
+                position.y + shift.y,
+                position.z + shift.z,
+            );
+            atom.set_position(shifted);
+        }
+    }
+
+    system.set_box(SimBox::from([edge, edge, edge]));
+
+    edge
+}
+
+/// Calculate the centers of the reference groups of all centering operations.
+fn reference_centers(
+    system: &System,
+    operations: &[Operation],
+    method: Method,
+    weighted: &[(String, f32)],
+) -> Result<Vec<Vector3D>, Box<dyn std::error::Error + Send + Sync>> {
+    operations
+        .iter()
+        .map(|(group, _, weighting)| {
+            reference_point(system, group, method.with_weighting(*weighting), weighted)
+        })
+        .collect()
+}
+
+/// Translate the system so that the reference groups are placed at the provided fixed positions.
+/// This removes the translation of the reference groups relative to the fixed frame.
+/// The translation is repeated `iterations` times.
+fn translate_to_fixed(
+    system: &mut System,
+    operations: &[Operation],
+    fixed: &[Vector3D],
+    method: Method,
+    weighted: &[(String, f32)],
+    iterations: usize,
+    wrap: Dimension,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for _ in 0..iterations {
+        for ((group, dims, weighting), target) in operations.iter().zip(fixed.iter()) {
+            let method = method.with_weighting(*weighting);
+            translate_group_to(system, group, *dims, target, method, weighted, wrap)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the `n`th frame of the trajectory and get the centers of the reference groups in this frame.
+fn read_fixed_centers<'a>(
+    reader: impl TrajMasterRead<'a>,
+    n: usize,
+    operations: &[Operation],
+    method: Method,
+    args: &Args,
+) -> Result<Vec<Vector3D>, Box<dyn std::error::Error + Send + Sync>> {
+    let duplicates = Cell::new(0);
+    let frames = step_range_frames(reader, args.begin_step, args.end_step);
+    let frames = discarded_frames(frames, args.discard);
+    let frames = unique_frames(frames, args.dedup, &duplicates);
+    let frame = parity_frames(spaced_frames(frames, args.every), args.frame_parity()).nth(n);
+    match frame {
+        Some(frame) => {
+            let frame = frame?;
+            keep_box(frame, args);
+            if let Some(target) = &args.rescale_box {
+                rescale_box(frame, target)?;
+            }
+            reference_centers(frame, operations, method, &weighted_groups(args))
+        }
+        None => Err(Box::new(RunError::ReferenceFrameNotFound(n))),
+    }
+}
+
+/// Pass through the trajectory reading the centers of the reference groups in the `n`th frame.
+struct FixedCentersPass<'o> {
+    n: usize,
+    operations: &'o [Operation],
+    method: Method,
+    args: &'o Args,
+}
+
+impl TrajectoryPass<Vec<Vector3D>> for FixedCentersPass<'_> {
+    fn run<'a>(
+        self,
+        reader: impl TrajMasterRead<'a>,
+    ) -> Result<Vec<Vector3D>, Box<dyn std::error::Error + Send + Sync>> {
+        read_fixed_centers(reader, self.n, self.operations, self.method, self.args)
+    }
+}
+
+/// Get the centers of the reference groups in the frame specified using `--reference-from-frame`.
+/// Returns `None` if no such frame has been requested.
+fn fixed_centers(
+    system: &mut System,
+    args: &Args,
+    start: Option<f32>,
+    times: Option<&[f32]>,
+    index: Option<&XtcIndex>,
+    operations: &[Operation],
+) -> Result<Option<Vec<Vector3D>>, Box<dyn std::error::Error + Send + Sync>> {
+    let n = match args.reference_from_frame {
+        None => return Ok(None),
+        Some(n) => n,
+    };
+
+    let pass = FixedCentersPass {
+        n,
+        operations,
+        method: Method::from_args(args),
+        args,
+    };
+
+    let centers = read_trajectories(system, args, start, times, index, pass)?;
+    Ok(Some(centers))
+}
+
+/// Statistics about the performed centering returned by `center`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CenterStats {
+    /// Number of centered and written frames (1 for a structure file).
+    pub frames: usize,
+    /// Number of frames read from the trajectory but not written because of `every`, `even-frames`, or `odd-frames`.
+    pub skipped: usize,
+    /// Number of frames read from the trajectory but not written because they duplicate the previous frame.
+    pub duplicates: usize,
+    /// Position of the reference before centering in the first centered frame.
+    pub first_centroid: [f32; 3],
+}
+
+/// Summary of the centering of a trajectory.
+#[derive(Debug, Default)]
+struct Summary {
+    /// Number of frames read from the input trajectory.
+    n_read: usize,
+    /// Number of frames written into the output trajectory.
+    n_written: usize,
+    /// Simulation time and position of the reference before centering for each frame.
+    /// Only collected if `--report-drift` is requested.
+    drift: Vec<(f32, Vector3D)>,
+    /// Simulation time and simulation box of each frame.
+    /// Only collected if `--report-box` is requested.
+    boxes: Vec<(f32, SimBox)>,
+    /// Number of frames containing atoms outside the simulation box.
+    /// Only collected if `--clamp-outside` is requested.
+    outside_frames: usize,
+    /// Highest number of atoms outside the simulation box in a single frame.
+    outside_max: usize,
+    /// Number of frames read from the trajectory but not selected for centering.
+    /// Does not include duplicate frames.
+    skipped: usize,
+    /// Number of frames skipped as duplicates of the previous frame.
+    /// Only collected if `--dedup` is requested.
+    duplicates: usize,
+    /// Position of the reference before centering in the first centered frame.
+    first_centroid: Option<Vector3D>,
+    /// Centering has been interrupted by the user.
+    interrupted: bool,
+    /// Time spent reading the frames.
+    read_time: Duration,
+    /// Time spent processing the frames.
+    center_time: Duration,
+    /// Time spent writing the frames.
+    write_time: Duration,
+}
+
+/// Get the dimensions centered by any of the operations.
+fn centered_dimensions(operations: &[Operation]) -> Dimension {
+    let mut dims = [false; 3];
+    for (_, dim, _) in operations {
+        dims[0] |= dim.is_x();
+        dims[1] |= dim.is_y();
+        dims[2] |= dim.is_z();
+    }
+
+    dims.into()
+}
+
+/// Get the displacement of the reference between two frames along the specified dimensions.
+/// The minimum image convention is applied so that crossing of a periodic boundary is not considered a displacement.
+fn reference_jump(
+    previous: &Vector3D,
+    current: &Vector3D,
+    dims: Dimension,
+    simbox: &SimBox,
+) -> f32 {
+    current.distance(previous, dims, simbox).abs()
+}
+
+/// Check that the reference has not moved by more than `halt-on-jump` since the previous frame.
+/// Returns an error or only prints a warning if `warn-on-jump` is provided.
+fn check_jump(
+    frame: &System,
+    previous: &Vector3D,
+    current: &Vector3D,
+    dims: Dimension,
+    index: usize,
+    args: &Args,
+) -> Result<(), RunError> {
+    let threshold = match args.halt_on_jump {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+
+    let simbox = frame.get_box().ok_or(RunError::BoxNotDefined)?;
+    let jump = reference_jump(previous, current, dims, simbox);
+    if jump <= threshold {
+        return Ok(());
+    }
+
+    if args.warn_on_jump {
+        warn!(
+            "{} reference moved by {} nm between frames {} and {} ({} ps), which exceeds the threshold of {} nm.\n",
+            "warning:".yellow().bold(),
+            format!("{:.3}", jump).yellow(),
+            (index - 1).to_string().yellow(),
+            index.to_string().yellow(),
+            format!("{:.3}", frame.get_simulation_time()).yellow(),
+            threshold.to_string().yellow()
+        );
+        Ok(())
+    } else {
+        Err(RunError::ReferenceJump(
+            index,
+            format!("{:.3}", jump),
+            format!("{:.3}", frame.get_simulation_time()),
+            threshold.to_string(),
+        ))
+    }
+}
+
+/// Get the position of the reference before centering.
+/// Each coordinate is taken from the reference group used for the corresponding dimension.
+/// Coordinates of dimensions that are not centered are taken from the first reference group.
+fn drift_point(
+    system: &System,
+    operations: &[Operation],
+    method: Method,
+    weighted: &[(String, f32)],
+) -> Result<Vector3D, Box<dyn std::error::Error + Send + Sync>> {
+    let mut point = match operations.first() {
+        Some((group, _, weighting)) => {
+            reference_point(system, group, method.with_weighting(*weighting), weighted)?
+        }
+        None => return Ok(Vector3D::default()),
+    };
+
+    for (group, dims, weighting) in operations.iter().skip(1) {
+        let position = reference_point(system, group, method.with_weighting(*weighting), weighted)?;
+
+        if dims.is_x() {
+            point.x = position.x;
+        }
+        if dims.is_y() {
+            point.y = position.y;
+        }
+        if dims.is_z() {
+            point.z = position.z;
+        }
+    }
+
+    Ok(point)
+}
+
+/// Write the positions of the reference into an xvg file.
+fn write_drift(filename: &str, drift: &[(f32, Vector3D)]) -> Result<(), RunError> {
+    let mut content = format!(
+        "# This file was created by gcenter {}
+# Position of the reference before centering
+@    title \"Reference position\"
+@    xaxis  label \"Time (ps)\"
+@    yaxis  label \"Position (nm)\"
+@TYPE xy
+@ s0 legend \"x\"
+@ s1 legend \"y\"
+@ s2 legend \"z\"
+",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    for (time, position) in drift {
+        content.push_str(&format!(
+            "{:>12.3} {:>10.4} {:>10.4} {:>10.4}\n",
+            time, position.x, position.y, position.z
+        ));
+    }
+
+    std::fs::write(filename, content).map_err(|_| RunError::DriftNotWritten(filename.to_owned()))
+}
+
+/// Write the dimensions and volumes of the simulation boxes into an xvg file.
+fn write_box_report(filename: &str, boxes: &[(f32, SimBox)]) -> Result<(), RunError> {
+    let mut content = format!(
+        "# This file was created by gcenter {}
+# Dimensions of the simulation box
+@    title \"Simulation box\"
+@    xaxis  label \"Time (ps)\"
+@    yaxis  label \"Box (nm) / Volume (nm^3)\"
+@TYPE xy
+@ s0 legend \"x\"
+@ s1 legend \"y\"
+@ s2 legend \"z\"
+@ s3 legend \"volume\"
+",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    for (time, simbox) in boxes {
+        content.push_str(&format!(
+            "{:>12.3} {:>10.4} {:>10.4} {:>10.4} {:>12.4}\n",
+            time,
+            simbox.x,
+            simbox.y,
+            simbox.z,
+            simbox.x * simbox.y * simbox.z
+        ));
+    }
+
+    std::fs::write(filename, content)
+        .map_err(|_| RunError::BoxReportNotWritten(filename.to_owned()))
+}
+
+/// Get the simulation time and the simulation box of the system for the box report.
+fn box_entry(system: &System) -> Result<(f32, SimBox), RunError> {
+    let simbox = system.get_box().ok_or(RunError::BoxNotDefined)?;
+    Ok((system.get_simulation_time(), simbox.clone()))
+}
+
+/// Writer of the statistics of the individual frames as JSON lines.
+/// The output is buffered and flushed when the writer is dropped,
+/// so the statistics of the frames processed before an error are preserved.
+struct StatsWriter {
+    /// Path to the output file.
+    filename: String,
+    /// Buffered output file.
+    writer: BufWriter<File>,
+}
+
+impl StatsWriter {
+    /// Create the file for the statistics, if requested.
+    fn from_args(args: &Args) -> Result<Option<Self>, RunError> {
+        let filename = match &args.stats_jsonl {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        let file =
+            File::create(filename).map_err(|_| RunError::StatsNotWritten(filename.to_owned()))?;
+
+        Ok(Some(StatsWriter {
+            filename: filename.to_owned(),
+            writer: BufWriter::new(file),
+        }))
+    }
+
+    /// Write the statistics of a single frame.
+    /// `simbox` and `centroid` are the simulation box and the position of the reference before centering.
+    fn write(
+        &mut self,
+        frame: usize,
+        time: f32,
+        step: u64,
+        simbox: &SimBox,
+        centroid: &Vector3D,
+    ) -> Result<(), RunError> {
+        writeln!(
+            self.writer,
+            "{{\"frame\": {}, \"time\": {:.3}, \"step\": {}, \"box\": [{:.4}, {:.4}, {:.4}], \"centroid\": [{:.4}, {:.4}, {:.4}]}}",
+            frame, time, step, simbox.x, simbox.y, simbox.z, centroid.x, centroid.y, centroid.z
+        )
+        .map_err(|_| RunError::StatsNotWritten(self.filename.to_owned()))
+    }
+
+    /// Flush the buffered statistics into the file.
+    fn close(mut self) -> Result<(), RunError> {
+        self.writer
+            .flush()
+            .map_err(|_| RunError::StatsNotWritten(self.filename.to_owned()))
+    }
+}
+
+/// Progress of the centering reported as JSON lines.
+struct JsonProgress {
+    /// Time of the first centered frame.
+    start: Option<f32>,
+    /// Time of the last frame to center, if known.
+    end: Option<f32>,
+}
+
+impl JsonProgress {
+    /// Estimate the time range of the centered frames.
+    /// If `end` is not provided, the time of the last frame of the (last) trajectory is used.
+    fn new(args: &Args, start: Option<f32>) -> Self {
+        let end = args.end_time.or_else(|| {
+            args.trajectory_paths()
+                .last()
+                .and_then(header::trajectory_last_time)
+        });
+
+        JsonProgress { start, end }
+    }
+
+    /// Get the JSON line describing the progress of the centering after the specified frame.
+    fn line(&mut self, frame: usize, time: f32) -> String {
+        let start = *self.start.get_or_insert(time);
+
+        match self.end.filter(|&end| end > start) {
+            Some(end) => format!(
+                "{{\"frame\": {}, \"time\": {:.3}, \"percent\": {:.1}}}",
+                frame,
+                time,
+                ((time - start) / (end - start) * 100.0).clamp(0.0, 100.0)
+            ),
+            None => format!("{{\"frame\": {}, \"time\": {:.3}}}", frame, time),
+        }
+    }
+}
+
+/// Center a trajectory.
+/// If `fixed` centers are provided, the reference groups are placed at these positions instead of the box center.
+/// `start` is the time of the first frame to read, if known.
+fn center_trajectory<'a>(
+    mut reader: impl TrajMasterRead<'a>,
+    args: &Args,
+    operations: Vec<Operation>,
+    fixed: &Option<Vec<Vector3D>>,
+    start: Option<f32>,
+    interrupt: &Interrupt,
+) -> Result<Summary, Box<dyn std::error::Error + Send + Sync>> {
+    let verbosity = args.verbosity();
+
+    let mut json = args.progress_json.then(|| JsonProgress::new(args, start));
+
+    // information about individual frames, JSON progress, and positions of the reference replace the progress printing
+    if verbosity != Verbosity::Silent
+        && verbosity != Verbosity::Verbose
+        && json.is_none()
+        && !args.print_centroid
+    {
+        let mut printer = ProgressPrinter::new()
+            .with_running_msg("CENTERING".yellow())
+            .with_newline_at_end(false);
+        if let Some(interval) = args.progress_interval {
+            printer = printer.with_print_freq(interval);
+        }
+
+        reader = reader.print_progress(printer);
+    }
+
+    let method = Method::from_args(args);
+    let weighted = weighted_groups(args);
+    let steps = args.transform_steps();
+    let n_frames = if args.first_frame_only {
+        1
+    } else {
+        args.max_frames.unwrap_or(usize::MAX)
+    };
+
+    let precision = output_precision(args);
+    let mut time_shift = TimeShift::from_args(args);
+    let mut trr = trr_writer(args)?;
+    let mut stats = StatsWriter::from_args(args)?;
+    let output_group = output_group(args);
+    let mut summary = Summary::default();
+    // the warning about large scaling factors is only printed once
+    let mut rescale_warned = false;
+    // position of the reference in the previous frame used to detect jumps
+    let mut previous_point: Option<Vector3D> = None;
+    let jump_dims = centered_dimensions(&operations);
+
+    let mut timer = Instant::now();
+    // frames read before the selection using simulation steps, `discard`, `every`, and frame parity
+    let n_candidates = Cell::new(0);
+    let frames = reader.inspect(|_| n_candidates.set(n_candidates.get() + 1));
+    let frames = step_range_frames(frames, args.begin_step, args.end_step);
+    let frames = discarded_frames(frames, args.discard);
+    // frames removed as duplicates of the previous frame
+    let n_duplicates = Cell::new(0);
+    let frames = unique_frames(frames, args.dedup, &n_duplicates);
+    let frames = parity_frames(spaced_frames(frames, args.every), args.frame_parity());
+    for frame in frames.take(n_frames) {
+        summary.read_time += timer.elapsed();
+        timer = Instant::now();
+
+        let frame = frame?;
+        summary.n_read += 1;
+
+        if args.report_box.is_some() {
+            summary.boxes.push(box_entry(frame)?);
+        }
+
+        // box of the frame as read from the input for the statistics
+        let input_box = match stats {
+            Some(_) => Some(box_entry(frame)?.1),
+            None => None,
+        };
+
+        keep_box(frame, args);
+
+        if let Some(target) = &args.rescale_box {
+            let factors = rescale_box(frame, target)?;
+
+            if !rescale_warned
+                && factors
+                    .iter()
+                    .any(|factor| (factor - 1.0).abs() > RESCALE_WARNING_THRESHOLD)
+            {
+                warn!(
+                    "{} box of the frame at {} ps is scaled by factors {} which deviate from 1 by more than 10%; check the target box of '{}'.\n",
+                    "warning:".yellow().bold(),
+                    frame.get_simulation_time(),
+                    format!("{:.3} {:.3} {:.3}", factors[0], factors[1], factors[2]).yellow(),
+                    "--rescale-box".yellow()
+                );
+                rescale_warned = true;
+            }
+        }
+
+        trace!(
+            "[FRAME {:>8}] step {:>12}   time {:>12.3} ps",
+            summary.n_read - 1,
+            frame.get_simulation_step(),
+            frame.get_simulation_time()
+        );
+
+        if summary.first_centroid.is_none() {
+            summary.first_centroid = Some(drift_point(frame, &operations, method, &weighted)?);
+        }
+
+        // position of the reference before centering for the statistics
+        let mut centroid = None;
+        if args.report_drift.is_some()
+            || args.print_centroid
+            || args.halt_on_jump.is_some()
+            || stats.is_some()
+        {
+            let point = drift_point(frame, &operations, method, &weighted)?;
+
+            if let Some(previous) = &previous_point {
+                check_jump(frame, previous, &point, jump_dims, summary.n_read - 1, args)?;
+            }
+
+            if args.print_centroid {
+                println!(
+                    "{:>12.3} {:>10.4} {:>10.4} {:>10.4}",
+                    frame.get_simulation_time(),
+                    point.x,
+                    point.y,
+                    point.z
+                );
+            }
+
+            if args.report_drift.is_some() {
+                summary
+                    .drift
+                    .push((frame.get_simulation_time(), point.clone()));
+            }
+
+            if stats.is_some() {
+                centroid = Some(point.clone());
+            }
+
+            previous_point = Some(point);
+        }
+
+        for step in steps.iter() {
+            match (step, fixed) {
+                (TransformStep::Center, Some(centers)) => translate_to_fixed(
+                    frame,
+                    &operations,
+                    centers,
+                    method,
+                    &weighted,
+                    args.center_iterations,
+                    args.wrap_dims,
+                )?,
+                (TransformStep::Center, None) => place_groups(
+                    frame,
+                    &operations,
+                    method,
+                    &weighted,
+                    args.abs_target.as_ref(),
+                    args.center_iterations,
+                    args.wrap_dims,
+                )?,
+                (TransformStep::Whole, _) => make_molecules_whole(frame, args.whole_threshold)?,
+                (TransformStep::Wrap, _) => wrap_atoms(frame, args.wrap_dims)?,
+            }
+        }
+
+        if let Some(dimension) = args.mirror {
+            mirror(frame, dimension);
+        }
+
+        let n_outside = check_outside_box(frame, args)?;
+        if n_outside > 0 {
+            summary.outside_frames += 1;
+            summary.outside_max = summary.outside_max.max(n_outside);
+        }
+
+        if args.no_velocities || args.positions_only {
+            frame
+                .atoms_iter_mut()
+                .for_each(|atom| atom.reset_velocity());
+        }
+
+        if let Some(precision) = precision {
+            frame.set_precision(precision);
+        }
+
+        summary.center_time += timer.elapsed();
+        timer = Instant::now();
+
+        // the original time is used to report the progress
+        let time = frame.get_simulation_time();
+        if let Some(shift) = time_shift.as_mut() {
+            frame.set_simulation_time(shift.shift(time, summary.n_read - 1));
+        }
+
+        // open the next file of the output split into chunks
+        if let Some(size) = args.max_output_frames.filter(|_| !args.no_output) {
+            if (summary.n_read - 1) % size == 0 {
+                let number = (summary.n_read - 1) / size + 1;
+                open_chunk(frame, &mut trr, args, output_group, number)?;
+            }
+        }
+
+        let written = match trr.as_mut() {
+            Some(writer) => frame_written(writer.write_frame(frame, output_group)),
+            None if !args.no_output => frame_written(frame.traj_write_frame()),
+            None => false,
+        };
+
+        if written {
+            summary.n_written += 1;
+        } else if !args.no_output {
+            // the frame could not be written; stop and let the frame count check report the incomplete output
+            break;
+        }
+
+        // make the frame immediately available to the reader of the stream; only trr output can be streamed
+        if let Some(writer) = trr.as_mut().filter(|_| args.stream) {
+            writer.flush()?;
+        }
+        summary.write_time += timer.elapsed();
+
+        if let Some(json) = json
+            .as_mut()
+            .filter(|_| (summary.n_read - 1) % args.progress_interval.unwrap_or(1) == 0)
+        {
+            eprintln!("{}", json.line(summary.n_read - 1, time));
+        }
+
+        if let (Some(stats), Some(simbox), Some(centroid)) = (stats.as_mut(), &input_box, &centroid)
+        {
+            stats.write(
+                summary.n_read - 1,
+                time,
+                frame.get_simulation_step(),
+                simbox,
+                centroid,
+            )?;
+        }
+
+        // stop after a complete frame has been written so that the output trajectory remains valid
+        if interrupt.requested() {
+            summary.interrupted = true;
+            break;
+        }
+
+        timer = Instant::now();
+    }
+
+    if let Some(writer) = trr {
+        // failure to flush an already incomplete output is reported by the frame count check
+        let closed = writer.close();
+        if summary.n_written == summary.n_read {
+            closed?;
+        }
+    }
+
+    if let Some(stats) = stats {
+        stats.close()?;
+    }
+
+    summary.duplicates = n_duplicates.get();
+    summary.skipped = n_candidates.get() - summary.n_read - summary.duplicates;
+    Ok(summary)
+}
+
+/// Check whether a frame has been successfully written into the output.
+/// The reason for the failure is reported as a warning.
+fn frame_written<E: std::fmt::Display>(result: Result<(), E>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("{}", e);
+            false
+        }
+    }
+}
+
+/// Get the path to the `number`th file of the output split into chunks.
+/// The number is inserted before the extension of the output file, i.e. `out.xtc` becomes `out.0001.xtc`.
+pub fn chunk_path(output: &str, number: usize) -> String {
+    let path = Path::new(output);
+    match path.extension().and_then(|x| x.to_str()) {
+        Some(extension) => path
+            .with_extension(format!("{:04}.{}", number, extension))
+            .to_string_lossy()
+            .into_owned(),
+        None => format!("{}.{:04}", output, number),
+    }
+}
+
+/// Close the current file of the output split into chunks and open the `number`th file.
+fn open_chunk(
+    system: &mut System,
+    trr: &mut Option<crate::trr::TrrWriter>,
+    args: &Args,
+    output_group: Option<&str>,
+    number: usize,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let output = chunk_path(&args.output, number);
+    crate::backup_output(args, &output)?;
+
+    if own_trr_output(args) {
+        let writer = crate::trr::TrrWriter::new(&output, args.positions_only)?;
+        if let Some(previous) = trr.replace(writer) {
+            previous.close()?;
+        }
+    } else {
+        system.traj_close();
+        trajectory_writer_init(
+            system,
+            &output,
+            crate::file_type(&args.output),
+            output_group,
+        )?;
+    }
+
+    debug!(
+        "{} writing frames into '{}'\n",
+        "note:".purple().bold(),
+        output.yellow()
+    );
+
+    Ok(())
+}
+
+/// Get the name of the group of atoms written into the output.
+/// Stripped atoms and atoms missing in the structure file are not written.
+fn output_group(args: &Args) -> Option<&'static str> {
+    (args.strip.is_some() || args.extra_atoms > 0).then_some(crate::OUTPUT)
+}
+
+/// Attach a trajectory writer to the system.
+/// The type of the writer is specified explicitly since the output may be written into a temporary file without a proper extension.
+fn trajectory_writer_init(
+    system: &mut System,
+    output: &str,
+    output_type: FileType,
+    output_group: Option<&str>,
+) -> Result<(), WriteTrajError> {
+    match (output_type, output_group) {
+        (FileType::XTC, None) => system.xtc_writer_init(output),
+        (FileType::XTC, Some(group)) => system.xtc_group_writer_init(output, group),
+        (FileType::TRR, None) => system.trr_writer_init(output),
+        (FileType::TRR, Some(group)) => system.trr_group_writer_init(output, group),
+        (FileType::GRO, None) => system.gro_writer_init(output),
+        (FileType::GRO, Some(group)) => system.gro_group_writer_init(output, group),
+        _ => panic!("\ngcenter: Fatal Error. Output file has unsupported file extension but this should have been handled before."),
+    }
+}
+
+/// Check that all the trajectories contain the same number of atoms as the system.
+/// This is done before the output file is created so that no partial output is written.
+fn check_atom_counts(system: &System, args: &Args) -> Result<(), RunError> {
+    for (traj, path) in args.trajectories.iter().zip(args.trajectory_paths()) {
+        if let Some(n_atoms) = header::trajectory_n_atoms(path) {
+            if n_atoms != system.get_n_atoms() {
+                return Err(RunError::AtomCountMismatch(
+                    traj.to_owned(),
+                    n_atoms,
+                    system.get_n_atoms(),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the index of frames of the input xtc trajectory, if requested.
+fn xtc_index(args: &Args) -> Option<XtcIndex> {
+    let file = args.xtc_index.as_ref()?;
+
+    let paths = args.trajectory_paths();
+    if paths.len() != 1 || crate::file_type(&paths[0]) != FileType::XTC {
+        warn!(
+            "{} option '{}' can only be used with a single xtc trajectory; the option will be ignored.\n",
+            "warning:".yellow().bold(),
+            "--xtc-index".yellow()
+        );
+        return None;
+    }
+
+    load_index(file, &paths[0], &args.trajectories[0])
+}
+
+/// Single pass through the frames of the input trajectory (or trajectories).
+trait TrajectoryPass<T> {
+    /// Process the frames provided by the reader.
+    fn run<'a>(
+        self,
+        reader: impl TrajMasterRead<'a>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Open the input trajectory (or trajectories) and pass its frames selected by the time range and step to `pass`.
+/// If `times` are provided, they are assigned to the frames of the (gro) trajectory.
+/// If `index` is provided, the reading of a single xtc trajectory starts directly at the start frame.
+fn read_trajectories<T>(
+    system: &mut System,
+    args: &Args,
+    start: Option<f32>,
+    times: Option<&[f32]>,
+    index: Option<&XtcIndex>,
+    pass: impl TrajectoryPass<T>,
+) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+    let paths = args.trajectory_paths();
+    if paths.len() == 1 {
+        match crate::file_type(&paths[0]) {
+            FileType::XTC => {
+                let mut xtc = XtcReader::new(system, &paths[0])?;
+                if let (Some(index), Some(start)) = (index, start) {
+                    seek_to_start(&mut xtc, index, &paths[0], start);
+                }
+                pass.run(read_range_step(TrajReader::wrap_traj(xtc), start, args)?)
+            }
+            FileType::TRR => pass.run(read_range_step(system.trr_iter(&paths[0])?, start, args)?),
+            FileType::GRO => match times {
+                Some(times) => pass.run(TimedReader::new(system.gro_iter(&paths[0])?, times, start, args)),
+                None => pass.run(read_step(system.gro_iter(&paths[0])?, args)?),
+            },
+            _ => panic!("\ngcenter: Fatal Error. Input file has unsupported file extension but this should have been handled before."),
+        }
+    } else {
+        match crate::file_type(&paths[0]) {
+            FileType::XTC => pass.run(read_range_step(system.xtc_cat_iter(paths)?, start, args)?),
+            FileType::TRR => pass.run(read_range_step(system.trr_cat_iter(paths)?, start, args)?),
+            _ => panic!("\ngcenter: Fatal Error. Input file has unsupported file extension but this should have been handled before."),
+        }
+    }
+}
+
+/// Pass through the trajectory centering and writing all selected frames.
+struct CenteringPass<'o> {
+    args: &'o Args,
+    operations: Vec<Operation>,
+    fixed: &'o Option<Vec<Vector3D>>,
+    start: Option<f32>,
+    interrupt: &'o Interrupt,
+}
+
+impl TrajectoryPass<Summary> for CenteringPass<'_> {
+    fn run<'a>(
+        self,
+        reader: impl TrajMasterRead<'a>,
+    ) -> Result<Summary, Box<dyn std::error::Error + Send + Sync>> {
+        center_trajectory(
+            reader,
+            self.args,
+            self.operations,
+            self.fixed,
+            self.start,
+            self.interrupt,
+        )
+    }
+}
+
+/// Center all the provided trajectories.
+/// If `times` are provided, they are assigned to the frames of the (gro) trajectory.
+fn center_trajectories(
+    system: &mut System,
+    args: &Args,
+    operations: Vec<Operation>,
+    times: Option<&[f32]>,
+    interrupt: &Interrupt,
+) -> Result<Summary, Box<dyn std::error::Error + Send + Sync>> {
+    if args.strict_box {
+        check_simulation_box(system)?;
+    } else {
+        simbox_error_to_warning(check_simulation_box(system), args.quiet_box_warnings);
+    }
+
+    let start = start_time(args)?;
+    let index = xtc_index(args);
+    let fixed = fixed_centers(system, args, start, times, index.as_ref(), &operations)?;
+
+    let pass = CenteringPass {
+        args,
+        operations,
+        fixed: &fixed,
+        start,
+        interrupt,
+    };
+
+    read_trajectories(system, args, start, times, index.as_ref(), pass)
+}
+
+/// Center the structure or trajectory file.
+pub fn center(
+    system: &mut System,
+    args: &Args,
+    operations: Vec<Operation>,
+    benchmark: &mut Benchmark,
+    interrupt: &Interrupt,
+) -> Result<CenterStats, Box<dyn std::error::Error + Send + Sync>> {
+    // determine type of the output file
+    let output_type = crate::file_type(&args.output);
+
+    if let Some(target) = &args.abs_target {
+        check_target(system, target);
+    }
+
+    if !args.no_output {
+        check_lost_data(system, args, output_type);
+    }
+
+    // only the atoms that are not stripped and are present in the structure file are written into the output
+    let output_group = output_group(args);
+
+    if args.renumber {
+        renumber(system, output_group, &args.output);
+    }
+
+    if args.trajectories.is_empty() {
+        // trajectory file not provided, center the structure file
+        let timer = Instant::now();
+        let stats = center_structure_file(system, args, output_type, output_group, operations)?;
+        benchmark.add("centering", timer.elapsed());
+        Ok(stats)
+    } else {
+        check_atom_counts(system, args)?;
+        let times = frame_times(args)?;
+
+        // attach trajectory writer; the writer of the output split into chunks is attached when the first frame is written
+        if !args.no_output && !own_trr_output(args) && args.max_output_frames.is_none() {
+            trajectory_writer_init(system, args.output_path(), output_type, output_group)?;
+        }
+        // on interrupt, finish writing the current frame and close the output trajectory
+        interrupt.catch();
+        let summary = center_trajectories(system, args, operations, times.as_deref(), interrupt);
+        interrupt.release();
+        let summary = summary?;
+        benchmark.add("reading frames", summary.read_time);
+        benchmark.add("centering frames", summary.center_time);
+        benchmark.add("writing frames", summary.write_time);
+
+        // flush and close the output trajectory
+        system.traj_close();
+
+        match args.verbosity() {
+            Verbosity::Silent => (),
+            // stdout only contains the positions of the reference
+            _ if args.print_centroid => eprintln!(),
+            // the progress printer does not end with a newline
+            Verbosity::Quiet | Verbosity::Normal if !args.progress_json => println!("\n"),
+            _ => println!(),
+        }
+
+        if summary.interrupted {
+            return Err(Box::new(RunError::Interrupted(
+                summary.n_written,
+                args.output.to_owned(),
+            )));
+        }
+
+        // check that no frame has been lost; frames are not written at all with `no-output`
+        if !args.no_output && summary.n_read != summary.n_written {
+            return Err(Box::new(RunError::FrameCountMismatch(
+                summary.n_read,
+                summary.n_written,
+            )));
+        }
+
+        debug!(
+            "{} read {} frame(s), written {} frame(s)\n",
+            "note:".purple().bold(),
+            summary.n_read,
+            summary.n_written
+        );
+
+        if args.dedup {
+            debug!(
+                "{} removed {} duplicate frame(s)\n",
+                "note:".purple().bold(),
+                summary.duplicates
+            );
+        }
+
+        report_outside_box(args, Some(summary.outside_frames), summary.outside_max);
+
+        if let Some(report) = &args.report_box {
+            write_box_report(report, &summary.boxes)?;
+
+            debug!(
+                "{} dimensions of the simulation box written into '{}'\n",
+                "note:".purple().bold(),
+                report.yellow()
+            );
+        }
+
+        if let Some(drift) = &args.report_drift {
+            write_drift(drift, &summary.drift)?;
+
+            debug!(
+                "{} position of the reference written into '{}'\n",
+                "note:".purple().bold(),
+                drift.yellow()
+            );
+        }
+
+        let centroid = summary.first_centroid.unwrap_or_default();
+        Ok(CenterStats {
+            frames: summary.n_read,
+            skipped: summary.skipped,
+            duplicates: summary.duplicates,
+            first_centroid: [centroid.x, centroid.y, centroid.z],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+    use crate::test_utils::system_from_positions;
+
+    /// Create a system with a cluster of atoms around x = 2 and a single outlier atom at x = 6.
+    fn system_with_outlier() -> System {
+        let positions = [2.0, 2.1, 1.9, 2.0, 2.0, 6.0].map(|x| [x, 5.0, 5.0]);
+        system_from_positions("Outlier", &positions, 10.0)
+    }
+
+    /// Center the upper leaflet of the membrane using the provided arguments.
+    /// Returns the statistics of the centering and the system before centering.
+    fn center_membrane(cli: &[&str]) -> (CenterStats, System) {
+        center_membrane_interruptible(cli, &Interrupt::default())
+    }
+
+    /// Center the upper leaflet of the membrane using the provided arguments and interrupt handler.
+    fn center_membrane_interruptible(cli: &[&str], interrupt: &Interrupt) -> (CenterStats, System) {
+        let base = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-r=resid 22 to 149",
+        ];
+        let args = Args::parse_from(base.iter().chain(cli.iter()));
+
+        let mut system = System::from_file(&args.structure).unwrap();
+        let operations =
+            crate::reference::create_references(&mut system, Dimension::XYZ, &args).unwrap();
+        let original = system.clone();
+
+        let stats = center(
+            &mut system,
+            &args,
+            operations,
+            &mut Benchmark::default(),
+            interrupt,
+        )
+        .unwrap();
+        (stats, original)
+    }
+
+    #[test]
+    fn center_stats_structure() {
+        let output = tempfile::Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o={}", output.path().display());
+
+        let (stats, original) = center_membrane(&[&output_arg]);
+        let expected = original.group_get_center(crate::MAIN_REFERENCE).unwrap();
+
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.skipped, 0);
+        assert_eq!(stats.first_centroid, [expected.x, expected.y, expected.z]);
+    }
+
+    #[test]
+    fn center_stats_trajectory() {
+        let (stats, mut original) = center_membrane(&[
+            "-f=tests/test_files/input.xtc",
+            "--no-output",
+            "--odd-frames",
+        ]);
+
+        // frame at 100 ps is the first odd frame
+        let frame = original
+            .xtc_iter("tests/test_files/input.xtc")
+            .unwrap()
+            .nth(1)
+            .unwrap()
+            .unwrap();
+        let expected = frame.group_get_center(crate::MAIN_REFERENCE).unwrap();
+
+        assert_eq!(stats.frames, 5);
+        assert_eq!(stats.skipped, 6);
+        assert_eq!(stats.first_centroid, [expected.x, expected.y, expected.z]);
+    }
+
+    #[test]
+    fn center_after_interrupt() {
+        // interrupt the previous centering
+        let interrupt = Interrupt::default();
+        interrupt.catch();
+        interrupt.request();
+        interrupt.release();
+
+        let (stats, _) = center_membrane_interruptible(
+            &["-f=tests/test_files/input.xtc", "--no-output"],
+            &interrupt,
+        );
+
+        assert_eq!(stats.frames, 11);
+    }
+
+    #[test]
+    fn median_odd() {
+        let mut values = [3.0, 1.0, 2.0];
+        assert_eq!(median(&mut values), 2.0);
+    }
+
+    #[test]
+    fn median_even() {
+        let mut values = [4.0, 1.0, 3.0, 2.0];
+        assert_eq!(median(&mut values), 2.5);
+    }
+
+    #[test]
+    fn median_order_independent() {
+        let values = [2.0, -0.0, 0.0, 2.0, 1.5, -1.0];
+        let expected = median(&mut values.clone());
+        assert_eq!(expected, 0.75);
+
+        for shift in 1..values.len() {
+            let mut rotated = values;
+            rotated.rotate_left(shift);
+            assert_eq!(median(&mut rotated).to_bits(), expected.to_bits());
+
+            rotated.reverse();
+            assert_eq!(median(&mut rotated).to_bits(), expected.to_bits());
+        }
+    }
+
+    #[test]
+    fn group_get_median_outlier() {
+        let system = system_with_outlier();
+        let median = group_get_median(&system, "all").unwrap();
+
+        assert!((median.x - 2.0).abs() < 1e-4);
+        assert!((median.y - 5.0).abs() < 1e-4);
+        assert!((median.z - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn group_get_median_pbc() {
+        let positions = [9.8, 9.9, 0.1, 0.2, 0.0].map(|x| [x, 5.0, 5.0]);
+        let system = system_from_positions("PBC", &positions, 10.0);
+
+        let median = group_get_median(&system, "all").unwrap();
+        assert!(median.x.abs() < 1e-4 || (median.x - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn center_median_ignores_outlier() {
+        let mut geometry = system_with_outlier();
+        center_group(
+            &mut geometry,
+            "all",
+            Dimension::X,
+            Method::Geometry,
+            &[],
+            Dimension::XYZ,
+        )
+        .unwrap();
+
+        let mut median = system_with_outlier();
+        center_group(
+            &mut median,
+            "all",
+            Dimension::X,
+            Method::Median,
+            &[],
+            Dimension::XYZ,
+        )
+        .unwrap();
+
+        // the outlier shifts the center of geometry away from the cluster
+        let geometry_x = geometry.get_atom(0).unwrap().get_position().unwrap().x;
+        assert!((geometry_x - 5.0).abs() > 0.1);
+
+        // the median is not affected by the outlier
+        let median_x = median.get_atom(0).unwrap().get_position().unwrap().x;
+        assert!((median_x - 5.0).abs() < 1e-4);
+
+        // other dimensions are not changed
+        let median_y = median.get_atom(0).unwrap().get_position().unwrap().y;
+        assert!((median_y - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn group_get_bbox_center_outlier() {
+        let system = system_with_outlier();
+        let center = group_get_bbox_center(&system, "all").unwrap();
+
+        // the midpoint between the leftmost atom (1.9) and the outlier (6.0)
+        assert!((center.x - 3.95).abs() < 1e-4);
+        assert!((center.y - 5.0).abs() < 1e-4);
+        assert!((center.z - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn group_get_bbox_center_pbc() {
+        let positions = [9.6, 9.9, 0.1, 0.2].map(|x| [x, 5.0, 5.0]);
+        let system = system_from_positions("PBC", &positions, 10.0);
+
+        let center = group_get_bbox_center(&system, "all").unwrap();
+        assert!((center.x - 9.9).abs() < 1e-4);
+    }
+
+    #[test]
+    fn center_bbox_center() {
+        let mut system = system_with_outlier();
+        center_group(
+            &mut system,
+            "all",
+            Dimension::X,
+            Method::BoundingBox,
+            &[],
+            Dimension::XYZ,
+        )
+        .unwrap();
+
+        let min = system
+            .atoms_iter()
+            .map(|atom| atom.get_position().unwrap().x)
+            .fold(f32::INFINITY, f32::min);
+        let max = system
+            .atoms_iter()
+            .map(|atom| atom.get_position().unwrap().x)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        // the bounding box is placed symmetrically around the box center
+        assert!(((min + max) / 2.0 - 5.0).abs() < 1e-4);
+
+        // other dimensions are not changed
+        let y = system.get_atom(0).unwrap().get_position().unwrap().y;
+        assert!((y - 5.0).abs() < 1e-4);
+    }
+
+    /// Create a system with a membrane-like group: four atoms in the upper leaflet and one atom in the lower leaflet.
+    fn system_asymmetric_leaflets(lower: f32, upper: f32) -> System {
+        let positions = [
+            [1.0, 5.0, upper],
+            [2.0, 5.0, upper],
+            [3.0, 5.0, upper],
+            [4.0, 5.0, upper],
+            [5.0, 5.0, lower],
+        ];
+        system_from_positions("Leaflets", &positions, 10.0)
+    }
+
+    #[test]
+    fn group_get_midplane_asymmetric() {
+        let system = system_asymmetric_leaflets(4.0, 6.0);
+
+        // the center of geometry is shifted towards the more populated leaflet
+        let center = system.group_get_center("all").unwrap();
+        assert!(center.z > 5.1);
+
+        // the midplane lies between the leaflets
+        let midplane = group_get_midplane(&system, "all").unwrap();
+        assert!((midplane.x - center.x).abs() < 1e-4);
+        assert!((midplane.y - center.y).abs() < 1e-4);
+        assert!((midplane.z - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn group_get_midplane_pbc() {
+        let system = system_asymmetric_leaflets(9.0, 1.0);
+
+        let midplane = group_get_midplane(&system, "all").unwrap();
+        assert!(midplane.z.abs() < 1e-4 || (midplane.z - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn group_get_midplane_single_leaflet() {
+        let system = system_asymmetric_leaflets(6.0, 6.0);
+
+        let midplane = group_get_midplane(&system, "all").unwrap();
+        assert!((midplane.z - 6.0).abs() < 1e-4);
+    }
+
+    /// Create a system of two residues with masses: a light residue 1 spanning the periodic boundary in x
+    /// and a heavy single-atom residue 2.
+    fn system_with_residues() -> System {
+        let positions = [9.9, 1.9, 1.0].map(|x| [x, 5.0, 5.0]);
+        let mut system = system_from_positions("Residues", &positions, 10.0);
+
+        for (atom, (resid, mass)) in system.atoms_iter_mut().zip([(1, 3.0), (1, 1.0), (2, 10.0)]) {
+            atom.set_residue_number(resid);
+            atom.set_mass(mass);
+        }
+
+        system
+    }
+
+    #[test]
+    fn group_get_residue_com_equal_weights() {
+        let system = system_with_residues();
+
+        // residue 1 spans the periodic boundary and has its center of mass at x = 0.4,
+        // residue 2 has its center of mass at x = 1.0; both residues contribute equally
+        let center = group_get_residue_com(&system, "all").unwrap();
+        assert!((center.x - 0.7).abs() < 1e-4);
+        assert!((center.y - 5.0).abs() < 1e-4);
+        assert!((center.z - 5.0).abs() < 1e-4);
+
+        // the center of mass of the whole group is dominated by the heavy residue 2
+        let com = system.group_get_com("all").unwrap();
+        assert!(com.x > 0.8);
+    }
+
+    #[test]
+    fn group_get_residue_com_no_mass() {
+        let mut system = system_with_outlier();
+        assert!(group_get_residue_com(&system, "all").is_err());
+
+        system.atoms_iter_mut().for_each(|atom| atom.set_mass(1.0));
+        let center = group_get_residue_com(&system, "all").unwrap();
+        assert!((center.x - 2.6667).abs() < 1e-3);
+    }
+
+    /// Create a system with two groups of atoms ('First' at x = `first` and 'Second' at x = `second`)
+    /// containing 1 and 3 atoms, respectively.
+    fn system_two_groups(first: f32, second: f32) -> System {
+        let positions = [first, second, second, second].map(|x| [x, 5.0, 5.0]);
+        let mut system = system_from_positions("Groups", &positions, 10.0);
+        system.group_create("First", "serial 1").unwrap();
+        system.group_create("Second", "serial 2 to 4").unwrap();
+        system
+            .group_create(crate::MAIN_REFERENCE, "serial 1 to 4")
+            .unwrap();
+        system
+    }
+
+    #[test]
+    fn weighted_point_weights() {
+        let system = system_two_groups(2.0, 6.0);
+
+        // groups contribute according to their weights, not their sizes
+        let weighted = [(String::from("First"), 1.0), (String::from("Second"), 1.0)];
+        let point =
+            reference_point(&system, crate::MAIN_REFERENCE, Method::Geometry, &weighted).unwrap();
+        assert!((point.x - 4.0).abs() < 1e-4);
+
+        let weighted = [(String::from("First"), 3.0), (String::from("Second"), 1.0)];
+        let point =
+            reference_point(&system, crate::MAIN_REFERENCE, Method::Geometry, &weighted).unwrap();
+        assert!((point.x - 3.0).abs() < 1e-4);
+        assert!((point.y - 5.0).abs() < 1e-4);
+        assert!((point.z - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn weighted_point_pbc() {
+        let system = system_two_groups(9.0, 2.0);
+
+        let weighted = [(String::from("First"), 1.0), (String::from("Second"), 1.0)];
+        let point =
+            reference_point(&system, crate::MAIN_REFERENCE, Method::Geometry, &weighted).unwrap();
+        assert!((point.x - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn weighted_point_unweighted_group() {
+        let system = system_two_groups(2.0, 6.0);
+
+        // groups other than the main reference are not affected by the weights
+        let weighted = [(String::from("First"), 1.0), (String::from("Second"), 1.0)];
+        let point = reference_point(&system, "Second", Method::Geometry, &weighted).unwrap();
+        assert!((point.x - 6.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wrap_position_dimensions() {
+        let simbox = SimBox::from([10.0, 10.0, 10.0]);
+        let mut position = Vector3D::new(12.0, -1.0, 11.0);
+        wrap_position(&mut position, &simbox, Dimension::XZ);
+
+        assert!((position.x - 2.0).abs() < 1e-4);
+        assert!((position.y + 1.0).abs() < 1e-4);
+        assert!((position.z - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn translate_atoms_wrap_dimensions() {
+        let shift = Vector3D::new(5.0, 0.0, 6.0);
+
+        let mut wrapped = system_with_outlier();
+        translate_atoms(&mut wrapped, &shift, Dimension::XYZ).unwrap();
+        let mut partial = system_with_outlier();
+        translate_atoms(&mut partial, &shift, Dimension::Z).unwrap();
+        let mut unwrapped = system_with_outlier();
+        translate_atoms(&mut unwrapped, &shift, Dimension::None).unwrap();
+
+        // the outlier is shifted out of the box in x and z
+        let position =
+            |system: &System| system.get_atom(5).unwrap().get_position().unwrap().clone();
+        assert!((position(&wrapped).x - 1.0).abs() < 1e-4);
+        assert!((position(&wrapped).z - 1.0).abs() < 1e-4);
+        assert!((position(&partial).x - 11.0).abs() < 1e-4);
+        assert!((position(&partial).z - 1.0).abs() < 1e-4);
+        assert!((position(&unwrapped).x - 11.0).abs() < 1e-4);
+        assert!((position(&unwrapped).z - 11.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn recenter_box_target() {
+        let mut system = system_with_outlier();
+        let target = Target([Some(0.0), None, Some(2.0)]);
+        place_groups(
+            &mut system,
+            &[(String::from("all"), Dimension::XYZ, None)],
+            Method::BoxCenter,
+            &[],
+            Some(&target),
+            1,
+            Dimension::XYZ,
+        )
+        .unwrap();
+
+        // the box center (5, 5, 5) is moved to the target; unspecified coordinates stay at the box center
+        let position = system.get_atom(5).unwrap().get_position().unwrap();
+        assert!((position.x - 1.0).abs() < 1e-4);
+        assert!((position.y - 5.0).abs() < 1e-4);
+        assert!((position.z - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn recenter_box_without_target() {
+        let mut system = system_with_outlier();
+        center_group(
+            &mut system,
+            "all",
+            Dimension::XYZ,
+            Method::BoxCenter,
+            &[],
+            Dimension::XYZ,
+        )
+        .unwrap();
+
+        let original = system_with_outlier();
+        for (atom, original) in system.atoms_iter().zip(original.atoms_iter()) {
+            assert_eq!(atom.get_position(), original.get_position());
+        }
+    }
+
+    #[test]
+    fn centered_dimensions_union() {
+        let operations = vec![
+            (String::from("Protein"), Dimension::X, None),
+            (String::from("Membrane"), Dimension::Z, None),
+        ];
+        assert_eq!(centered_dimensions(&operations), Dimension::XZ);
+        assert_eq!(centered_dimensions(&[]), Dimension::None);
+    }
+
+    #[test]
+    fn reference_jump_periodic() {
+        let simbox = SimBox::from([10.0, 10.0, 10.0]);
+        let previous = Vector3D::new(9.8, 5.0, 5.0);
+
+        // crossing the periodic boundary is not a jump
+        let current = Vector3D::new(0.1, 5.0, 5.0);
+        assert!((reference_jump(&previous, &current, Dimension::XYZ, &simbox) - 0.3).abs() < 1e-5);
+
+        // displacement along dimensions that are not centered is ignored
+        let current = Vector3D::new(9.8, 8.0, 5.0);
+        assert!(reference_jump(&previous, &current, Dimension::XZ, &simbox).abs() < 1e-5);
+        assert!((reference_jump(&previous, &current, Dimension::Y, &simbox) - 3.0).abs() < 1e-5);
+    }
+
+    /// Create a molecule of three atoms crossing the periodic boundary in x.
+    /// The bond between the second and the third atom is 4 nm long (using the minimum image convention).
+    fn system_with_long_bond() -> System {
+        let positions = [1.0, 9.5, 5.5].map(|x| [x, 5.0, 5.0]);
+        let mut system = system_from_positions("Long bond", &positions, 10.0);
+        system.add_bond(0, 1).unwrap();
+        system.add_bond(1, 2).unwrap();
+        system
+    }
+
+    fn x_positions(system: &System) -> Vec<f32> {
+        system
+            .atoms_iter()
+            .map(|atom| atom.get_position().unwrap().x)
+            .collect()
+    }
+
+    #[test]
+    fn make_molecules_whole_long_bond_followed() {
+        let mut system = system_with_long_bond();
+        make_molecules_whole(&mut system, Some(5.0)).unwrap();
+
+        for (x, expected) in x_positions(&system).into_iter().zip([1.0, -0.5, -4.5]) {
+            assert!((x - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn make_molecules_whole_long_bond_split() {
+        let mut system = system_with_long_bond();
+        make_molecules_whole(&mut system, Some(3.0)).unwrap();
+
+        // the third atom is not joined to the rest of the molecule
+        for (x, expected) in x_positions(&system).into_iter().zip([1.0, -0.5, 5.5]) {
+            assert!((x - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn is_inside_box_boundaries() {
+        let simbox = SimBox::from([10.0, 10.0, 10.0]);
+        assert!(is_inside_box(&Vector3D::new(0.0, 5.0, 10.0), &simbox));
+        assert!(!is_inside_box(&Vector3D::new(-0.01, 5.0, 5.0), &simbox));
+        assert!(!is_inside_box(&Vector3D::new(5.0, 5.0, 10.01), &simbox));
+        assert!(!is_inside_box(&Vector3D::new(f32::NAN, 5.0, 5.0), &simbox));
+    }
+
+    #[test]
+    fn atoms_outside_box_fix() {
+        let mut system = system_with_outlier();
+        system.get_atom_mut(5).unwrap().set_position_x(16.0);
+
+        assert_eq!(atoms_outside_box(&mut system, false).unwrap(), 1);
+        assert_eq!(atoms_outside_box(&mut system, true).unwrap(), 1);
+        assert_eq!(atoms_outside_box(&mut system, false).unwrap(), 0);
+
+        let inside = system.get_atom(0).unwrap().get_position().unwrap().x;
+        assert!((inside - 2.0).abs() < 1e-4);
+        let fixed = system.get_atom(5).unwrap().get_position().unwrap().x;
+        assert!((fixed - 6.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn rescale_box_positions() {
+        let mut system = system_with_outlier();
+        let factors = rescale_box(&mut system, &BoxSize([5.0, 10.0, 20.0])).unwrap();
+        assert_eq!(factors, [0.5, 1.0, 2.0]);
+
+        let simbox = system.get_box().unwrap();
+        assert_eq!([simbox.x, simbox.y, simbox.z], [5.0, 10.0, 20.0]);
+
+        let position = system.get_atom(5).unwrap().get_position().unwrap();
+        assert!((position.x - 3.0).abs() < 1e-4);
+        assert!((position.y - 5.0).abs() < 1e-4);
+        assert!((position.z - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn template_box_max() {
+        let mut system = system_with_outlier();
+        system.set_box(SimBox::from([8.0, 10.0, 6.0]));
+
+        assert_eq!(apply_template_box(&mut system, TemplateBox::Max), 10.0);
+
+        let simbox = system.get_box().unwrap();
+        assert_eq!([simbox.x, simbox.y, simbox.z], [10.0, 10.0, 10.0]);
+
+        let position = system.get_atom(5).unwrap().get_position().unwrap();
+        assert!((position.x - 7.0).abs() < 1e-4);
+        assert!((position.y - 5.0).abs() < 1e-4);
+        assert!((position.z - 7.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn template_box_edge_smaller() {
+        let mut system = system_with_outlier();
+
+        assert_eq!(apply_template_box(&mut system, TemplateBox::Edge(4.0)), 4.0);
+
+        let simbox = system.get_box().unwrap();
+        assert_eq!([simbox.x, simbox.y, simbox.z], [4.0, 4.0, 4.0]);
+
+        // atoms are not wrapped into the smaller box
+        let position = system.get_atom(5).unwrap().get_position().unwrap();
+        assert!((position.x - 3.0).abs() < 1e-4);
+        assert!((position.y - 2.0).abs() < 1e-4);
+        assert!((position.z - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn mirror_twice_identity() {
+        let mut system = system_with_outlier();
+        system
+            .atoms_iter_mut()
+            .for_each(|atom| atom.set_velocity(Vector3D::new(0.1, -0.2, 0.3)));
+        let original = system.get_atoms_copy();
+
+        mirror(&mut system, Dimension::XZ);
+
+        let position = system.get_atom(5).unwrap().get_position().unwrap();
+        assert!((position.x - 4.0).abs() < 1e-4);
+        assert!((position.y - 5.0).abs() < 1e-4);
+        assert!((position.z - 5.0).abs() < 1e-4);
+
+        let velocity = system.get_atom(5).unwrap().get_velocity().unwrap();
+        assert!((velocity.x + 0.1).abs() < 1e-6);
+        assert!((velocity.y + 0.2).abs() < 1e-6);
+        assert!((velocity.z + 0.3).abs() < 1e-6);
+
+        mirror(&mut system, Dimension::XZ);
+
+        for (atom, expected) in system.atoms_iter().zip(original.iter()) {
+            let position = atom.get_position().unwrap();
+            let expected_position = expected.get_position().unwrap();
+            assert!((position.x - expected_position.x).abs() < 1e-5);
+            assert!((position.y - expected_position.y).abs() < 1e-5);
+            assert!((position.z - expected_position.z).abs() < 1e-5);
+            assert_eq!(atom.get_velocity(), expected.get_velocity());
+        }
+    }
+
+    #[test]
+    fn time_shift_offset() {
+        let mut shift = TimeShift {
+            offset: -100.0,
+            reset: false,
+            first: None,
+            step: None,
+        };
+
+        assert_eq!(shift.shift(100.0, 0), 0.0);
+        assert_eq!(shift.shift(300.0, 1), 200.0);
+    }
+
+    #[test]
+    fn time_shift_reset() {
+        let mut shift = TimeShift {
+            offset: 10.0,
+            reset: true,
+            first: None,
+            step: None,
+        };
+
+        assert_eq!(shift.shift(500.0, 0), 10.0);
+        assert_eq!(shift.shift(600.0, 1), 110.0);
+        // the time step is determined from the first two frames only
+        assert_eq!(shift.shift(1000.0, 2), 210.0);
+    }
+
+    #[test]
+    fn chain_ters() {
+        let content = "CRYST1   10.000   10.000   10.000  90.00  90.00  90.00 P 1           1
+MODEL        1
+ATOM      1  BB  ASN A   1       1.000   1.000   1.000  1.00  0.00
+ATOM      2  BB  LEU A   2       1.000   1.000   1.000  1.00  0.00
+ATOM      3  BB  ASN B   3       1.000   1.000   1.000  1.00  0.00
+ATOM      4  W     W     4       1.000   1.000   1.000  1.00  0.00
+ATOM      5  W     W     5       1.000   1.000   1.000  1.00  0.00
+ATOM      6  BB  ASN C   6       1.000   1.000   1.000  1.00  0.00
+TER
+ENDMDL
+END
+";
+
+        let expected = "CRYST1   10.000   10.000   10.000  90.00  90.00  90.00 P 1           1
+MODEL        1
+ATOM      1  BB  ASN A   1       1.000   1.000   1.000  1.00  0.00
+ATOM      2  BB  LEU A   2       1.000   1.000   1.000  1.00  0.00
+TER
+ATOM      3  BB  ASN B   3       1.000   1.000   1.000  1.00  0.00
+TER
+ATOM      4  W     W     4       1.000   1.000   1.000  1.00  0.00
+ATOM      5  W     W     5       1.000   1.000   1.000  1.00  0.00
+ATOM      6  BB  ASN C   6       1.000   1.000   1.000  1.00  0.00
+TER
+ENDMDL
+END
+";
+
+        assert_eq!(add_chain_ters(content), expected);
+    }
+
+    #[test]
+    fn chunk_paths() {
+        assert_eq!(chunk_path("output.xtc", 1), "output.0001.xtc");
+        assert_eq!(chunk_path("dir.d/output.xtc", 12), "dir.d/output.0012.xtc");
+        assert_eq!(
+            chunk_path("output.centered.gro", 3),
+            "output.centered.0003.gro"
+        );
+        assert_eq!(chunk_path("output", 10000), "output.10000");
+    }
+
+    #[test]
+    fn title_gro() {
+        let content = "Membrane\n    1\n    1ASN     BB    1   1.000   1.000   1.000\n   2.00000   2.00000   2.00000\n";
+        let expected = "Centered membrane\n    1\n    1ASN     BB    1   1.000   1.000   1.000\n   2.00000   2.00000   2.00000\n";
+
+        assert_eq!(
+            replace_title(content, FileType::GRO, "Centered membrane"),
+            expected
+        );
+    }
+
+    #[test]
+    fn title_pdb() {
+        let content = "TITLE     Membrane
+REMARK    THIS IS A SIMULATION BOX
+ATOM      1  BB  ASN A   1       1.000   1.000   1.000  1.00  0.00
+END
+";
+
+        let expected = "TITLE     Centered membrane
+REMARK    THIS IS A SIMULATION BOX
+ATOM      1  BB  ASN A   1       1.000   1.000   1.000  1.00  0.00
+END
+";
+
+        assert_eq!(
+            replace_title(content, FileType::PDB, "Centered membrane"),
+            expected
+        );
+    }
+
+    #[test]
+    fn title_pqr_missing() {
+        let content = "ATOM      1  BB  ASN A   1       1.000   1.000   1.000  0.000 1.0000
+END
+";
+
+        let expected = "TITLE     Centered membrane
+ATOM      1  BB  ASN A   1       1.000   1.000   1.000  0.000 1.0000
+END
+";
+
+        assert_eq!(
+            replace_title(content, FileType::PQR, "Centered membrane"),
+            expected
+        );
+    }
+}
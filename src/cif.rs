@@ -0,0 +1,452 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of a minimal reader of PDBx/mmCIF structure files.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use groan_rs::structures::atom::Atom;
+use groan_rs::structures::simbox::SimBox;
+use groan_rs::structures::vector3d::Vector3D;
+use groan_rs::system::System;
+
+use crate::errors::RunError;
+
+/// Atomic masses (in daltons) of elements commonly found in mmCIF files.
+/// Masses of atoms of other elements are guessed, if needed.
+const MASSES: [(&str, f32); 14] = [
+    ("H", 1.0079),
+    ("C", 12.0107),
+    ("N", 14.0067),
+    ("O", 15.9994),
+    ("P", 30.9738),
+    ("S", 32.065),
+    ("SE", 78.96),
+    ("NA", 22.9898),
+    ("K", 39.0983),
+    ("CL", 35.453),
+    ("MG", 24.305),
+    ("CA", 40.078),
+    ("ZN", 65.38),
+    ("FE", 55.845),
+];
+
+/// Check whether the file is a PDBx/mmCIF file based on its extension.
+pub fn is_cif(filename: impl AsRef<Path>) -> bool {
+    filename
+        .as_ref()
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cif"))
+}
+
+/// Single token of an mmCIF file.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    value: String,
+    /// Quoted values are never interpreted as tags or keywords.
+    quoted: bool,
+}
+
+impl Token {
+    fn is_tag(&self) -> bool {
+        !self.quoted && self.value.starts_with('_')
+    }
+
+    fn is_keyword(&self) -> bool {
+        !self.quoted
+            && (self.value.eq_ignore_ascii_case("loop_")
+                || self.value.to_ascii_lowercase().starts_with("data_"))
+    }
+}
+
+/// Split a single line into tokens, handling quoted values and comments.
+fn tokenize_line(line: &str, tokens: &mut Vec<Token>) {
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if chars[i] == '#' {
+            return;
+        }
+
+        if chars[i] == '\'' || chars[i] == '"' {
+            // the closing quote must be followed by whitespace or the end of the line
+            let quote = chars[i];
+            let start = i + 1;
+            let mut end = start;
+            while end < chars.len() {
+                let at_boundary = match chars.get(end + 1) {
+                    Some(c) => c.is_whitespace(),
+                    None => true,
+                };
+
+                if chars[end] == quote && at_boundary {
+                    break;
+                }
+
+                end += 1;
+            }
+
+            tokens.push(Token {
+                value: chars[start..end.min(chars.len())].iter().collect(),
+                quoted: true,
+            });
+            i = end + 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+
+        tokens.push(Token {
+            value: chars[start..i].iter().collect(),
+            quoted: false,
+        });
+    }
+}
+
+/// Split the content of an mmCIF file into tokens.
+fn tokenize(content: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        // multi-line text field delimited by semicolons
+        if let Some(first) = line.strip_prefix(';') {
+            let mut text = first.to_owned();
+            for next in lines.by_ref() {
+                if next.starts_with(';') {
+                    break;
+                }
+                text.push('\n');
+                text.push_str(next);
+            }
+
+            tokens.push(Token {
+                value: text,
+                quoted: true,
+            });
+            continue;
+        }
+
+        tokenize_line(line, &mut tokens);
+    }
+
+    tokens
+}
+
+/// Content of an mmCIF file relevant for constructing the system.
+#[derive(Debug, Default)]
+struct CifData {
+    name: Option<String>,
+    /// Values of non-looped items.
+    items: HashMap<String, String>,
+    /// Tags of the `_atom_site` loop.
+    atom_tags: Vec<String>,
+    /// Values of the `_atom_site` loop.
+    atom_values: Vec<String>,
+}
+
+/// Collect data items and the `_atom_site` loop from the tokens.
+/// Only the first data block is read.
+fn parse_tokens(tokens: Vec<Token>) -> Result<CifData, String> {
+    let mut data = CifData::default();
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        if token.is_keyword() && token.value.to_ascii_lowercase().starts_with("data_") {
+            if data.name.is_some() {
+                break;
+            }
+            data.name = Some(token.value[5..].to_owned());
+        } else if token.is_keyword() {
+            let mut tags = Vec::new();
+            while let Some(tag) = tokens.next_if(|t| t.is_tag()) {
+                tags.push(tag.value.to_ascii_lowercase());
+            }
+
+            let mut values = Vec::new();
+            while let Some(value) = tokens.next_if(|t| !t.is_tag() && !t.is_keyword()) {
+                values.push(value.value);
+            }
+
+            if tags.first().is_some_and(|t| t.starts_with("_atom_site.")) {
+                if values.len() % tags.len() != 0 {
+                    return Err(String::from(
+                        "invalid number of values in the atom_site loop",
+                    ));
+                }
+
+                data.atom_tags = tags;
+                data.atom_values = values;
+            }
+        } else if token.is_tag() {
+            match tokens.next_if(|t| !t.is_tag() && !t.is_keyword()) {
+                Some(value) => {
+                    data.items
+                        .insert(token.value.to_ascii_lowercase(), value.value);
+                }
+                None => return Err(format!("missing value for item '{}'", token.value)),
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+/// Check whether the value is missing ('.' or '?').
+fn is_missing(value: &str) -> bool {
+    value == "." || value == "?"
+}
+
+/// Construct the simulation box from the `_cell` items. Lengths are converted from Å to nm.
+fn parse_box(items: &HashMap<String, String>) -> Result<Option<SimBox>, String> {
+    let get = |name: &str, default: Option<f32>| -> Result<Option<f32>, String> {
+        match items.get(name) {
+            None => Ok(default),
+            Some(x) if is_missing(x) => Ok(default),
+            Some(x) => x
+                .parse::<f32>()
+                .map(Some)
+                .map_err(|_| format!("could not parse '{}' as '{}'", x, name)),
+        }
+    };
+
+    let lengths = [
+        get("_cell.length_a", None)?,
+        get("_cell.length_b", None)?,
+        get("_cell.length_c", None)?,
+    ];
+    let angles = [
+        get("_cell.angle_alpha", Some(90.0))?,
+        get("_cell.angle_beta", Some(90.0))?,
+        get("_cell.angle_gamma", Some(90.0))?,
+    ];
+
+    match (lengths, angles) {
+        ([Some(a), Some(b), Some(c)], [Some(alpha), Some(beta), Some(gamma)]) => {
+            Ok(Some(SimBox::from_lengths_angles(
+                Vector3D::new(a / 10.0, b / 10.0, c / 10.0),
+                Vector3D::new(alpha, beta, gamma),
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Construct atoms from the `_atom_site` loop. Only atoms of the first model are read.
+fn parse_atoms(data: &CifData) -> Result<Vec<Atom>, String> {
+    let column = |names: &[&str]| -> Option<usize> {
+        names.iter().find_map(|name| {
+            data.atom_tags
+                .iter()
+                .position(|tag| tag == &format!("_atom_site.{}", name))
+        })
+    };
+
+    let required = |names: &[&str]| -> Result<usize, String> {
+        column(names).ok_or_else(|| format!("missing column '_atom_site.{}'", names[0]))
+    };
+
+    let atom_name = required(&["auth_atom_id", "label_atom_id"])?;
+    let residue_name = required(&["auth_comp_id", "label_comp_id"])?;
+    let x = required(&["cartn_x"])?;
+    let y = required(&["cartn_y"])?;
+    let z = required(&["cartn_z"])?;
+    let atom_number = column(&["id"]);
+    let residue_numbers = [column(&["auth_seq_id"]), column(&["label_seq_id"])];
+    let chain = column(&["auth_asym_id", "label_asym_id"]);
+    let symbol = column(&["type_symbol"]);
+    let model = column(&["pdbx_pdb_model_num"]);
+
+    let mut atoms = Vec::new();
+    let mut first_model = None;
+
+    for (i, row) in data.atom_values.chunks(data.atom_tags.len()).enumerate() {
+        if let Some(m) = model {
+            match &first_model {
+                None => first_model = Some(row[m].clone()),
+                Some(first) if first != &row[m] => break,
+                Some(_) => (),
+            }
+        }
+
+        let coordinate = |c: usize| -> Result<f32, String> {
+            row[c]
+                .parse::<f32>()
+                .map(|v| v / 10.0)
+                .map_err(|_| format!("could not parse coordinate '{}'", row[c]))
+        };
+
+        let resnum = residue_numbers
+            .iter()
+            .flatten()
+            .find_map(|&c| row[c].parse::<usize>().ok())
+            .ok_or_else(|| format!("could not parse residue number of atom {}", i + 1))?;
+
+        let atomnum = atom_number
+            .and_then(|c| row[c].parse::<usize>().ok())
+            .unwrap_or(i + 1);
+
+        let mut atom =
+            Atom::new(resnum, &row[residue_name], atomnum, &row[atom_name]).with_position(
+                Vector3D::new(coordinate(x)?, coordinate(y)?, coordinate(z)?),
+            );
+
+        if let Some(c) = chain
+            .map(|c| &row[c])
+            .filter(|c| !is_missing(c))
+            .and_then(|c| c.chars().next())
+        {
+            atom.set_chain(c);
+        }
+
+        if let Some(s) = symbol.map(|c| &row[c]).filter(|s| !is_missing(s)) {
+            atom.set_element_symbol(s);
+
+            if let Some((_, mass)) = MASSES
+                .iter()
+                .find(|(element, _)| element.eq_ignore_ascii_case(s))
+            {
+                atom.set_mass(*mass);
+            }
+        }
+
+        atoms.push(atom);
+    }
+
+    Ok(atoms)
+}
+
+/// Read a PDBx/mmCIF file and construct a system from it.
+///
+/// ## Notes
+/// - Only the `_atom_site` loop and the `_cell` items of the first data block are read.
+/// - Only atoms of the first model are read.
+/// - Masses are assigned to atoms of common elements based on the `type_symbol` column.
+pub fn read_cif(filename: &str) -> Result<System, RunError> {
+    let content = std::fs::read_to_string(filename).map_err(|_| {
+        RunError::CifInvalid(filename.to_owned(), String::from("file could not be read"))
+    })?;
+
+    let error = |e: String| RunError::CifInvalid(filename.to_owned(), e);
+
+    let data = parse_tokens(tokenize(&content)).map_err(error)?;
+    if data.atom_tags.is_empty() {
+        return Err(error(String::from("no atom_site loop found")));
+    }
+
+    let atoms = parse_atoms(&data).map_err(error)?;
+    let simbox = parse_box(&data.items).map_err(error)?;
+
+    let name = data
+        .items
+        .get("_struct.title")
+        .filter(|x| !is_missing(x))
+        .or(data.name.as_ref())
+        .cloned()
+        .unwrap_or_else(|| String::from("Unknown"));
+
+    Ok(System::new(&name, atoms, simbox))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tokenize_quoted() {
+        let tokens = tokenize("ATOM \"O5'\" 'it''s' x # comment\n;multi\nline\n;\n_tag");
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+
+        assert_eq!(values, ["ATOM", "O5'", "it''s", "x", "multi\nline", "_tag"]);
+        assert!(!tokens[0].quoted);
+        assert!(tokens[1].quoted);
+        assert!(tokens[4].quoted);
+        assert!(tokens[5].is_tag());
+    }
+
+    #[test]
+    fn is_cif_extension() {
+        assert!(is_cif("structure.cif"));
+        assert!(is_cif("structure.CIF"));
+        assert!(!is_cif("structure.pdb"));
+        assert!(!is_cif("cif"));
+    }
+
+    #[test]
+    fn read_cif_peptide() {
+        let system = read_cif("tests/test_files/input_aa_peptide.cif").unwrap();
+        let expected = System::from_file("tests/test_files/input_aa_peptide.gro").unwrap();
+
+        assert_eq!(system.get_name(), "AA peptide in a large box");
+        assert_eq!(system.get_n_atoms(), expected.get_n_atoms());
+
+        for (atom, expected_atom) in system.atoms_iter().zip(expected.atoms_iter()) {
+            assert_eq!(atom.get_atom_name(), expected_atom.get_atom_name());
+            assert_eq!(atom.get_residue_name(), expected_atom.get_residue_name());
+            assert_eq!(
+                atom.get_residue_number(),
+                expected_atom.get_residue_number()
+            );
+            assert_eq!(atom.get_chain(), Some('A'));
+            assert!(atom.get_mass().is_some());
+
+            let pos = atom.get_position().unwrap();
+            let expected_pos = expected_atom.get_position().unwrap();
+            assert!((pos.x - expected_pos.x).abs() < 0.0001);
+            assert!((pos.y - expected_pos.y).abs() < 0.0001);
+            assert!((pos.z - expected_pos.z).abs() < 0.0001);
+        }
+
+        let simbox = system.get_box().unwrap();
+        assert!(simbox.is_orthogonal());
+        assert!((simbox.x - 8.36432).abs() < 0.0001);
+        assert!((simbox.y - 8.68132).abs() < 0.0001);
+        assert!((simbox.z - 10.41583).abs() < 0.0001);
+    }
+
+    #[test]
+    fn read_cif_first_model_only() {
+        let content = "data_TEST
+loop_
+_atom_site.id
+_atom_site.label_atom_id
+_atom_site.label_comp_id
+_atom_site.label_seq_id
+_atom_site.Cartn_x
+_atom_site.Cartn_y
+_atom_site.Cartn_z
+_atom_site.pdbx_PDB_model_num
+1 N ALA 1 1.0 2.0 3.0 1
+2 CA ALA 1 2.0 3.0 4.0 1
+1 N ALA 1 5.0 6.0 7.0 2
+";
+        let data = parse_tokens(tokenize(content)).unwrap();
+        let atoms = parse_atoms(&data).unwrap();
+
+        assert_eq!(atoms.len(), 2);
+        assert_eq!(atoms[1].get_atom_name(), "CA");
+        assert!(atoms[0].get_mass().is_none());
+        assert!(parse_box(&data.items).unwrap().is_none());
+    }
+
+    #[test]
+    fn read_cif_invalid() {
+        let content = "data_TEST
+loop_
+_atom_site.id
+_atom_site.label_atom_id
+1 N 2
+";
+        assert!(parse_tokens(tokenize(content)).is_err());
+    }
+}
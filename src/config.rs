@@ -0,0 +1,324 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of reading default values of options from a TOML configuration file.
+
+use std::path::Path;
+
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::Deserialize;
+
+use crate::argparse::{parse_dimension, Args};
+use crate::errors::RunError;
+
+/// Name of the configuration file which is read from the current working directory if `config` is not provided.
+pub const DEFAULT_CONFIG: &str = "gcenter.toml";
+
+/// Default values of options read from a configuration file.
+/// Keys of the file correspond to the long names of the options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    reference: Option<String>,
+    index: Option<String>,
+    xref: Option<String>,
+    yref: Option<String>,
+    zref: Option<String>,
+    dim: Option<String>,
+    com: Option<bool>,
+    median: Option<bool>,
+    plane_fit: Option<bool>,
+    by_residue_com: Option<bool>,
+    bbox_center: Option<bool>,
+    elements: Option<String>,
+    center_iterations: Option<usize>,
+    whole: Option<bool>,
+    quiet: Option<bool>,
+    overwrite: Option<bool>,
+    backup_dir: Option<String>,
+}
+
+/// Check whether any of the options has been explicitly provided on the command line.
+fn on_command_line(matches: &ArgMatches, ids: &[&str]) -> bool {
+    ids.iter()
+        .any(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+}
+
+/// Report an error if more than one of the mutually exclusive options is enabled in the configuration file.
+fn check_exclusive(file: &str, options: &[(&str, Option<bool>)]) -> Result<(), RunError> {
+    let enabled: Vec<&str> = options
+        .iter()
+        .filter(|(_, value)| value.unwrap_or(false))
+        .map(|(name, _)| *name)
+        .collect();
+
+    if enabled.len() > 1 {
+        return Err(RunError::ConfigConflict(
+            file.to_owned(),
+            enabled[0].to_owned(),
+            enabled[1].to_owned(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Read the configuration file.
+fn read_config(file: &str) -> Result<Config, RunError> {
+    let content =
+        std::fs::read_to_string(file).map_err(|_| RunError::ConfigNotRead(file.to_owned()))?;
+
+    toml::from_str(&content)
+        .map_err(|e| RunError::ConfigInvalid(file.to_owned(), e.message().to_owned()))
+}
+
+/// Set the options that have not been provided on the command line to the values from the configuration file.
+/// Related options (e.g., all options selecting the centering method) are taken from the configuration file
+/// only if none of them has been provided on the command line.
+fn merge(
+    args: &mut Args,
+    matches: &ArgMatches,
+    config: Config,
+    file: &str,
+) -> Result<(), RunError> {
+    if !on_command_line(
+        matches,
+        &[
+            "reference",
+            "reference_serials",
+            "reference_words",
+            "reference_groups",
+        ],
+    ) {
+        if let Some(reference) = config.reference {
+            args.reference = reference;
+        }
+    }
+
+    if !on_command_line(matches, &["index"]) && config.index.is_some() {
+        args.index = config.index;
+    }
+
+    if !on_command_line(
+        matches,
+        &[
+            "xreference",
+            "yreference",
+            "zreference",
+            "invert_selection",
+            "align_to",
+        ],
+    ) {
+        for (target, value) in [
+            (&mut args.xreference, config.xref),
+            (&mut args.yreference, config.yref),
+            (&mut args.zreference, config.zref),
+        ] {
+            if value.is_some() {
+                *target = value;
+            }
+        }
+    }
+
+    if !on_command_line(
+        matches,
+        &["xdimension", "ydimension", "zdimension", "dimensions"],
+    ) {
+        if let Some(dim) = config.dim {
+            let dimension = parse_dimension(&dim).map_err(|e| {
+                RunError::ConfigInvalid(file.to_owned(), format!("invalid value for 'dim': {}", e))
+            })?;
+            args.dimensions = Some(dimension);
+        }
+    }
+
+    if !on_command_line(
+        matches,
+        &[
+            "com",
+            "median",
+            "plane_fit",
+            "by_residue_com",
+            "bbox_center",
+        ],
+    ) {
+        check_exclusive(
+            file,
+            &[
+                ("com", config.com),
+                ("median", config.median),
+                ("plane-fit", config.plane_fit),
+                ("by-residue-com", config.by_residue_com),
+                ("bbox-center", config.bbox_center),
+            ],
+        )?;
+
+        args.com = config.com.unwrap_or(args.com);
+        args.median = config.median.unwrap_or(args.median);
+        args.plane_fit = config.plane_fit.unwrap_or(args.plane_fit);
+        args.by_residue_com = config.by_residue_com.unwrap_or(args.by_residue_com);
+        args.bbox_center = config.bbox_center.unwrap_or(args.bbox_center);
+    }
+
+    if !on_command_line(matches, &["elements"]) && config.elements.is_some() {
+        args.elements = config.elements;
+    }
+
+    if !on_command_line(matches, &["center_iterations"]) {
+        args.center_iterations = config.center_iterations.unwrap_or(args.center_iterations);
+    }
+
+    if !on_command_line(matches, &["whole", "transform"]) {
+        args.whole = config.whole.unwrap_or(args.whole);
+    }
+
+    if !on_command_line(matches, &["silent", "quiet", "verbose"]) {
+        args.quiet = config.quiet.unwrap_or(args.quiet);
+    }
+
+    if !on_command_line(matches, &["overwrite", "backup_dir"]) {
+        if config.overwrite.unwrap_or(false) && config.backup_dir.is_some() {
+            return Err(RunError::ConfigConflict(
+                file.to_owned(),
+                String::from("overwrite"),
+                String::from("backup-dir"),
+            ));
+        }
+
+        args.overwrite = config.overwrite.unwrap_or(args.overwrite);
+        if config.backup_dir.is_some() {
+            args.backup_dir = config.backup_dir;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the configuration file provided using `config` or the default configuration file, if it exists,
+/// and use it to set the options that have not been provided on the command line.
+pub fn apply_config(args: &mut Args, matches: &ArgMatches) -> Result<(), RunError> {
+    let file = match &args.config {
+        Some(file) => {
+            if !Path::new(file).exists() {
+                return Err(RunError::ConfigNotFound(file.to_owned()));
+            }
+            file.to_owned()
+        }
+        None if Path::new(DEFAULT_CONFIG).is_file() => {
+            args.config = Some(DEFAULT_CONFIG.to_owned());
+            DEFAULT_CONFIG.to_owned()
+        }
+        None => return Ok(()),
+    };
+
+    let config = read_config(&file)?;
+    merge(args, matches, config, &file)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+    use clap::FromArgMatches;
+    use groan_rs::structures::dimension::Dimension;
+
+    fn merged(cli: &[&str], config: &str) -> Result<Args, RunError> {
+        let base = ["gcenter", "-s=tests/test_files/input.gro", "-o=output.gro"];
+        let matches = Args::command().get_matches_from(base.iter().chain(cli.iter()));
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        let config: Config = toml::from_str(config).unwrap();
+        merge(&mut args, &matches, config, "gcenter.toml")?;
+        Ok(args)
+    }
+
+    #[test]
+    fn config_sets_defaults() {
+        let args = merged(
+            &[],
+            "reference = \"Membrane\"\ncom = true\ndim = \"xy\"\ncenter-iterations = 3",
+        )
+        .unwrap();
+
+        assert_eq!(args.reference, "Membrane");
+        assert!(args.com);
+        assert_eq!(args.dimensions, Some(Dimension::XY));
+        assert_eq!(args.center_iterations, 3);
+    }
+
+    #[test]
+    fn command_line_overrides_config() {
+        let args = merged(
+            &["-r=Water", "--median", "-z"],
+            "reference = \"Membrane\"\ncom = true\ndim = \"xy\"",
+        )
+        .unwrap();
+
+        assert_eq!(args.reference, "Water");
+        assert!(!args.com);
+        assert!(args.median);
+        assert!(args.zdimension);
+        assert_eq!(args.dimensions, None);
+    }
+
+    #[test]
+    fn config_conflicting_options() {
+        assert_eq!(
+            merged(&[], "com = true\nmedian = true").unwrap_err(),
+            RunError::ConfigConflict(
+                String::from("gcenter.toml"),
+                String::from("com"),
+                String::from("median")
+            )
+        );
+
+        assert!(merged(&["--plane-fit"], "com = true\nmedian = true").is_ok());
+    }
+
+    #[test]
+    fn config_unknown_key() {
+        assert!(toml::from_str::<Config>("referenc = \"Protein\"").is_err());
+    }
+
+    #[test]
+    fn config_documented_keys() {
+        // keys listed in the help of `--config` and in README
+        let keys = [
+            ("reference", "\"Membrane\""),
+            ("index", "\"index.ndx\""),
+            ("xref", "\"Protein\""),
+            ("yref", "\"Protein\""),
+            ("zref", "\"Membrane\""),
+            ("dim", "\"xy\""),
+            ("com", "true"),
+            ("median", "false"),
+            ("plane-fit", "false"),
+            ("by-residue-com", "false"),
+            ("bbox-center", "false"),
+            ("elements", "\"elements.yaml\""),
+            ("center-iterations", "2"),
+            ("whole", "true"),
+            ("quiet", "true"),
+            ("overwrite", "true"),
+            ("backup-dir", "\"backups\""),
+        ];
+
+        let command = Args::command();
+        for (key, _) in keys {
+            assert!(
+                command
+                    .get_arguments()
+                    .any(|arg| arg.get_long() == Some(key)),
+                "'{}' is not a long name of an option",
+                key
+            );
+        }
+
+        let content = keys
+            .iter()
+            .map(|(key, value)| format!("{} = {}", key, value))
+            .collect::<Vec<String>>()
+            .join("\n");
+        assert!(toml::from_str::<Config>(&content).is_ok());
+    }
+}
@@ -3,6 +3,8 @@
 
 //! Implementation of errors originating from the `gcenter` program.
 
+use std::fmt::Display;
+
 use colored::Colorize;
 use thiserror::Error;
 
@@ -17,10 +19,16 @@ pub enum RunError {
     IdenticalInputFiles(String, String),
     #[error("{} invalid value '{}' for '{}': input structure file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--structure <STRUCTURE>".bold(), "--help".bold())]
     InputStructureNotFound(String),
+    #[error("{} the following required argument was not provided: '{}'; the structure file can only be omitted if the trajectory is a single gro file\n\nFor more information, try '{}'.", "error:".red().bold(), "--structure <STRUCTURE>".bold(), "--help".bold())]
+    StructureRequired,
+    #[error("{} invalid value '{}' for '{}': input coordinates file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--coordinates <COORDINATES>".bold(), "--help".bold())]
+    InputCoordinatesNotFound(String),
     #[error("{} invalid value '{}' for '{}': input trajectory file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--trajectory [<TRAJECTORIES>...]".bold(), "--help".bold())]
     InputTrajectoryNotFound(String),
     #[error("{} invalid value '{}' for '{}': query contains `molecule with` keyword; this is only supported if a tpr file is provided\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), .1.bold(), "--help".bold())]
     UnsupportedQuery(String, String),
+    #[error("{} invalid value '{}' for '{}': centering cannot be skipped if '{}' is provided\n\nFor more information, try '{}'.", "error:".red().bold(), "none".yellow(), "--reference <REFERENCE>".bold(), .0.bold(), "--help".bold())]
+    NoReferenceWithDimensionReference(String),
     #[error("{} invalid values '{}' and '{}' for '{}': all trajectory files must have the same file format\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), .1.to_string().yellow(), "--trajectory [<TRAJECTORIES>...]".bold(), "--help".bold())]
     InconsistentTrajectoryFiles(String, String),
     #[error("{} invalid value '{}' for '{}': concatenation of gro trajectories is not supported\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--trajectory [<TRAJECTORIES>...]".bold(), "--help".bold())]
@@ -29,12 +37,150 @@ pub enum RunError {
     BeginNotSupportedForGro(String),
     #[error("{} invalid value '{}' for '{}': gro trajectories are not guaranteed to contain information about simulation time, therefore `end_time` cannot be specified\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--end <END_TIME>".bold(), "--help".bold())]
     EndNotSupportedForGro(String),
+    #[error("{} invalid value '{}' for '{}': gro trajectories are not guaranteed to contain information about simulation time, therefore `every` cannot be specified\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--every <DT>".bold(), "--help".bold())]
+    EveryNotSupportedForGro(String),
+    #[error("{} gro trajectories are not guaranteed to contain information about simulation step and time, therefore '{}' cannot be used\n\nFor more information, try '{}'.", "error:".red().bold(), "--dedup".bold(), "--help".bold())]
+    DedupNotSupportedForGro,
+    #[error("{} invalid value '{}' for '{}': gro trajectories do not contain information about simulation steps\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), .1.bold(), "--help".bold())]
+    StepRangeNotSupportedForGro(String, String),
+    #[error("{} invalid value '{}' for '{}': B-factors can only be used as weights for a pdb input structure file\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--weights-from-bfactor".bold(), "--help".bold())]
+    BfactorsRequirePdb(String),
+    #[error("{} invalid value '{}' for '{}': gro trajectories are not guaranteed to contain information about simulation time, therefore `last` cannot be specified\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--last <LAST>".bold(), "--help".bold())]
+    LastNotSupportedForGro(String),
+    #[error("{} invalid value '{}' for '{}': frame times file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--frame-times <FILE>".bold(), "--help".bold())]
+    FrameTimesNotFound(String),
+    #[error("{} invalid value '{}' for '{}': frame times can only be assigned to a gro trajectory\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--trajectory [<TRAJECTORIES>...]".bold(), "--help".bold())]
+    FrameTimesRequireGro(String),
+    #[error("{} could not read frame times file '{}'\n", "error:".red().bold(), .0.yellow())]
+    FrameTimesNotRead(String),
+    #[error("{} could not parse line {} of frame times file '{}': expected time in ps\n", "error:".red().bold(), .1.to_string().yellow(), .0.yellow())]
+    FrameTimesInvalid(String, usize),
+    #[error("{} frame times file '{}' lists {} time(s) but trajectory '{}' contains {} frame(s)\n", "error:".red().bold(), .0.yellow(), .1.to_string().yellow(), .2.yellow(), .3.to_string().yellow())]
+    FrameTimesTooShort(String, usize, String, usize),
+    #[error("{} could not determine the time of the last frame of trajectory '{}'", "error:".red().bold(), .0.yellow())]
+    LastTimeNotRead(String),
+    #[error("{} invalid value '{}' for '{}': at least one frame must be written\n\nFor more information, try '{}'.", "error:".red().bold(), "0".yellow(), "--max-frames <MAX_FRAMES>".bold(), "--help".bold())]
+    ZeroMaxFrames,
+    #[error("{} invalid value '{}' for '{}': each output file must contain at least one frame\n\nFor more information, try '{}'.", "error:".red().bold(), "0".yellow(), "--max-output-frames <N>".bold(), "--help".bold())]
+    ZeroMaxOutputFrames,
+    #[error("{} invalid value '{}' for '{}': progress must be updated at least every frame\n\nFor more information, try '{}'.", "error:".red().bold(), "0".yellow(), "--progress-interval <N>".bold(), "--help".bold())]
+    ZeroProgressInterval,
+    #[error("{} invalid value '{}' for '{}': path matches an input or output file\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--report-drift <REPORT_DRIFT>".bold(), "--help".bold())]
+    DriftFileMatch(String),
+    #[error("{} invalid value '{}' for '{}': path matches an input or output file\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--report-box <REPORT_BOX>".bold(), "--help".bold())]
+    BoxReportFileMatch(String),
+    #[error("{} invalid value '{}' for '{}': path matches an input or output file\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--stats-jsonl <FILE>".bold(), "--help".bold())]
+    StatsFileMatch(String),
+    #[error("{} invalid value '{}' for '{}': at least one iteration must be performed\n\nFor more information, try '{}'.", "error:".red().bold(), "0".yellow(), "--center-iterations <CENTER_ITERATIONS>".bold(), "--help".bold())]
+    ZeroCenterIterations,
+    #[error("{} invalid value '{}' for '{}': precision must be positive\n\nFor more information, try '{}'.", "error:".red().bold(), "0".yellow(), "--output-precision <PRECISION>".bold(), "--help".bold())]
+    ZeroOutputPrecision,
+    #[error("{} invalid value '{}' for '{}': expected file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--compare <EXPECTED>".bold(), "--help".bold())]
+    CompareNotFound(String),
+    #[error("{} invalid value '{}' for '{}': path matches the output file\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--compare <EXPECTED>".bold(), "--help".bold())]
+    CompareMatchesOutput(String),
+    #[error("{} could not read file '{}' for comparison\n", "error:".red().bold(), .0.yellow())]
+    CompareNotRead(String),
+    #[error("{} output file '{}' differs from the expected file '{}' (first difference at byte {})\n", "error:".red().bold(), .0.yellow(), .1.yellow(), .2.to_string().yellow())]
+    OutputDiffers(String, String, usize),
     #[error("{} invalid argument '{}': this option is only supported when a tpr file is provided\n\nFor more information, try '{}'.", "error:".red().bold(), "--whole".bold(), "--help".bold())]
     WholeRequiresTprFile,
+    #[error("{} invalid value '{}' for '{}': this operation is only supported when a tpr file is provided\n\nFor more information, try '{}'.", "error:".red().bold(), "whole".yellow(), "--transform <STEPS>".bold(), "--help".bold())]
+    TransformWholeRequiresTprFile,
+    #[error("{} invalid argument '{}': molecules are not made whole; use '{}' or the 'whole' operation of '{}'\n\nFor more information, try '{}'.", "error:".red().bold(), "--whole-threshold <NM>".bold(), "--whole".bold(), "--transform".bold(), "--help".bold())]
+    WholeThresholdWithoutWhole,
+    #[error("{} number of atoms in the coordinates file '{}' ({}) does not match the number of atoms in the structure file ({})\n", "error:".red().bold(), .0.yellow(), .1.to_string().yellow(), .2.to_string().yellow())]
+    CoordinatesAtomsMismatch(String, usize, usize),
+    #[error("{} could not read mmCIF file '{}': {}\n", "error:".red().bold(), .0.yellow(), .1)]
+    CifInvalid(String, String),
+    #[error("{} could not read g96 file '{}': {}\n", "error:".red().bold(), .0.yellow(), .1)]
+    G96Invalid(String, String),
+    #[error("{} could not write g96 file '{}'\n", "error:".red().bold(), .0.yellow())]
+    G96NotWritten(String),
+    #[error("{} could not read masses file '{}': {}\n", "error:".red().bold(), .0.yellow(), .1)]
+    MassesInvalid(String, String),
+    #[error("{} could not use B-factors from '{}' as weights: {}\n", "error:".red().bold(), .0.yellow(), .1)]
+    BfactorsInvalid(String, String),
+    #[error("{} {} atom(s) of the reference group '{}' have no mass assigned; center of mass cannot be calculated\n", "error:".red().bold(), .1.to_string().yellow(), .0.yellow())]
+    ReferenceWithoutMass(String, usize),
+    #[error("{} masses of atoms are not available in '{}' and '{}' forbids guessing them; use a tpr file or provide the masses using '{}'\n", "error:".red().bold(), .0.yellow(), "--require-masses".bold(), "--masses <FILE>".bold())]
+    MassesRequired(String),
+    #[error("{} number of atoms in the trajectory file '{}' ({}) does not match the number of atoms in the structure file ({})\n", "error:".red().bold(), .0.yellow(), .1.to_string().yellow(), .2.to_string().yellow())]
+    AtomCountMismatch(String, usize, usize),
+    #[error("{} group '{}' in the ndx file '{}' references atom '{}' but the system only contains {} atoms; is the ndx file associated with this system?\n", "error:".red().bold(), .0.yellow(), .1.yellow(), .2.to_string().yellow(), .3.to_string().yellow())]
+    IndexOutOfRange(String, String, usize, usize),
+    #[error("{} could not create backup directory '{}'\n", "error:".red().bold(), .0.yellow())]
+    BackupDirNotCreated(String),
+    #[error("{} could not move '{}' into backup directory '{}'\n", "error:".red().bold(), .0.yellow(), .1.yellow())]
+    BackupFailed(String, String),
+    #[error("{} could not remove backup '{}'\n", "error:".red().bold(), .0.yellow())]
+    BackupNotRemoved(String),
+    #[error("{} number of written frames ({}) does not match the number of read frames ({}); the output trajectory may be incomplete\n", "error:".red().bold(), .1.to_string().yellow(), .0.to_string().yellow())]
+    FrameCountMismatch(usize, usize),
+    #[error("{} centering interrupted; {} frame(s) salvaged in '{}'\n", "error:".red().bold(), .0.to_string().yellow(), .1.yellow())]
+    Interrupted(usize, String),
+    #[error("{} could not install the handler of interrupts\n", "error:".red().bold())]
+    InterruptHandlerNotSet,
+    #[error("{} could not rename temporary output file '{}' to '{}'\n", "error:".red().bold(), .0.yellow(), .1.yellow())]
+    OutputNotRenamed(String, String),
+    #[error("{} could not write the drift of the reference into '{}'\n", "error:".red().bold(), .0.yellow())]
+    DriftNotWritten(String),
+    #[error("{} could not write the reference cache '{}'\n", "error:".red().bold(), .0.yellow())]
+    ReferenceCacheNotWritten(String),
+    #[error("{} could not write the dimensions of the simulation box into '{}'\n", "error:".red().bold(), .0.yellow())]
+    BoxReportNotWritten(String),
+    #[error("{} could not write the statistics of the frames into '{}'\n", "error:".red().bold(), .0.yellow())]
+    StatsNotWritten(String),
+    #[error("{} could not write the reference groups into '{}'\n", "error:".red().bold(), .0.yellow())]
+    IndexNotWritten(String),
+    #[error("{} could not write the output trajectory '{}'\n", "error:".red().bold(), .0.yellow())]
+    TrrNotWritten(String),
+    #[error("{} output file '{}' cannot be streamed: only frames of trr trajectories are flushed as soon as they are written; use an output file with the '{}' extension\n", "error:".red().bold(), .0.yellow(), "trr".bold())]
+    StreamUnsupported(String),
+    #[error("{} could not write TER records into the output file '{}'\n", "error:".red().bold(), .0.yellow())]
+    TerNotWritten(String),
+    #[error("{} could not write the title into the output file '{}'\n", "error:".red().bold(), .0.yellow())]
+    TitleNotWritten(String),
+    #[error("{} could not transcode trajectory '{}' into {} format\n", "error:".red().bold(), .0.yellow(), .1.yellow())]
+    TranscodingFailed(String, String),
+    #[error("{} could not decompress trajectory '{}'\n", "error:".red().bold(), .0.yellow())]
+    DecompressionFailed(String),
+    #[error("{} could not compress the output into '{}'\n", "error:".red().bold(), .0.yellow())]
+    CompressionFailed(String),
     #[error("{} reference group '{}' is empty\n", "error:".red().bold(), .0.yellow())]
     EmptyReference(String),
-    #[error("{} no protein atoms autodetected\n", "error:".red().bold())]
-    AutodetectionFailed,
+    #[error("{} system loaded from '{}' contains no atoms\n", "error:".red().bold(), .0.yellow())]
+    EmptySystem(String),
+    #[error("{} reference moved by {} nm between frames {} and {} ({} ps), which exceeds the threshold of {} nm set using '{}'\n", "error:".red().bold(), .1.yellow(), (.0 - 1).to_string().yellow(), .0.to_string().yellow(), .2.yellow(), .3.yellow(), "--halt-on-jump".bold())]
+    ReferenceJump(usize, String, String, String),
+    #[error("{} {} atom(s) lie outside the simulation box of the frame at {} ps even though all atoms have been wrapped into the box; check the simulation box of the input\n", "error:".red().bold(), .0.to_string().yellow(), .1.yellow())]
+    AtomsOutsideBox(usize, String),
+    #[error("{} invalid value '{}' for '{}': the system only contains {} atoms\n\nFor more information, try '{}'.", "error:".red().bold(), .0.to_string().yellow(), "--reference-serials <SERIALS>".bold(), .1.to_string().yellow(), "--help".bold())]
+    SerialOutOfRange(usize, usize),
+    #[error("{} query '{}' used for stripping selects all atoms of the system; nothing would be written\n", "error:".red().bold(), .0.yellow())]
+    StripAll(String),
+    #[error("{} reference group '{}' contains atoms that are removed by '{}'\n", "error:".red().bold(), .0.yellow(), "--strip".bold())]
+    StripReference(String),
+    #[error("{} invalid value '{}' for '{}': selection file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--selection-file <FILE>".bold(), "--help".bold())]
+    SelectionFileNotFound(String),
+    #[error("{} could not parse line {} of selection file '{}': expected 'name = query'\n", "error:".red().bold(), .1.to_string().yellow(), .0.yellow())]
+    SelectionFileInvalid(String, usize),
+    #[error("{} selection '{}' is not defined in selection file '{}'\n", "error:".red().bold(), .0.yellow(), .1.yellow())]
+    SelectionNotFound(String, String),
+    #[error("{} query '{}' uses a named selection but no selection file has been provided\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--help".bold())]
+    SelectionFileRequired(String),
+    #[error("{} invalid value '{}' for '{}': configuration file does not exist\n\nFor more information, try '{}'.", "error:".red().bold(), .0.yellow(), "--config <CONFIG>".bold(), "--help".bold())]
+    ConfigNotFound(String),
+    #[error("{} could not read configuration file '{}'\n", "error:".red().bold(), .0.yellow())]
+    ConfigNotRead(String),
+    #[error("{} could not parse configuration file '{}': {}\n", "error:".red().bold(), .0.yellow(), .1)]
+    ConfigInvalid(String, String),
+    #[error("{} options '{}' and '{}' cannot be used together (configuration file '{}')\n", "error:".red().bold(), .1.bold(), .2.bold(), .0.yellow())]
+    ConfigConflict(String, String, String),
+    #[error("{} frame '{}' requested using '{}' does not exist in the trajectory\n", "error:".red().bold(), .0.to_string().yellow(), "--reference-from-frame <REFERENCE_FROM_FRAME>".bold())]
+    ReferenceFrameNotFound(usize),
+    #[error("{} no {} atoms autodetected\n", "error:".red().bold(), .0)]
+    AutodetectionFailed(String),
     #[error("{} simulation box is not orthogonal; this is not supported, sorry\n", "error:".red().bold())]
     BoxNotOrthogonal,
     #[error("{} simulation box is not a valid simulation box; some required dimensions are not positive\n", "error:".red().bold())]
@@ -42,3 +188,98 @@ pub enum RunError {
     #[error("{} simulation box is not defined\n", "error:".red().bold())]
     BoxNotDefined,
 }
+
+/// Escape a string so that it can be written inside a JSON string.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Remove ANSI escape sequences used for coloring the text.
+fn strip_colors(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // skip the sequence up to and including its final letter
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+/// Get the kind of the error, i.e. the name of the variant of the error enum.
+pub fn error_kind(error: &dyn std::error::Error) -> String {
+    format!("{:?}", error)
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Convert the error into a single-line JSON object containing the kind of the error and its message.
+/// Colors, the leading 'error:', and the hint about '--help' are removed from the message.
+pub fn error_to_json(kind: &str, error: &dyn Display) -> String {
+    let text = strip_colors(&error.to_string());
+
+    let message = text.trim();
+    let message = message
+        .strip_prefix("error:")
+        .unwrap_or(message)
+        .trim_start();
+    let message = message.split("\n\n").next().unwrap_or_default().trim();
+
+    format!(
+        "{{\"kind\": \"{}\", \"message\": \"{}\"}}",
+        json_escape(kind),
+        json_escape(message)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_json() {
+        assert_eq!(json_escape("plain"), "plain");
+        assert_eq!(json_escape("a \"b\" c"), "a \\\"b\\\" c");
+        assert_eq!(json_escape("a\\b\nc\td"), "a\\\\b\\nc\\td");
+        assert_eq!(json_escape("\u{1b}"), "\\u001b");
+    }
+
+    #[test]
+    fn colors_stripped() {
+        assert_eq!(
+            strip_colors("\u{1b}[1;31merror:\u{1b}[0m 'x'"),
+            "error: 'x'"
+        );
+        assert_eq!(strip_colors("no colors"), "no colors");
+    }
+
+    #[test]
+    fn run_error_to_json() {
+        let error = RunError::IOMatch(String::from("system.gro"));
+        assert_eq!(error_kind(&error), "IOMatch");
+        assert_eq!(
+            error_to_json(&error_kind(&error), &error),
+            "{\"kind\": \"IOMatch\", \"message\": \"invalid value 'system.gro' for '--output <OUTPUT>': output path matches input path\"}"
+        );
+
+        assert_eq!(error_kind(&RunError::BoxNotValid), "BoxNotValid");
+    }
+}
@@ -0,0 +1,113 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of reading trajectories containing more atoms than the structure file (e.g. virtual sites).
+
+use colored::Colorize;
+use groan_rs::structures::atom::Atom;
+use groan_rs::structures::vector3d::Vector3D;
+use groan_rs::system::System;
+use log::debug;
+
+use crate::argparse::Args;
+use crate::header;
+
+/// Residue and atom name of the atoms appended to the system.
+const EXTRA_NAME: &str = "EXTR";
+
+/// Append `n_extra` atoms to the system. Groups, simulation box, and properties of the frame are preserved.
+fn append_atoms(system: &System, n_extra: usize) -> System {
+    let mut atoms = system.atoms_extract();
+    let (last_residue, last_atom) = atoms
+        .last()
+        .map(|atom| (atom.get_residue_number(), atom.get_atom_number()))
+        .unwrap_or((0, 0));
+
+    for i in 1..=n_extra {
+        atoms.push(
+            Atom::new(last_residue + i, EXTRA_NAME, last_atom + i, EXTRA_NAME)
+                .with_position(Vector3D::default()),
+        );
+    }
+
+    let mut extended = System::new(system.get_name(), atoms, system.get_box_copy());
+    extended.set_simulation_step(system.get_simulation_step());
+    extended.set_simulation_time(system.get_simulation_time());
+    extended.set_precision(system.get_precision());
+    extended.set_lambda(system.get_lambda());
+
+    // groups `all` and `All` are created with the system and contain the appended atoms
+    for (name, group) in system.get_groups() {
+        if name == "all" || name == "All" {
+            continue;
+        }
+
+        extended
+            .group_create_from_indices(name, group.get_atoms().iter().collect())
+            .expect("\ngcenter: Fatal Error. Group of the system could not be copied.");
+    }
+
+    extended
+}
+
+/// Append the atoms present in the trajectory but missing in the structure to the system, if requested.
+/// The extra atoms are assumed to be placed after all atoms of the structure and are never written into the output.
+/// If the structure contains more atoms than the trajectory, the system is not changed and the atom count check fails later.
+pub fn extend_system(system: &mut System, args: &mut Args) {
+    if !args.ignore_extra_atoms {
+        return;
+    }
+
+    let n_atoms = match args
+        .trajectory_paths()
+        .first()
+        .and_then(header::trajectory_n_atoms)
+    {
+        Some(n) if n > system.get_n_atoms() => n,
+        _ => return,
+    };
+
+    let n_extra = n_atoms - system.get_n_atoms();
+    let n_original = system.get_n_atoms();
+    *system = append_atoms(system, n_extra);
+
+    // only the atoms of the structure are written into the output
+    if args.strip.is_none() {
+        system
+            .group_create_from_indices(crate::OUTPUT, (0..n_original).collect())
+            .expect("\ngcenter: Fatal Error. Output group could not be created.");
+    }
+
+    args.extra_atoms = n_extra;
+
+    debug!(
+        "{} trajectory contains {} atom(s) not present in the structure; these atoms are assumed to be appended after the atoms of the structure and will not be written into the output\n",
+        "note:".purple().bold(),
+        n_extra.to_string().yellow()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::system_from_positions;
+
+    #[test]
+    fn append_atoms_preserves_groups() {
+        let mut system = system_from_positions("Small", &[[1.0, 1.0, 1.0]; 4], 5.0);
+        system.group_create("First", "serial 1 2").unwrap();
+        system.set_simulation_time(100.0);
+
+        let extended = append_atoms(&system, 3);
+
+        assert_eq!(extended.get_n_atoms(), 7);
+        assert_eq!(extended.group_get_n_atoms("all").unwrap(), 7);
+        assert_eq!(extended.group_get_n_atoms("First").unwrap(), 2);
+        assert_eq!(extended.get_simulation_time(), 100.0);
+
+        let last = extended.get_atom(6).unwrap();
+        assert_eq!(last.get_atom_number(), 7);
+        assert_eq!(last.get_residue_number(), 4);
+        assert_eq!(last.get_atom_name(), EXTRA_NAME);
+    }
+}
@@ -0,0 +1,359 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of a reader and a writer of GROMOS87 (g96) structure files.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use groan_rs::structures::atom::Atom;
+use groan_rs::structures::simbox::SimBox;
+use groan_rs::structures::vector3d::Vector3D;
+use groan_rs::system::System;
+
+use crate::errors::RunError;
+
+/// Number of characters preceding the coordinates on POSITION and VELOCITY lines.
+const ATOM_PREFIX: usize = 24;
+
+/// Check whether the file is a g96 file based on its extension.
+pub fn is_g96(filename: impl AsRef<Path>) -> bool {
+    filename
+        .as_ref()
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("g96"))
+}
+
+/// Content of a single block of a g96 file.
+struct Block<'a> {
+    name: &'a str,
+    lines: Vec<&'a str>,
+}
+
+/// Split the content of a g96 file into blocks. Comment lines are skipped.
+fn parse_blocks(content: &str) -> Result<Vec<Block<'_>>, String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+
+    for line in content.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        match current.as_mut() {
+            None if line.trim().is_empty() => (),
+            None => {
+                current = Some(Block {
+                    name: line.trim(),
+                    lines: Vec::new(),
+                })
+            }
+            Some(_) if line.trim() == "END" => blocks.push(current.take().unwrap()),
+            Some(block) => block.lines.push(line),
+        }
+    }
+
+    match current {
+        Some(block) => Err(format!("block '{}' is not terminated by END", block.name)),
+        None => Ok(blocks),
+    }
+}
+
+/// Parse three floating point numbers.
+fn parse_vector(string: &str) -> Option<Vector3D> {
+    let values = string
+        .split_whitespace()
+        .map(|x| x.parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+
+    match values[..] {
+        [x, y, z] => Some(Vector3D::new(x, y, z)),
+        _ => None,
+    }
+}
+
+/// Parse a line of the POSITION block as an atom.
+fn line_as_atom(line: &str) -> Option<Atom> {
+    let prefix = line.get(..ATOM_PREFIX)?;
+
+    let residue_number = prefix.get(0..5)?.trim().parse::<usize>().ok()?;
+    let residue_name = prefix.get(6..11)?.trim();
+    let atom_name = prefix.get(12..17)?.trim();
+    let atom_number = prefix.get(17..24)?.trim().parse::<usize>().ok()?;
+    let position = parse_vector(line.get(ATOM_PREFIX..)?)?;
+
+    Some(Atom::new(residue_number, residue_name, atom_number, atom_name).with_position(position))
+}
+
+/// Parse the line of the BOX block.
+fn line_as_box(line: &str) -> Option<SimBox> {
+    let values = line
+        .split_whitespace()
+        .map(|x| x.parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+
+    match values[..] {
+        [x, y, z] => Some(SimBox::from([x, y, z])),
+        [_, _, _, v1y, v1z, _, v2z, _, _] if v1y == 0.0 && v1z == 0.0 && v2z == 0.0 => {
+            Some(SimBox::from(<[f32; 9]>::try_from(values).ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Read a g96 structure file.
+///
+/// ## Notes
+/// - Only the TITLE, POSITION, VELOCITY, and BOX blocks are read; other blocks are ignored.
+/// - Reduced blocks (POSITIONRED, VELOCITYRED) are not supported since they contain no atom names.
+pub fn read_g96(filename: &str) -> Result<System, RunError> {
+    let content = std::fs::read_to_string(filename).map_err(|_| {
+        RunError::G96Invalid(filename.to_owned(), String::from("file could not be read"))
+    })?;
+
+    let error = |e: String| RunError::G96Invalid(filename.to_owned(), e);
+
+    let blocks = parse_blocks(&content).map_err(error)?;
+    let block = |name: &str| blocks.iter().find(|block| block.name == name);
+
+    let positions =
+        block("POSITION").ok_or_else(|| error(String::from("no POSITION block found")))?;
+    let mut atoms = positions
+        .lines
+        .iter()
+        .map(|line| line_as_atom(line).ok_or_else(|| format!("could not parse line '{}'", line)))
+        .collect::<Result<Vec<Atom>, String>>()
+        .map_err(error)?;
+
+    if let Some(velocities) = block("VELOCITY") {
+        if velocities.lines.len() != atoms.len() {
+            return Err(error(format!(
+                "VELOCITY block contains {} atom(s) but POSITION block contains {} atom(s)",
+                velocities.lines.len(),
+                atoms.len()
+            )));
+        }
+
+        for (atom, line) in atoms.iter_mut().zip(velocities.lines.iter()) {
+            let velocity = line
+                .get(ATOM_PREFIX..)
+                .and_then(parse_vector)
+                .ok_or_else(|| error(format!("could not parse line '{}'", line)))?;
+            atom.set_velocity(velocity);
+        }
+    }
+
+    let simbox = match block("BOX") {
+        Some(simbox) => Some(
+            simbox
+                .lines
+                .first()
+                .and_then(|line| line_as_box(line))
+                .ok_or_else(|| error(String::from("could not parse the BOX block")))?,
+        ),
+        None => None,
+    };
+
+    let name = block("TITLE")
+        .map(|title| title.lines.join(" ").trim().to_owned())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| String::from("Unknown"));
+
+    Ok(System::new(&name, atoms, simbox))
+}
+
+/// Write a single line of the POSITION or VELOCITY block.
+fn write_atom_line(writer: &mut impl Write, atom: &Atom, vector: &Vector3D) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "{:>5} {:<5} {:<5}{:>7}{:>15.9}{:>15.9}{:>15.9}",
+        atom.get_residue_number() % 100000,
+        atom.get_residue_name().chars().take(5).collect::<String>(),
+        atom.get_atom_name().chars().take(5).collect::<String>(),
+        atom.get_atom_number() % 10000000,
+        vector.x,
+        vector.y,
+        vector.z
+    )
+}
+
+/// Write atoms of the system (or of the specified group) into a g96 file.
+fn write_blocks(
+    system: &System,
+    group: &str,
+    writer: &mut impl Write,
+    velocities: bool,
+    title: &str,
+) -> std::io::Result<()> {
+    writeln!(writer, "TITLE\n{}\nEND", title)?;
+
+    let atoms = || {
+        system
+            .group_iter(group)
+            .expect("\ngcenter: Fatal Error. Output group does not exist.")
+    };
+
+    writeln!(writer, "POSITION")?;
+    for atom in atoms() {
+        let position = atom.get_position().cloned().unwrap_or_default();
+        write_atom_line(writer, atom, &position)?;
+    }
+    writeln!(writer, "END")?;
+
+    if velocities {
+        writeln!(writer, "VELOCITY")?;
+        for atom in atoms() {
+            let velocity = atom.get_velocity().cloned().unwrap_or_default();
+            write_atom_line(writer, atom, &velocity)?;
+        }
+        writeln!(writer, "END")?;
+    }
+
+    if let Some(simbox) = system.get_box() {
+        writeln!(writer, "BOX")?;
+        write!(
+            writer,
+            "{:>15.9}{:>15.9}{:>15.9}",
+            simbox.v1x, simbox.v2y, simbox.v3z
+        )?;
+        if !simbox.is_orthogonal() {
+            write!(
+                writer,
+                "{:>15.9}{:>15.9}{:>15.9}{:>15.9}{:>15.9}{:>15.9}",
+                simbox.v1y, simbox.v1z, simbox.v2x, simbox.v2z, simbox.v3x, simbox.v3y
+            )?;
+        }
+        writeln!(writer, "\nEND")?;
+    }
+
+    writer.flush()
+}
+
+/// Write atoms of the system (or of the specified group) into a g96 file.
+/// If no title is provided, the name of the system is used.
+pub fn write_g96(
+    system: &System,
+    group: Option<&str>,
+    filename: &str,
+    velocities: bool,
+    title: Option<&str>,
+) -> Result<(), RunError> {
+    let file = File::create(filename).map_err(|_| RunError::G96NotWritten(filename.to_owned()))?;
+    let mut writer = BufWriter::new(file);
+
+    write_blocks(
+        system,
+        group.unwrap_or("all"),
+        &mut writer,
+        velocities,
+        title.unwrap_or(system.get_name()),
+    )
+    .map_err(|_| RunError::G96NotWritten(filename.to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_g96_extension() {
+        assert!(is_g96("structure.g96"));
+        assert!(is_g96("structure.G96"));
+        assert!(!is_g96("structure.gro"));
+        assert!(!is_g96("g96"));
+    }
+
+    #[test]
+    fn read_g96_membrane() {
+        let system = read_g96("tests/test_files/input.g96").unwrap();
+        let expected = System::from_file("tests/test_files/input.gro").unwrap();
+
+        assert_eq!(system.get_name(), expected.get_name());
+        assert_eq!(system.get_n_atoms(), expected.get_n_atoms());
+
+        let (simbox, expected_box) = (system.get_box().unwrap(), expected.get_box().unwrap());
+        assert_eq!(
+            [simbox.x, simbox.y, simbox.z],
+            [expected_box.x, expected_box.y, expected_box.z]
+        );
+
+        for (atom, expected_atom) in system.atoms_iter().zip(expected.atoms_iter()) {
+            assert_eq!(atom.get_atom_name(), expected_atom.get_atom_name());
+            assert_eq!(atom.get_atom_number(), expected_atom.get_atom_number());
+            assert_eq!(atom.get_residue_name(), expected_atom.get_residue_name());
+            assert_eq!(
+                atom.get_residue_number(),
+                expected_atom.get_residue_number()
+            );
+
+            let pos = atom.get_position().unwrap();
+            let expected_pos = expected_atom.get_position().unwrap();
+            assert!((pos.x - expected_pos.x).abs() < 1e-6);
+            assert!((pos.y - expected_pos.y).abs() < 1e-6);
+            assert!((pos.z - expected_pos.z).abs() < 1e-6);
+
+            let vel = atom.get_velocity().unwrap();
+            let expected_vel = expected_atom.get_velocity().unwrap();
+            assert!((vel.x - expected_vel.x).abs() < 1e-6);
+            assert!((vel.y - expected_vel.y).abs() < 1e-6);
+            assert!((vel.z - expected_vel.z).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn write_read_g96() {
+        let system = read_g96("tests/test_files/input.g96").unwrap();
+        let output = tempfile::Builder::new().suffix(".g96").tempfile().unwrap();
+        let path = output.path().to_str().unwrap();
+
+        write_g96(&system, None, path, true, Some("Written")).unwrap();
+        let written = std::fs::read_to_string(path).unwrap();
+        assert!(written.starts_with("TITLE\nWritten\nEND\nPOSITION\n"));
+        assert!(written.ends_with("BOX\n    9.261899948    9.261899948   11.045299530\nEND\n"));
+
+        let read = read_g96(path).unwrap();
+        assert_eq!(read.get_name(), "Written");
+        assert_eq!(read.get_n_atoms(), system.get_n_atoms());
+
+        for (atom, expected) in read.atoms_iter().zip(system.atoms_iter()) {
+            assert_eq!(atom.get_atom_name(), expected.get_atom_name());
+            assert_eq!(atom.get_residue_number(), expected.get_residue_number());
+            assert_eq!(atom.get_position(), expected.get_position());
+            assert_eq!(atom.get_velocity(), expected.get_velocity());
+        }
+    }
+
+    #[test]
+    fn read_g96_invalid() {
+        let file = tempfile::Builder::new().suffix(".g96").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        std::fs::write(path, "TITLE\nInvalid\nEND\nBOX\n   1.0   1.0   1.0\nEND\n").unwrap();
+        assert!(read_g96(path).is_err());
+
+        std::fs::write(
+            path,
+            "POSITION\n    1 ASN   BB         1    1.0    1.0    1.0\n",
+        )
+        .unwrap();
+        assert!(read_g96(path).is_err());
+
+        std::fs::write(
+            path,
+            "POSITION\n    1 ASN   BB         1    1.0    1.0\nEND\n",
+        )
+        .unwrap();
+        assert!(read_g96(path).is_err());
+    }
+
+    #[test]
+    fn read_g96_triclinic_box() {
+        let simbox = line_as_box("   5.0   5.0   5.0   0.0   0.0   1.0   0.0   1.0   2.0").unwrap();
+        assert!(!simbox.is_orthogonal());
+        assert_eq!([simbox.v2x, simbox.v3x, simbox.v3y], [1.0, 1.0, 2.0]);
+
+        assert!(line_as_box("   5.0   5.0   5.0   1.0   0.0   1.0   0.0   1.0   2.0").is_none());
+        assert!(line_as_box("   5.0   5.0").is_none());
+    }
+}
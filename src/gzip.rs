@@ -0,0 +1,146 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of reading gzip-compressed xtc and trr trajectories and writing gzip-compressed output.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use colored::Colorize;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use groan_rs::files::FileType;
+use log::{debug, warn};
+
+use crate::argparse::Args;
+use crate::errors::RunError;
+use crate::interrupt::Interrupt;
+use crate::temporary::TemporaryFiles;
+
+/// Check whether the file is compressed using gzip based on its extension.
+pub fn is_gzip(filename: impl AsRef<Path>) -> bool {
+    filename
+        .as_ref()
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// Get the type of the trajectory file. For gzip-compressed files, the type of the compressed file is returned,
+/// i.e. 'traj.xtc.gz' is an xtc file.
+pub fn trajectory_type(filename: impl AsRef<Path>) -> FileType {
+    let path = filename.as_ref();
+    if is_gzip(path) {
+        crate::file_type(path.with_extension(""))
+    } else {
+        crate::file_type(path)
+    }
+}
+
+/// Decompress the gzip-compressed file `input` into the file `output`.
+fn decompress(input: &str, output: &str) -> std::io::Result<()> {
+    let mut decoder = MultiGzDecoder::new(BufReader::new(File::open(input)?));
+    let mut writer = BufWriter::new(File::create(output)?);
+    std::io::copy(&mut decoder, &mut writer)?;
+    writer.flush()
+}
+
+/// Decompress all gzip-compressed trajectories into temporary files.
+/// The readers of xtc and trr files require seeking, so the trajectories can not be decompressed on the fly.
+/// The decompressed trajectories are read instead of the original files; the original names are kept for reporting.
+/// Returns the temporary files which are removed once they are dropped or once the program is interrupted.
+pub fn decompress_trajectories(
+    args: &mut Args,
+    interrupt: &Interrupt,
+) -> Result<TemporaryFiles, RunError> {
+    let mut decompressed = TemporaryFiles::new(interrupt);
+    let mut paths = args.trajectory_paths().to_vec();
+
+    for (i, (traj, read_path)) in args.trajectories.iter().zip(paths.iter_mut()).enumerate() {
+        if !is_gzip(traj.as_str()) {
+            continue;
+        }
+
+        let extension = match trajectory_type(traj.as_str()) {
+            FileType::XTC => "xtc",
+            FileType::TRR => "trr",
+            _ => {
+                panic!("\ngcenter: Fatal Error. Only xtc and trr trajectories can be decompressed.")
+            }
+        };
+
+        let path = decompressed.create(&args.output, "decompressed", i, extension);
+        if decompress(read_path, &path).is_err() {
+            return Err(RunError::DecompressionFailed(traj.to_owned()));
+        }
+
+        debug!(
+            "{} trajectory '{}' decompressed\n",
+            "note:".purple().bold(),
+            traj.yellow()
+        );
+
+        *read_path = path;
+    }
+
+    if !decompressed.is_empty() {
+        args.trajectories_tmp = Some(paths);
+    }
+
+    Ok(decompressed)
+}
+
+/// Compress the file `input` into the gzip-compressed file `output`.
+pub fn compress(input: &str, output: &str) -> std::io::Result<()> {
+    let mut reader = BufReader::new(File::open(input)?);
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(output)?),
+        Compression::default(),
+    );
+    std::io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?.flush()
+}
+
+/// Get the path to the gzip-compressed output file, if `--gzip-output` is requested.
+/// Binary trajectories are already compressed, so the option is ignored for them.
+pub fn compressed_output(args: &Args) -> Option<String> {
+    if !args.gzip_output {
+        return None;
+    }
+
+    match crate::file_type(&args.output) {
+        FileType::XTC | FileType::TRR => {
+            warn!(
+                "{} output file '{}' is a binary trajectory; '{}' will be ignored\n",
+                "warning:".yellow().bold(),
+                args.output.yellow(),
+                "--gzip-output".bold()
+            );
+            None
+        }
+        _ => Some(format!("{}.gz", args.output)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gzip_extension() {
+        assert!(is_gzip("traj.xtc.gz"));
+        assert!(is_gzip("traj.TRR.GZ"));
+        assert!(!is_gzip("traj.xtc"));
+        assert!(!is_gzip("gz"));
+    }
+
+    #[test]
+    fn compressed_trajectory_type() {
+        assert_eq!(trajectory_type("traj.xtc.gz"), FileType::XTC);
+        assert_eq!(trajectory_type("traj.trr.gz"), FileType::TRR);
+        assert_eq!(trajectory_type("traj.gro.gz"), FileType::GRO);
+        assert_eq!(trajectory_type("traj.xtc"), FileType::XTC);
+        assert_eq!(trajectory_type("traj.gz"), FileType::Unknown);
+    }
+}
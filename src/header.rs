@@ -0,0 +1,325 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of reading the number of atoms from the headers of trajectory files.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek};
+use std::path::Path;
+
+use groan_rs::files::FileType;
+
+/// Read a single 32-bit integer encoded in the XDR (big-endian) format.
+fn read_xdr_int(reader: &mut impl Read) -> Option<i32> {
+    let mut buffer = [0u8; 4];
+    reader.read_exact(&mut buffer).ok()?;
+    Some(i32::from_be_bytes(buffer))
+}
+
+/// Read a single 32-bit float encoded in the XDR format.
+fn read_xdr_float(reader: &mut impl Read) -> Option<f32> {
+    let mut buffer = [0u8; 4];
+    reader.read_exact(&mut buffer).ok()?;
+    Some(f32::from_be_bytes(buffer))
+}
+
+/// Read a single 64-bit float encoded in the XDR format.
+fn read_xdr_double(reader: &mut impl Read) -> Option<f64> {
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer).ok()?;
+    Some(f64::from_be_bytes(buffer))
+}
+
+/// Read a non-negative 32-bit integer encoded in the XDR format.
+fn read_xdr_size(reader: &mut impl Read) -> Option<usize> {
+    read_xdr_int(reader)?.try_into().ok()
+}
+
+/// Skip the specified number of bytes.
+fn skip_bytes(reader: &mut (impl Read + Seek), n_bytes: usize) -> Option<()> {
+    reader.seek_relative(n_bytes.try_into().ok()?).ok()
+}
+
+/// Read the number of atoms from the header of an xtc file.
+fn xtc_n_atoms(reader: &mut impl Read) -> Option<usize> {
+    // magic number
+    read_xdr_int(reader)?;
+
+    read_xdr_int(reader)?.try_into().ok()
+}
+
+/// Read the number of atoms from the header of a trr file.
+fn trr_n_atoms(reader: &mut impl Read) -> Option<usize> {
+    // magic number and the length of the version string
+    read_xdr_int(reader)?;
+    read_xdr_int(reader)?;
+
+    // version string, padded to a multiple of 4 bytes
+    let length: usize = read_xdr_int(reader)?.try_into().ok()?;
+    let mut version = vec![0u8; length.div_ceil(4) * 4];
+    reader.read_exact(&mut version).ok()?;
+
+    // sizes of the individual data blocks
+    for _ in 0..10 {
+        read_xdr_int(reader)?;
+    }
+
+    read_xdr_int(reader)?.try_into().ok()
+}
+
+/// Read whether the first frame of a trr file contains velocities and forces.
+fn trr_velocities_forces(reader: &mut impl Read) -> Option<(bool, bool)> {
+    // magic number and the length of the version string
+    read_xdr_int(reader)?;
+    read_xdr_int(reader)?;
+
+    // version string, padded to a multiple of 4 bytes
+    let length = read_xdr_size(reader)?;
+    let mut version = vec![0u8; length.div_ceil(4) * 4];
+    reader.read_exact(&mut version).ok()?;
+
+    // ir, e, box, vir, pres, top, sym, and x sizes
+    for _ in 0..8 {
+        read_xdr_int(reader)?;
+    }
+
+    let v_size = read_xdr_size(reader)?;
+    let f_size = read_xdr_size(reader)?;
+
+    Some((v_size != 0, f_size != 0))
+}
+
+/// Read the simulation time of an xtc frame and skip the rest of the frame.
+/// Returns `None` if there are no more frames to read.
+fn xtc_frame_time(reader: &mut (impl Read + Seek)) -> Option<f32> {
+    // magic number, number of atoms, and simulation step
+    for _ in 0..3 {
+        read_xdr_int(reader)?;
+    }
+
+    let time = read_xdr_float(reader)?;
+
+    // simulation box
+    skip_bytes(reader, 9 * 4)?;
+
+    let n_atoms = read_xdr_size(reader)?;
+    if n_atoms <= 9 {
+        // coordinates of small systems are not compressed
+        skip_bytes(reader, n_atoms * 3 * 4)?;
+    } else {
+        // precision, minimal and maximal integer coordinates, and the smallest index
+        skip_bytes(reader, 8 * 4)?;
+        // compressed coordinates, padded to a multiple of 4 bytes
+        let n_bytes = read_xdr_size(reader)?;
+        skip_bytes(reader, n_bytes.div_ceil(4) * 4)?;
+    }
+
+    Some(time)
+}
+
+/// Read the simulation time of a trr frame and skip the rest of the frame.
+/// Returns `None` if there are no more frames to read.
+fn trr_frame_time(reader: &mut (impl Read + Seek)) -> Option<f32> {
+    // magic number and the length of the version string
+    read_xdr_int(reader)?;
+    read_xdr_int(reader)?;
+
+    // version string, padded to a multiple of 4 bytes
+    let length = read_xdr_size(reader)?;
+    skip_bytes(reader, length.div_ceil(4) * 4)?;
+
+    // ir, e, box, vir, pres, top, sym, x, v, and f sizes, number of atoms, step, and nre
+    let mut sizes = [0usize; 13];
+    for size in sizes.iter_mut() {
+        *size = read_xdr_size(reader)?;
+    }
+
+    let [_, _, box_size, vir_size, pres_size, _, _, x_size, v_size, f_size, n_atoms, _, _] = sizes;
+
+    // determine whether the frame is written in single or double precision
+    let real_size = if box_size != 0 {
+        box_size / 9
+    } else {
+        [x_size, v_size, f_size]
+            .into_iter()
+            .find(|&size| size != 0)
+            .map(|size| size / (n_atoms * 3).max(1))?
+    };
+
+    let time = match real_size {
+        4 => read_xdr_float(reader)?,
+        8 => read_xdr_double(reader)? as f32,
+        _ => return None,
+    };
+
+    // lambda and the data blocks
+    skip_bytes(
+        reader,
+        real_size + box_size + vir_size + pres_size + x_size + v_size + f_size,
+    )?;
+
+    Some(time)
+}
+
+/// Read the number of atoms from the first frame of a gro file.
+fn gro_n_atoms(reader: &mut impl BufRead) -> Option<usize> {
+    let mut line = String::new();
+
+    // title line
+    reader.read_line(&mut line).ok()?;
+
+    line.clear();
+    reader.read_line(&mut line).ok()?;
+    line.trim().parse().ok()
+}
+
+/// Get the number of atoms in the first frame of a trajectory file without reading the frame.
+/// Returns `None` if the file could not be opened or its header could not be read.
+/// Such errors are reported when the trajectory is actually read.
+pub fn trajectory_n_atoms(filename: impl AsRef<Path>) -> Option<usize> {
+    let file = File::open(filename.as_ref()).ok()?;
+    let mut reader = BufReader::new(file);
+
+    match crate::file_type(filename.as_ref()) {
+        FileType::XTC => xtc_n_atoms(&mut reader),
+        FileType::TRR => trr_n_atoms(&mut reader),
+        FileType::GRO => gro_n_atoms(&mut reader),
+        _ => None,
+    }
+}
+
+/// Get whether the first frame of a trr file contains velocities and forces without reading the frame.
+/// Returns `None` if the file could not be opened, its header could not be read, or it is not a trr file.
+pub fn trajectory_velocities_forces(filename: impl AsRef<Path>) -> Option<(bool, bool)> {
+    if crate::file_type(filename.as_ref()) != FileType::TRR {
+        return None;
+    }
+
+    let file = File::open(filename.as_ref()).ok()?;
+    let mut reader = BufReader::new(file);
+
+    trr_velocities_forces(&mut reader)
+}
+
+/// Get the number of frames in a gro trajectory by counting its lines.
+/// Returns `None` if the file could not be opened or its header could not be read.
+pub fn gro_n_frames(filename: impl AsRef<Path>) -> Option<usize> {
+    let file = File::open(filename.as_ref()).ok()?;
+    let mut reader = BufReader::new(file);
+
+    let n_atoms = gro_n_atoms(&mut reader)?;
+    // title and number of atoms have already been read
+    let n_lines = 2 + reader.lines().count();
+
+    Some(n_lines / (n_atoms + 3))
+}
+
+/// Get the simulation time of the last frame of an xtc or trr file by reading only the headers of the frames.
+/// Returns `None` if the file could not be opened, contains no frames, or is not an xtc or trr file.
+pub fn trajectory_last_time(filename: impl AsRef<Path>) -> Option<f32> {
+    let file = File::open(filename.as_ref()).ok()?;
+    let mut reader = BufReader::new(file);
+
+    let frame_time = match crate::file_type(filename.as_ref()) {
+        FileType::XTC => xtc_frame_time,
+        FileType::TRR => trr_frame_time,
+        _ => return None,
+    };
+
+    let mut last = None;
+    while let Some(time) = frame_time(&mut reader) {
+        last = Some(time);
+    }
+
+    last
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn n_atoms_xtc() {
+        assert_eq!(trajectory_n_atoms("tests/test_files/input.xtc"), Some(8327));
+    }
+
+    #[test]
+    fn n_atoms_trr() {
+        assert_eq!(trajectory_n_atoms("tests/test_files/input.trr"), Some(8327));
+    }
+
+    #[test]
+    fn n_atoms_gro() {
+        assert_eq!(
+            trajectory_n_atoms("tests/test_files/input_traj.gro"),
+            Some(8327)
+        );
+    }
+
+    #[test]
+    fn n_atoms_nonexistent() {
+        assert_eq!(trajectory_n_atoms("tests/test_files/nonexistent.xtc"), None);
+    }
+
+    #[test]
+    fn n_frames_gro() {
+        assert_eq!(gro_n_frames("tests/test_files/input_traj.gro"), Some(11));
+    }
+
+    #[test]
+    fn n_frames_gro_nonexistent() {
+        assert_eq!(gro_n_frames("tests/test_files/nonexistent.gro"), None);
+    }
+
+    #[test]
+    fn velocities_forces_trr() {
+        assert_eq!(
+            trajectory_velocities_forces("tests/test_files/input.trr"),
+            Some((false, false))
+        );
+        assert_eq!(
+            trajectory_velocities_forces("tests/test_files/input_aa_peptide.trr"),
+            Some((true, true))
+        );
+    }
+
+    #[test]
+    fn velocities_forces_xtc() {
+        assert_eq!(
+            trajectory_velocities_forces("tests/test_files/input.xtc"),
+            None
+        );
+    }
+
+    #[test]
+    fn last_time_xtc() {
+        assert_eq!(
+            trajectory_last_time("tests/test_files/input.xtc"),
+            Some(1000.0)
+        );
+    }
+
+    #[test]
+    fn last_time_trr() {
+        assert_eq!(
+            trajectory_last_time("tests/test_files/input.trr"),
+            Some(1000.0)
+        );
+    }
+
+    #[test]
+    fn last_time_gro() {
+        assert_eq!(
+            trajectory_last_time("tests/test_files/input_traj.gro"),
+            None
+        );
+    }
+
+    #[test]
+    fn last_time_nonexistent() {
+        assert_eq!(
+            trajectory_last_time("tests/test_files/nonexistent.xtc"),
+            None
+        );
+    }
+}
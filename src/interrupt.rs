@@ -0,0 +1,141 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of handling interrupts (e.g. Ctrl-C) sent by the user.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::errors::RunError;
+
+/// Interrupts sent by the user while centering a trajectory.
+/// Interrupts are only caught while the frames of a trajectory are centered.
+/// At any other time, an interrupt terminates the program after removing the registered temporary files.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    /// Set when the user interrupts the centering.
+    requested: Arc<AtomicBool>,
+    /// Set while the frames of a trajectory are centered.
+    catching: Arc<AtomicBool>,
+    /// Temporary files removed if the program is terminated by an interrupt.
+    temporary: Arc<Mutex<Vec<String>>>,
+}
+
+impl Interrupt {
+    /// Install the process-wide handler of interrupts.
+    /// The handler can only be installed once per process.
+    pub fn install() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let interrupt = Interrupt::default();
+        let handler = interrupt.clone();
+
+        ctrlc::set_handler(move || {
+            if handler.catching.load(Ordering::SeqCst) {
+                handler.requested.store(true, Ordering::SeqCst);
+            } else {
+                // destructors are not run on exit, so the temporary files have to be removed here
+                handler.remove_temporary();
+                // same exit code as for a process killed by SIGINT
+                std::process::exit(130);
+            }
+        })
+        .map_err(|_| RunError::InterruptHandlerNotSet)?;
+
+        Ok(interrupt)
+    }
+
+    /// Start catching interrupts. Interrupts sent before this call are forgotten.
+    pub(crate) fn catch(&self) {
+        self.requested.store(false, Ordering::SeqCst);
+        self.catching.store(true, Ordering::SeqCst);
+    }
+
+    /// Stop catching interrupts.
+    pub(crate) fn release(&self) {
+        self.catching.store(false, Ordering::SeqCst);
+    }
+
+    /// Check whether the user has interrupted the centering.
+    pub(crate) fn requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// Register a temporary file to be removed if the program is terminated by an interrupt.
+    pub(crate) fn register(&self, path: &str) {
+        if let Ok(mut files) = self.temporary.lock() {
+            files.push(path.to_owned());
+        }
+    }
+
+    /// Stop tracking a temporary file which has already been removed.
+    pub(crate) fn unregister(&self, path: &str) {
+        if let Ok(mut files) = self.temporary.lock() {
+            files.retain(|file| file != path);
+        }
+    }
+
+    /// Remove all registered temporary files.
+    fn remove_temporary(&self) {
+        if let Ok(mut files) = self.temporary.lock() {
+            for file in files.drain(..) {
+                let _ = std::fs::remove_file(file);
+            }
+        }
+    }
+
+    /// Mark the centering as interrupted, as if the interrupt was sent by the user.
+    #[cfg(test)]
+    pub(crate) fn request(&self) {
+        if self.catching.load(Ordering::SeqCst) {
+            self.requested.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interrupt_only_while_catching() {
+        let interrupt = Interrupt::default();
+
+        interrupt.request();
+        assert!(!interrupt.requested());
+
+        interrupt.catch();
+        interrupt.request();
+        assert!(interrupt.requested());
+    }
+
+    #[test]
+    fn interrupt_reset_on_catch() {
+        let interrupt = Interrupt::default();
+
+        interrupt.catch();
+        interrupt.request();
+        interrupt.release();
+        assert!(interrupt.requested());
+
+        // the next centering is not affected by the previous interrupt
+        interrupt.catch();
+        assert!(!interrupt.requested());
+    }
+
+    #[test]
+    fn interrupt_removes_temporary() {
+        let interrupt = Interrupt::default();
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.xtc");
+        let removed = dir.path().join("removed.xtc");
+        std::fs::write(&kept, "frame").unwrap();
+        std::fs::write(&removed, "frame").unwrap();
+
+        interrupt.register(kept.to_str().unwrap());
+        interrupt.register(removed.to_str().unwrap());
+        interrupt.unregister(kept.to_str().unwrap());
+        interrupt.remove_temporary();
+
+        assert!(kept.exists());
+        assert!(!removed.exists());
+    }
+}
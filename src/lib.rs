@@ -2,135 +2,735 @@
 // Copyright (c) 2023-2024 Ladislav Bartos
 
 mod argparse;
+mod benchmark;
+mod cache;
+mod capabilities;
 mod center;
+mod cif;
+mod config;
 mod errors;
+mod extra_atoms;
+mod g96;
+mod gzip;
+mod header;
+mod interrupt;
+mod logger;
+mod masses;
 mod reference;
+mod stream;
+mod temporary;
+#[cfg(test)]
+mod test_utils;
+mod transcode;
+mod trr;
+mod xtc_index;
 
 use colored::Colorize;
 use groan_rs::errors::ElementError;
 use groan_rs::files::FileType;
 use groan_rs::structures::dimension::Dimension;
 use groan_rs::structures::element::Elements;
+use groan_rs::structures::simbox::SimBox;
 use groan_rs::system::System;
-use std::path::Path;
+use log::{debug, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use argparse::Args;
+use argparse::{Args, ErrorFormat, Weighting};
+use benchmark::Benchmark;
+use errors::RunError;
+
+pub use center::CenterStats;
+pub use interrupt::Interrupt;
+pub use logger::init_logger;
+
+/// Write the error into stderr in the format requested using `--error-format`.
+pub fn report_error(error: &(dyn std::error::Error + Send + Sync)) {
+    match argparse::error_format() {
+        ErrorFormat::Text => eprintln!("{}", error),
+        ErrorFormat::Json => eprintln!(
+            "{}",
+            errors::error_to_json(&errors::error_kind(error), error)
+        ),
+    }
+}
 
 const MAIN_REFERENCE: &str = "CNTR-Main";
 const X_REFERENCE: &str = "CNTR-X";
 const Y_REFERENCE: &str = "CNTR-Y";
 const Z_REFERENCE: &str = "CNTR-Z";
+const WEIGHTED_REFERENCE: &str = "CNTR-Weighted";
+const NO_REFERENCE: &str = "none";
+const STRIPPED: &str = "CNTR-Stripped";
+const OUTPUT: &str = "CNTR-Output";
 
 /// Print options specified for the centering. Non-default values are colored in blue.
 fn print_options(args: &Args, system: &System, dim: &Dimension) {
-    println!("[STRUCTURE]     {}", &args.structure.bright_blue());
+    info!("[STRUCTURE]     {}", &args.structure.bright_blue());
+
+    if let Some(coordinates) = &args.coordinates {
+        info!("[COORDINATES]   {}", coordinates.bright_blue());
+    }
 
     match args.trajectories.len() {
         0 => (),
-        1 => println!("[TRAJECTORY]    {}", args.trajectories[0].bright_blue()),
+        1 => info!("[TRAJECTORY]    {}", args.trajectories[0].bright_blue()),
         _ => {
-            print!("[TRAJECTORIES]  ");
-            println!("{}", args.trajectories[0].bright_blue());
+            info!("[TRAJECTORIES]  {}", args.trajectories[0].bright_blue());
             for traj in args.trajectories.iter().skip(1) {
-                println!("                {}", traj.bright_blue());
+                info!("                {}", traj.bright_blue());
             }
         }
     }
 
-    println!("[OUTPUT]        {}", &args.output.bright_blue());
+    if args.no_output {
+        info!("[OUTPUT]        {}", "none".bright_blue());
+    } else {
+        info!("[OUTPUT]        {}", args.final_output().bright_blue());
+    }
+
+    if args.stream {
+        info!("[STREAM]        {}", "flush after each frame".bright_blue());
+    }
+
+    if let Some(directory) = &args.backup_dir {
+        info!("[BACKUP DIR]    {}", directory.bright_blue());
+    }
 
     if args.index.is_some() {
-        println!(
+        info!(
             "[INDEX]         {}",
             &args.index.clone().unwrap().bright_blue()
         );
     } else if system.get_n_groups() > 2 {
-        println!("[INDEX]         index.ndx");
+        info!("[INDEX]         index.ndx");
     }
 
-    if args.xreference.is_none() && args.yreference.is_none() && args.zreference.is_none() {
-        if args.reference == "Protein" {
-            println!("[REFERENCE]     {}", &args.reference);
+    let weighted = args
+        .reference_groups
+        .iter()
+        .map(|group| group.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    if args.xreference.is_none()
+        && args.yreference.is_none()
+        && args.zreference.is_none()
+        && !args.xref_invert
+        && !args.yref_invert
+        && !args.zref_invert
+    {
+        if args.recenter_box {
+            info!("[REFERENCE]     {}", "simulation box center".bright_blue());
+        } else if !weighted.is_empty() {
+            info!("[REFERENCE]     {}", weighted.bright_blue());
+        } else if let Some(serials) = &args.reference_serials {
+            let query = if args.invert_selection {
+                format!("not (serials {})", serials)
+            } else {
+                format!("serials {}", serials)
+            };
+
+            info!("[REFERENCE]     {}", query.bright_blue());
+        } else if args.reference == NO_REFERENCE {
+            info!("[REFERENCE]     {}", "none (no centering)".bright_blue());
+        } else if args.invert_selection {
+            info!(
+                "[REFERENCE]     {}",
+                format!("not ({})", &args.reference).bright_blue()
+            );
+        } else if args.reference == "Protein" {
+            info!("[REFERENCE]     {}", &args.reference);
         } else {
-            println!("[REFERENCE]     {}", &args.reference.bright_blue());
+            info!("[REFERENCE]     {}", &args.reference.bright_blue());
         }
     } else {
-        for ((reference, name), dimension) in [&args.xreference, &args.yreference, &args.zreference]
-            .into_iter()
-            .zip(["[XREFERENCE]", "[YREFERENCE]", "[ZREFERENCE]"].into_iter())
-            .zip([dim.is_x(), dim.is_y(), dim.is_z()].into_iter())
+        for ((((reference, name), dimension), weighting), invert) in
+            [&args.xreference, &args.yreference, &args.zreference]
+                .into_iter()
+                .zip(["[XREFERENCE]", "[YREFERENCE]", "[ZREFERENCE]"].into_iter())
+                .zip([dim.is_x(), dim.is_y(), dim.is_z()].into_iter())
+                .zip([args.xweighting, args.yweighting, args.zweighting])
+                .zip([args.xref_invert, args.yref_invert, args.zref_invert])
         {
             if !dimension {
                 continue;
             }
 
-            match reference {
-                None => {
-                    if args.reference == "Protein" {
-                        println!("{}    {}", name, &args.reference);
-                    } else {
-                        println!("{}    {}", name, &args.reference.bright_blue());
-                    }
+            let query = match reference {
+                None if !weighted.is_empty() => weighted.clone(),
+                None => match &args.reference_serials {
+                    Some(serials) => format!("serials {}", serials),
+                    None => args.reference.clone(),
+                },
+                Some(query) => query.clone(),
+            };
+
+            let query = if invert {
+                format!("not ({})", query)
+            } else {
+                query
+            };
+
+            match weighting {
+                Some(weighting) if reference.is_some() => info!(
+                    "{}    {} ({})",
+                    name,
+                    query.bright_blue(),
+                    weighting.to_string().bright_blue()
+                ),
+                _ if reference.is_none() && !invert && query == "Protein" => {
+                    info!("{}    {}", name, query)
                 }
-                Some(query) => println!("{}    {}", name, query.bright_blue()),
+                _ => info!("{}    {}", name, query.bright_blue()),
             }
         }
     }
 
+    if let Some(strip) = &args.strip {
+        info!("[STRIP]         {}", strip.bright_blue());
+    }
+
     if !args.xdimension
         && !args.ydimension
         && !args.zdimension
+        && args.dimensions.is_none()
         && args.xreference.is_none()
         && args.yreference.is_none()
         && args.zreference.is_none()
     {
-        println!("[DIMENSIONS]    {}", dim);
+        info!("[DIMENSIONS]    {}", dim);
     } else {
-        println!("[DIMENSIONS]    {}", dim.to_string().bright_blue());
+        info!("[DIMENSIONS]    {}", dim.to_string().bright_blue());
     }
 
     if let Some(s) = args.start_time {
         let time = format!("{} ns", s / 1000.0);
-        println!("[START TIME]    {}", time.bright_blue());
+        info!("[START TIME]    {}", time.bright_blue());
+    }
+
+    if let Some(l) = args.last {
+        let time = format!("{} ns", l / 1000.0);
+        info!("[LAST]          {}", time.bright_blue());
+    }
+
+    if let Some(index) = &args.xtc_index {
+        info!("[XTC INDEX]     {}", index.bright_blue());
     }
 
     if let Some(e) = args.end_time {
         let time = format!("{} ns", e / 1000.0);
-        println!("[END TIME]      {}", time.bright_blue());
+        info!("[END TIME]      {}", time.bright_blue());
+    }
+
+    if let Some(step) = args.begin_step {
+        info!("[BEGIN STEP]    {}", step.to_string().bright_blue());
+    }
+
+    if let Some(step) = args.end_step {
+        info!("[END STEP]      {}", step.to_string().bright_blue());
+    }
+
+    if let Some(times) = &args.frame_times {
+        info!("[FRAME TIMES]   {}", times.bright_blue());
     }
 
     if args.step != 1 {
-        println!("[STEP]          {}", &args.step.to_string().bright_blue());
+        info!("[STEP]          {}", &args.step.to_string().bright_blue());
+    }
+
+    if let Some(n) = args.discard {
+        info!(
+            "[DISCARD]       {}",
+            format!("{} frame(s)", n).bright_blue()
+        );
+    }
+
+    if let Some(every) = args.every {
+        info!("[EVERY]         {}", format!("{} ps", every).bright_blue());
+    }
+
+    match args.frame_parity() {
+        Some(0) => info!("[PARITY]        {}", "even frames".bright_blue()),
+        Some(_) => info!("[PARITY]        {}", "odd frames".bright_blue()),
+        None => (),
+    }
+
+    if args.dedup {
+        info!("[DEDUP]         {}", "skip duplicate frames".bright_blue());
+    }
+
+    if args.first_frame_only {
+        info!("[FRAMES]        {}", "first only".bright_blue());
+    } else if let Some(n) = args.max_frames {
+        info!("[FRAMES]        at most {}", n.to_string().bright_blue());
+    }
+
+    if let Some(n) = args.max_output_frames {
+        info!(
+            "[SPLIT OUTPUT]  {}",
+            format!("{} frames per file", n).bright_blue()
+        );
+    }
+
+    if let Some(n) = args.reference_from_frame {
+        info!("[FIXED FRAME]   {}", n.to_string().bright_blue());
+    }
+
+    if let Some(structure) = &args.align_to {
+        info!("[ALIGN TO]      {}", structure.bright_blue());
+    }
+
+    if let Some(target) = &args.abs_target {
+        info!("[TARGET]        {}", target.to_string().bright_blue());
+    }
+
+    if let Some(drift) = &args.report_drift {
+        info!("[DRIFT]         {}", drift.bright_blue());
+    }
+
+    if let Some(report) = &args.report_box {
+        info!("[BOX REPORT]    {}", report.bright_blue());
+    }
+
+    if let Some(stats) = &args.stats_jsonl {
+        info!("[STATS]         {}", stats.bright_blue());
+    }
+
+    if args.print_centroid {
+        info!("[CENTROID]      {}", "stdout".bright_blue());
+    }
+
+    if let Some(threshold) = args.halt_on_jump {
+        let action = if args.warn_on_jump { "warn" } else { "halt" };
+        info!(
+            "[JUMP LIMIT]    {}",
+            format!("{} nm ({})", threshold, action).bright_blue()
+        );
+    }
+
+    if args.clamp_outside {
+        let action = if args.fix_outside { "fix" } else { "check" };
+        info!("[OUTSIDE BOX]   {}", action.bright_blue());
+    }
+
+    if let Some(ndx) = &args.index_out {
+        info!("[INDEX OUT]     {}", ndx.bright_blue());
+    }
+
+    if let Some(cache) = &args.reference_cache {
+        info!("[REF CACHE]     {}", cache.bright_blue());
+    }
+
+    if args.allow_empty_reference {
+        info!("[EMPTY REF]     {}", "allowed".bright_blue());
+    }
+
+    if let Some(simbox) = &args.simbox {
+        let simbox = if args.keep_box {
+            format!("{} nm (all frames)", simbox)
+        } else {
+            format!("{} nm", simbox)
+        };
+        info!("[BOX]           {}", simbox.bright_blue());
+    }
+
+    if let Some(simbox) = &args.rescale_box {
+        let simbox = format!("{} nm", simbox);
+        info!("[RESCALE BOX]   {}", simbox.bright_blue());
+    }
+
+    if args.wrap_dims != Dimension::XYZ {
+        info!(
+            "[WRAP DIMS]     {}",
+            args.wrap_dims.to_string().bright_blue()
+        );
+    }
+
+    if let Some(dimension) = &args.mirror {
+        info!("[MIRROR]        {}", dimension.to_string().bright_blue());
+    }
+
+    if let Some(template) = &args.output_template_box {
+        info!("[OUTPUT BOX]    {}", template.to_string().bright_blue());
     }
 
     if args.com {
-        println!("[METHOD]        {}", "center of mass".bright_blue());
+        info!("[METHOD]        {}", "center of mass".bright_blue());
+    } else if args.median {
+        info!("[METHOD]        {}", "median".bright_blue());
+    } else if args.plane_fit {
+        info!("[METHOD]        {}", "membrane midplane (z)".bright_blue());
+    } else if args.by_residue_com {
+        info!(
+            "[METHOD]        {}",
+            "average of residue centers of mass".bright_blue()
+        );
+    } else if args.bbox_center {
+        info!("[METHOD]        {}", "bounding box center".bright_blue());
+    }
+
+    if let Some(elements) = &args.elements {
+        info!("[ELEMENTS]      {}", elements.bright_blue());
+    }
+
+    if let Some(masses) = &args.masses {
+        info!("[MASSES]        {}", masses.bright_blue());
+    }
+
+    if args.weights_from_bfactor {
+        info!("[WEIGHTS]       {}", "B-factors".bright_blue());
+    }
+
+    if args.center_iterations != 1 {
+        info!(
+            "[ITERATIONS]    {}",
+            args.center_iterations.to_string().bright_blue()
+        );
     }
 
     if args.whole {
-        println!("[WHOLE]         {}", "molecules".bright_blue())
+        info!("[WHOLE]         {}", "molecules".bright_blue())
+    }
+
+    if let Some(transform) = &args.transform {
+        info!("[TRANSFORM]     {}", transform.to_string().bright_blue());
     }
 
-    println!();
+    if let Some(threshold) = args.whole_threshold {
+        info!(
+            "[BOND LIMIT]    {}",
+            format!("{} nm", threshold).bright_blue()
+        );
+    }
+
+    if args.positions_only {
+        info!("[VELOCITIES]    {}", "not written".bright_blue());
+        info!("[FORCES]        {}", "not written".bright_blue());
+    } else if args.no_velocities {
+        info!("[VELOCITIES]    {}", "not written".bright_blue());
+    }
+
+    if let Some(expected) = &args.compare {
+        info!("[COMPARE]       {}", expected.bright_blue());
+    }
+
+    if let Some(precision) = args.output_precision {
+        info!("[PRECISION]     {}", precision.to_string().bright_blue());
+    }
+
+    match (args.time_offset, args.time_reset) {
+        (Some(offset), false) => {
+            info!("[TIME OFFSET]   {}", format!("{} ps", offset).bright_blue())
+        }
+        (Some(offset), true) => info!(
+            "[TIME RESET]    {}",
+            format!("from {} ps", offset).bright_blue()
+        ),
+        (None, true) => info!("[TIME RESET]    {}", "from 0 ps".bright_blue()),
+        (None, false) => (),
+    }
+
+    if args.renumber {
+        info!("[RENUMBER]      {}", "atoms and residues".bright_blue());
+    }
+
+    if args.pdb_ter {
+        info!("[PDB TER]       {}", "between chains".bright_blue());
+    }
+
+    if let Some(title) = &args.title {
+        info!("[TITLE]         {}", title.bright_blue());
+    }
+
+    info!("");
 }
 
-/// Guess elements for target system printing warnings (if not silent) and returning errors.
+/// Get the offset of the first byte that differs between two byte sequences.
+/// If one sequence is a prefix of the other, the offset is the length of the shorter sequence.
+/// Returns `None` if the sequences are identical.
+fn first_difference(a: &[u8], b: &[u8]) -> Option<usize> {
+    match a.iter().zip(b).position(|(x, y)| x != y) {
+        Some(offset) => Some(offset),
+        None if a.len() != b.len() => Some(a.len().min(b.len())),
+        None => None,
+    }
+}
+
+/// Move the temporary output file to its final name if the output has been completely written.
+/// This is also done if the centering has been interrupted since the output then only contains complete frames.
+/// Otherwise, the temporary output file is removed.
+/// If `compressed` is provided, the temporary file is compressed into it instead.
+fn finalize_output(
+    temporary: &str,
+    output: &str,
+    compressed: Option<&str>,
+    result: &Result<CenterStats, Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<(), RunError> {
+    let complete = match result {
+        Ok(_) => true,
+        Err(e) => matches!(
+            e.downcast_ref::<RunError>(),
+            Some(RunError::Interrupted(_, _))
+        ),
+    };
+
+    if let (true, Some(compressed)) = (complete, compressed) {
+        let result = gzip::compress(temporary, compressed)
+            .map_err(|_| RunError::CompressionFailed(compressed.to_owned()));
+        let _ = fs::remove_file(temporary);
+        result
+    } else if complete {
+        fs::rename(temporary, output)
+            .map_err(|_| RunError::OutputNotRenamed(temporary.to_owned(), output.to_owned()))
+    } else {
+        let _ = fs::remove_file(temporary);
+        Ok(())
+    }
+}
+
+/// Compare the output file with the expected file byte-by-byte.
+fn compare_files(output: &str, expected: &str) -> Result<(), RunError> {
+    let read = |file: &str| fs::read(file).map_err(|_| RunError::CompareNotRead(file.to_owned()));
+    let (output_bytes, expected_bytes) = (read(output)?, read(expected)?);
+
+    match first_difference(&output_bytes, &expected_bytes) {
+        None => Ok(()),
+        Some(offset) => Err(RunError::OutputDiffers(
+            output.to_owned(),
+            expected.to_owned(),
+            offset,
+        )),
+    }
+}
+
+/// Guess elements for target system logging warnings and returning errors.
+/// Custom `elements` are used instead of the default ones, if provided.
 fn guess_elements(
     system: &mut System,
-    silent: bool,
+    elements: Option<Elements>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    match system.guess_elements(Elements::default()) {
+    match system.guess_elements(elements.unwrap_or_default()) {
         Ok(_) => Ok(()),
         Err(ElementError::ElementGuessWarning(e)) => {
-            if !silent {
-                eprintln!("{}", ElementError::ElementGuessWarning(e));
-            }
+            warn!("{}", ElementError::ElementGuessWarning(e));
             Ok(())
         }
         Err(e) => Err(Box::from(e)),
     }
 }
 
+/// Check that no group read from the ndx file references atoms that are not present in the system.
+/// Returns an error naming the first offending group.
+fn check_ndx_indices(system: &System, ndx: &str) -> Result<(), RunError> {
+    let n_atoms = system.get_n_atoms();
+    for (name, group) in system.get_groups() {
+        if let Some(max) = group.get_atoms().iter().max() {
+            if max >= n_atoms {
+                return Err(RunError::IndexOutOfRange(
+                    name.to_owned(),
+                    ndx.to_owned(),
+                    max + 1,
+                    n_atoms,
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the type of the file from its extension. Unlike `FileType::from_name`, the case of the extension is ignored,
+/// i.e. 'output.XTC' is an xtc file.
+pub fn file_type(filename: impl AsRef<Path>) -> FileType {
+    let path = filename.as_ref();
+    match path.extension().and_then(|x| x.to_str()) {
+        Some(extension) => FileType::from_name(path.with_extension(extension.to_ascii_lowercase())),
+        None => FileType::Unknown,
+    }
+}
+
+/// Read a system from a structure file of any supported format.
+/// The case of the extension of the file is ignored.
+pub fn read_system(filename: &str) -> Result<System, Box<dyn std::error::Error + Send + Sync>> {
+    let system = if cif::is_cif(filename) {
+        cif::read_cif(filename)?
+    } else if g96::is_g96(filename) {
+        g96::read_g96(filename)?
+    } else {
+        match file_type(filename) {
+            FileType::GRO => groan_rs::io::gro_io::read_gro(filename)?,
+            FileType::PDB => groan_rs::io::pdb_io::read_pdb(filename)?,
+            FileType::PQR => groan_rs::io::pqr_io::read_pqr(filename)?,
+            FileType::TPR => groan_rs::io::tpr_io::read_tpr(filename)?,
+            _ => System::from_file(filename)?,
+        }
+    };
+
+    Ok(system)
+}
+
+/// Replace positions of atoms and the simulation box of the system with those from the coordinates file.
+/// Atoms are matched by their index.
+fn overlay_coordinates(
+    system: &mut System,
+    coordinates: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let source = read_system(coordinates)?;
+
+    if source.get_n_atoms() != system.get_n_atoms() {
+        return Err(Box::new(RunError::CoordinatesAtomsMismatch(
+            coordinates.to_owned(),
+            source.get_n_atoms(),
+            system.get_n_atoms(),
+        )));
+    }
+
+    for (atom, source_atom) in system.atoms_iter_mut().zip(source.atoms_iter()) {
+        match source_atom.get_position() {
+            Some(pos) => atom.set_position(pos.clone()),
+            None => atom.reset_position(),
+        }
+    }
+
+    match source.get_box_copy() {
+        Some(simbox) => system.set_box(simbox),
+        None => system.reset_box(),
+    }
+
+    Ok(())
+}
+
+/// Back up an existing file with the same name as the output file, unless overwriting is requested.
+fn backup_output(args: &Args, file: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if args.clean_backups {
+        remove_backups(file)?;
+    }
+
+    if !Path::new(file).exists() {
+        return Ok(());
+    }
+
+    if !args.overwrite {
+        let backup = match &args.backup_dir {
+            Some(directory) => backup_to_dir(file, directory)?,
+            None => backitup::backup(file)?,
+        };
+
+        debug!(
+            "{} backed up '{}' as '{}'\n",
+            "note:".purple().bold(),
+            file.yellow(),
+            backup.to_str().unwrap().yellow()
+        );
+    } else {
+        warn!(
+            "{} overwriting '{}'\n",
+            "warning:".yellow().bold(),
+            file.yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Move the file into the backup directory, creating the directory if needed.
+/// The backup is named using the name of the original file and the current timestamp.
+fn backup_to_dir(file: &str, directory: &str) -> Result<PathBuf, RunError> {
+    fs::create_dir_all(directory)
+        .map_err(|_| RunError::BackupDirNotCreated(directory.to_owned()))?;
+
+    let filename = Path::new(file)
+        .file_name()
+        .and_then(|x| x.to_str())
+        .ok_or_else(|| RunError::BackupFailed(file.to_owned(), directory.to_owned()))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0);
+
+    // make sure that no previous backup is overwritten
+    let mut backup = Path::new(directory).join(format!("#{}.{}#", filename, timestamp));
+    let mut n = 1;
+    while backup.exists() {
+        backup = Path::new(directory).join(format!("#{}.{}.{}#", filename, timestamp, n));
+        n += 1;
+    }
+
+    // renaming fails if the backup directory is located on a different filesystem
+    if fs::rename(file, &backup).is_err() {
+        fs::copy(file, &backup)
+            .and_then(|_| fs::remove_file(file))
+            .map_err(|_| RunError::BackupFailed(file.to_owned(), directory.to_owned()))?;
+    }
+
+    Ok(backup)
+}
+
+/// Check whether the name of a file corresponds to a backup of the file `original` created by `backitup`,
+/// i.e. '#<original>-<year>-<month>-<day>-<hour>-<minute>-<second>(-<microseconds>)#'.
+fn is_backup_of(name: &str, original: &str) -> bool {
+    let Some(timestamp) = name
+        .strip_prefix('#')
+        .and_then(|x| x.strip_suffix('#'))
+        .and_then(|x| x.strip_prefix(original))
+        .and_then(|x| x.strip_prefix('-'))
+    else {
+        return false;
+    };
+
+    let fields = timestamp.split('-').collect::<Vec<&str>>();
+    matches!(fields.len(), 6 | 7)
+        && fields
+            .iter()
+            .all(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Remove backups of the file located in the same directory as the file.
+fn remove_backups(file: &str) -> Result<(), RunError> {
+    let path = Path::new(file);
+    let Some(original) = path.file_name().and_then(|x| x.to_str()) else {
+        return Ok(());
+    };
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Ok(());
+    };
+
+    let mut backups = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|x| x.is_file()))
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| is_backup_of(name, original))
+        })
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>();
+    backups.sort();
+
+    for backup in backups {
+        let name = backup.to_string_lossy().into_owned();
+        fs::remove_file(&backup).map_err(|_| RunError::BackupNotRemoved(name.clone()))?;
+
+        info!(
+            "{} removed backup '{}'\n",
+            "note:".purple().bold(),
+            name.yellow()
+        );
+    }
+
+    Ok(())
+}
+
 /// Get the dimensions and reference selections in which the centering should be performed.
 fn construct_dimensions(args: &Args) -> Dimension {
     let refbool = [&args.xreference, &args.yreference, &args.zreference]
@@ -138,10 +738,12 @@ fn construct_dimensions(args: &Args) -> Dimension {
         .map(|x| x.is_some())
         .collect::<Vec<bool>>();
 
-    match (
-        [refbool[0], refbool[1], refbool[2]].into(),
-        [args.xdimension, args.ydimension, args.zdimension].into(),
-    ) {
+    let flags = match args.dimensions {
+        Some(dim) => dim,
+        None => [args.xdimension, args.ydimension, args.zdimension].into(),
+    };
+
+    match ([refbool[0], refbool[1], refbool[2]].into(), flags) {
         // if neither of the possible dimension specifications has been supplied, default to XYZ
         (Dimension::None, Dimension::None) => Dimension::XYZ,
         (a, b) => [
@@ -153,117 +755,400 @@ fn construct_dimensions(args: &Args) -> Dimension {
     }
 }
 
+/// Warn if a dimension-specific reference is provided for a dimension that is not selected explicitly.
+/// Such dimension is centered anyway since the dimensions of the references are merged with the explicit ones.
+fn check_dimension_references(args: &Args) {
+    let flags = match args.dimensions {
+        Some(dim) => dim,
+        None => [args.xdimension, args.ydimension, args.zdimension].into(),
+    };
+
+    // no explicit dimensions
+    if flags == Dimension::None {
+        return;
+    }
+
+    for ((reference, option), (selected, label)) in
+        [&args.xreference, &args.yreference, &args.zreference]
+            .into_iter()
+            .zip(["--xref", "--yref", "--zref"])
+            .zip([
+                (flags.is_x(), "x"),
+                (flags.is_y(), "y"),
+                (flags.is_z(), "z"),
+            ])
+    {
+        if reference.is_some() && !selected {
+            warn!(
+                "{} reference '{}' is provided but the {} dimension is not among the selected dimensions ({}); centering will also be performed in the {} dimension.\n",
+                "warning:".yellow().bold(),
+                option.yellow(),
+                label,
+                flags.to_string().yellow(),
+                label
+            );
+        }
+    }
+}
+
 /// Returns true if a query contains the "element" keyword or its alternatives.
 fn query_contains_element(query: &str) -> bool {
     query.contains("element") || query.contains("elname") || query.contains("elsymbol")
 }
 
+/// Returns true if a query contains the "mass" keyword.
+fn query_contains_mass(query: &str) -> bool {
+    query.contains("mass")
+}
+
+/// Returns true if a query contains the "charge" keyword.
+fn query_contains_charge(query: &str) -> bool {
+    query.contains("charge")
+}
+
 /// Assign elements and masses to atoms if this is required.
 fn guess_elements_masses(
     system: &mut System,
     args: &Args,
     input_file: FileType,
+    elements: Option<Elements>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let queries: Vec<&String> = [
+        &args.xreference,
+        &args.yreference,
+        &args.zreference,
+        &args.strip,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(args.reference_groups.iter().map(|group| &group.query))
+    .chain(std::iter::once(&args.reference))
+    .collect();
+
+    // masses are read from the file instead of being guessed; elements are only guessed if they are needed
+    if let Some(file) = &args.masses {
+        if input_file != FileType::TPR && queries.iter().any(|query| query_contains_element(query))
+        {
+            debug!(
+                "{} element keyword detected in a query; will guess elements...\n",
+                "note:".purple().bold()
+            );
+
+            guess_elements(system, elements)?;
+        }
+
+        return Ok(masses::assign_masses(system, file)?);
+    }
+
+    // B-factors are used as masses; elements are only guessed if they are needed
+    if args.weights_from_bfactor {
+        if queries.iter().any(|query| query_contains_element(query)) {
+            debug!(
+                "{} element keyword detected in a query; will guess elements...\n",
+                "note:".purple().bold()
+            );
+
+            guess_elements(system, elements)?;
+        }
+
+        return Ok(masses::assign_bfactor_weights(system, &args.structure)?);
+    }
+
     if input_file == FileType::TPR {
         return Ok(());
     }
 
-    if args.com {
-        if !args.silent {
-            println!("{} center of mass calculation requested; will guess elements and assign masses...\n", "note:".purple().bold());
-        }
+    // masses assigned based on the element symbols of a cif file are more reliable than guessed masses
+    if cif::is_cif(&args.structure) && system.atoms_iter().all(|atom| atom.get_mass().is_some()) {
+        return Ok(());
+    }
 
-        return guess_elements(system, args.silent);
+    // charges can not be guessed
+    if queries.iter().any(|query| query_contains_charge(query))
+        && system.atoms_iter().all(|atom| atom.get_charge().is_none())
+    {
+        debug!(
+            "{} charge keyword detected in a query but charges are not available in the input structure file '{}'; atoms will be treated as having no charge\n",
+            "note:".purple().bold(),
+            args.structure.yellow()
+        );
     }
 
-    for reference in [&args.xreference, &args.yreference, &args.zreference]
-        .into_iter()
-        .flatten()
+    let weighted_by_mass =
+        [args.xweighting, args.yweighting, args.zweighting].contains(&Some(Weighting::Mass));
+    let mass_in_query = queries.iter().any(|query| query_contains_mass(query));
+    if args.require_masses && (args.com || args.by_residue_com || weighted_by_mass || mass_in_query)
     {
-        if query_contains_element(reference) {
-            if !args.silent {
-                println!(
-                    "{} element keyword detected in a query; will guess elements...\n",
-                    "note:".purple().bold()
-                );
-            }
+        return Err(Box::new(RunError::MassesRequired(
+            args.structure.to_owned(),
+        )));
+    }
 
-            return guess_elements(system, args.silent);
-        }
+    if args.com || args.by_residue_com || weighted_by_mass {
+        debug!(
+            "{} center of mass calculation requested; will guess elements and assign masses...\n",
+            "note:".purple().bold()
+        );
+
+        return guess_elements(system, elements);
     }
 
-    if query_contains_element(&args.reference) {
-        if !args.silent {
-            println!(
-                "{} element keyword detected in a query; will guess elements...\n",
-                "note:".purple().bold()
-            );
-        }
+    if mass_in_query {
+        debug!(
+            "{} mass keyword detected in a query; will guess elements and assign masses...\n",
+            "note:".purple().bold()
+        );
 
-        return guess_elements(system, args.silent);
+        return guess_elements(system, elements);
+    }
+
+    if queries.iter().any(|query| query_contains_element(query)) {
+        debug!(
+            "{} element keyword detected in a query; will guess elements...\n",
+            "note:".purple().bold()
+        );
+
+        return guess_elements(system, elements);
     }
 
     Ok(())
 }
 
 /// Perform the centering.
-pub fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let args = argparse::parse()?;
+/// Interrupts caught by `interrupt` stop the centering of a trajectory after the current frame.
+/// Returns statistics about the performed centering.
+pub fn run(interrupt: &Interrupt) -> Result<CenterStats, Box<dyn std::error::Error + Send + Sync>> {
+    let mut args = argparse::parse()?;
+    log::set_max_level(logger::level_filter(args.verbosity()));
+    if args.print_centroid {
+        logger::reserve_stdout();
+    }
 
-    if !args.silent {
-        let version = format!("\n >> gcenter {} <<\n", env!("CARGO_PKG_VERSION"));
-        println!("{}", version.bold());
+    let version = format!("\n >> gcenter {} <<\n", env!("CARGO_PKG_VERSION"));
+    info!("{}", version.bold());
+
+    args.output_gz = gzip::compressed_output(&args);
+    stream::detect_fifo(&mut args);
+    stream::check_streamable(&args)?;
+
+    // skip the processing if the output already exists; an existing named pipe is the target of the stream
+    if args.keep_existing && !args.stream {
+        let output = match args.max_output_frames {
+            Some(_) => center::chunk_path(&args.output, 1),
+            None => args.final_output().to_owned(),
+        };
+
+        if Path::new(&output).exists() {
+            debug!(
+                "{} output file '{}' already exists; nothing to do\n",
+                "note:".purple().bold(),
+                output.yellow()
+            );
+
+            return Ok(CenterStats::default());
+        }
     }
 
     let dim = construct_dimensions(&args);
+    check_dimension_references(&args);
+
+    // read custom elements, if provided
+    let elements = match &args.elements {
+        Some(file) => Some(Elements::from_file(file)?),
+        None => None,
+    };
+
+    let mut benchmark = Benchmark::default();
+    let mut timer = Instant::now();
 
     // read structure file
-    let mut system = System::from_file(&args.structure)?;
+    let mut system = read_system(&args.structure)?;
+
+    if system.get_n_atoms() == 0 {
+        return Err(Box::new(RunError::EmptySystem(args.structure.to_owned())));
+    }
+
+    // replace coordinates from the structure file, if requested
+    if let Some(coordinates) = &args.coordinates {
+        overlay_coordinates(&mut system, coordinates)?;
+    }
+
+    // replace the simulation box, if requested
+    if let Some(simbox) = &args.simbox {
+        system.set_box(SimBox::from(simbox.0));
+    }
 
     // read ndx file
     system.read_ndx_with_default(&args.index, "index.ndx")?;
+    check_ndx_indices(&system, args.index.as_deref().unwrap_or("index.ndx"))?;
+    benchmark.add("loading structure", timer.elapsed());
 
     // print options
-    if !args.silent {
-        print_options(&args, &system, &dim);
-    }
-
-    // backup the output
-    if Path::new(&args.output).exists() {
-        if !args.overwrite {
-            let backup = backitup::backup(&args.output)?;
-
-            if !args.silent {
-                println!(
-                    "{} backed up '{}' as '{}'\n",
-                    "note:".purple().bold(),
-                    &args.output.yellow(),
-                    backup.to_str().unwrap().yellow()
-                );
-            }
-        } else if !args.silent {
-            println!(
-                "{} overwriting '{}'\n",
-                "warning:".yellow().bold(),
-                &args.output.yellow()
+    print_options(&args, &system, &dim);
+
+    if let Some(config) = &args.config {
+        debug!(
+            "{} default values of options read from configuration file '{}'\n",
+            "note:".purple().bold(),
+            config.yellow()
+        );
+    }
+
+    // explain options enabled using environment variables
+    for (enabled, option, variable) in [
+        (args.com_from_env, "--com", "GCENTER_COM"),
+        (args.whole_from_env, "--whole", "GCENTER_WHOLE"),
+    ] {
+        if enabled {
+            debug!(
+                "{} '{}' enabled by the environment variable '{}'\n",
+                "note:".purple().bold(),
+                option.bold(),
+                variable.yellow()
             );
         }
     }
 
+    // backup the output; files of the output split into chunks are backed up when they are created
+    // and a named pipe receiving the stream is never backed up
+    if args.max_output_frames.is_none() && !stream::is_fifo(args.final_output()) {
+        backup_output(&args, args.final_output())?;
+    }
+
     // guess elements and assign masses, if needed
-    let input_file_type = FileType::from_name(&args.structure);
-    guess_elements_masses(&mut system, &args, input_file_type)?;
+    timer = Instant::now();
+    let input_file_type = file_type(&args.structure);
+    guess_elements_masses(&mut system, &args, input_file_type, elements)?;
+    benchmark.add("guessing elements", timer.elapsed());
+    timer = Instant::now();
+
+    // select atoms to strip from the output
+    if let Some(query) = &args.strip {
+        reference::create_output_group(&mut system, query)?;
+    }
+
+    // place the reference at its position in another structure, if requested
+    if args.align_to.is_some() {
+        args.abs_target = center::align_target(&args)?;
+    }
+
+    // select reference atoms, reusing the groups from the cache, if possible
+    let cached = args
+        .reference_cache
+        .as_deref()
+        .and_then(|file| cache::read_cache(&mut system, dim, &args, file));
+    let operations = match cached {
+        Some(operations) => operations,
+        None => {
+            let operations = reference::create_references(&mut system, dim, &args)?;
+            if let Some(file) = &args.reference_cache {
+                cache::write_cache(&system, dim, &operations, &args, file)?;
+            }
+            operations
+        }
+    };
+
+    // check that the masses of the reference atoms are available, if they are read from a file
+    if args.masses.is_some() {
+        masses::check_reference_masses(&system, &operations, &args)?;
+    }
+
+    // check that no reference atoms are stripped
+    if args.strip.is_some() {
+        reference::check_stripped(&mut system, &operations, &args)?;
+    }
+
+    // write the reference groups, if requested
+    if let Some(ndx) = &args.index_out {
+        let main_name = reference::main_reference_name(&args);
+        reference::write_references(&system, &operations, ndx, &main_name)?;
+
+        let names = operations
+            .iter()
+            .map(|(name, _, _)| match name.as_str() {
+                MAIN_REFERENCE => format!("'{}'", main_name),
+                _ => format!("'{}'", name),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        debug!(
+            "{} reference group(s) {} written into '{}'\n",
+            "note:".purple().bold(),
+            names.yellow(),
+            ndx.yellow()
+        );
+    }
 
-    // select reference atoms
-    let operations = reference::create_references(&mut system, dim, &args)?;
+    benchmark.add("selecting references", timer.elapsed());
+
+    // decompress gzip-compressed trajectories; temporary files are removed at the end of the run
+    timer = Instant::now();
+    let decompressed = gzip::decompress_trajectories(&mut args, interrupt)?;
+    if !decompressed.is_empty() {
+        benchmark.add("decompressing", timer.elapsed());
+    }
+
+    // append atoms present in the trajectory but not in the structure, if requested
+    extra_atoms::extend_system(&mut system, &mut args);
+
+    // transcode trajectories of mixed formats; temporary files are removed at the end of the run
+    timer = Instant::now();
+    let transcoded = transcode::transcode_mixed(&system, &mut args, interrupt)?;
+    if !transcoded.is_empty() {
+        benchmark.add("transcoding", timer.elapsed());
+    }
+
+    // write the output into a temporary file so that an incomplete output file is never left under the final name
+    // the compressed output is always created from a temporary file; the streamed output never is
+    if !args.no_output
+        && (!args.no_atomic || args.output_gz.is_some())
+        && args.max_output_frames.is_none()
+        && !args.stream
+    {
+        args.output_tmp = Some(format!("{}.tmp", args.output));
+    }
 
     // perform centering
-    center::center(&mut system, &args, operations)?;
+    let result = center::center(&mut system, &args, operations, &mut benchmark, interrupt);
+    if let Some(temporary) = &args.output_tmp {
+        finalize_output(temporary, &args.output, args.output_gz.as_deref(), &result)?;
+    }
+    let stats = result?;
 
-    if !args.silent {
-        let result = format!("Successfully written output file '{}'.", &args.output);
-        println!("{}", result.green().bold());
+    match args.max_output_frames {
+        _ if args.no_output => (),
+        Some(size) => {
+            let n_files = stats.frames.div_ceil(size);
+            let result = format!(
+                "Successfully written {} output file(s) '{}' to '{}'.",
+                n_files,
+                center::chunk_path(&args.output, 1),
+                center::chunk_path(&args.output, n_files.max(1))
+            );
+            info!("{}", result.green().bold());
+        }
+        None => {
+            let result = format!(
+                "Successfully written output file '{}'.",
+                args.final_output()
+            );
+            info!("{}", result.green().bold());
+        }
     }
 
-    Ok(())
+    // compare the output with the expected file, if requested
+    if let Some(expected) = &args.compare {
+        compare_files(&args.output, expected)?;
+
+        let result = format!("Output file matches the expected file '{}'.", expected);
+        info!("{}", result.green().bold());
+    }
+
+    if args.benchmark {
+        info!("");
+        benchmark.print();
+    }
+
+    Ok(stats)
 }
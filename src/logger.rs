@@ -0,0 +1,74 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of the default logger used when `gcenter` is run as a binary.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::argparse::Verbosity;
+
+/// Logger writing the human-readable messages of `gcenter` into the terminal.
+/// Warnings and errors are written into stderr, all other messages are written into stdout
+/// unless stdout is reserved for data (see `reserve_stdout`).
+struct Logger;
+
+/// All messages are written into stderr.
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("gcenter") && metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match record.level() {
+            Level::Error | Level::Warn => eprintln!("{}", record.args()),
+            _ if STDOUT_RESERVED.load(Ordering::Relaxed) => eprintln!("{}", record.args()),
+            _ => println!("{}", record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+
+/// Install the default logger of `gcenter`.
+/// Does nothing if another logger has already been installed.
+pub fn init_logger() {
+    let _ = log::set_logger(&LOGGER);
+}
+
+/// Write all messages into stderr so that stdout only contains the data printed by `gcenter`.
+pub(crate) fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+/// Get the maximal level of log messages that should be written for the specified verbosity.
+pub(crate) fn level_filter(verbosity: Verbosity) -> LevelFilter {
+    match verbosity {
+        Verbosity::Silent => LevelFilter::Off,
+        Verbosity::Quiet => LevelFilter::Info,
+        Verbosity::Normal => LevelFilter::Debug,
+        Verbosity::Verbose => LevelFilter::Trace,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn level_filter_verbosity() {
+        assert_eq!(level_filter(Verbosity::Silent), LevelFilter::Off);
+        assert_eq!(level_filter(Verbosity::Quiet), LevelFilter::Info);
+        assert_eq!(level_filter(Verbosity::Normal), LevelFilter::Debug);
+        assert_eq!(level_filter(Verbosity::Verbose), LevelFilter::Trace);
+    }
+}
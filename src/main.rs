@@ -4,8 +4,13 @@
 use std::process;
 
 fn main() {
-    if let Err(e) = gcenter::run() {
-        eprintln!("{}", e);
+    gcenter::init_logger();
+
+    // interrupts are caught while centering trajectories so that the output remains valid
+    let result = gcenter::Interrupt::install().and_then(|interrupt| gcenter::run(&interrupt));
+
+    if let Err(e) = result {
+        gcenter::report_error(e.as_ref());
         process::exit(1);
     }
 
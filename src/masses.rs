@@ -0,0 +1,309 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of reading masses of atoms from a file or using B-factors of atoms as their weights.
+
+use std::collections::HashMap;
+
+use colored::Colorize;
+use groan_rs::system::System;
+use log::warn;
+
+use crate::argparse::{Args, Weighting};
+use crate::errors::RunError;
+use crate::reference::Operation;
+
+/// Masses read from a masses file.
+#[derive(Debug, Clone, PartialEq)]
+enum Masses {
+    /// Mass of each atom name.
+    ByName(HashMap<String, f32>),
+    /// Mass of each atom of the system in order.
+    ByIndex(Vec<f32>),
+}
+
+/// Parse a mass. Masses must be non-negative.
+fn parse_mass(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(x) if x.is_finite() && x >= 0.0 => Ok(x),
+        _ => Err(format!("invalid mass '{}'", s)),
+    }
+}
+
+/// Parse the content of a masses file.
+/// Each line contains either an atom name and its mass or only the mass of a single atom.
+/// Text following ';' or '#' is a comment.
+fn parse_masses(content: &str) -> Result<Masses, String> {
+    let mut by_name = HashMap::new();
+    let mut by_index = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split([';', '#']).next().unwrap_or_default();
+
+        match line.split_whitespace().collect::<Vec<&str>>()[..] {
+            [] => continue,
+            [mass] => by_index.push(parse_mass(mass)?),
+            [name, mass] => {
+                if by_name.insert(name.to_owned(), parse_mass(mass)?).is_some() {
+                    return Err(format!("atom name '{}' is listed multiple times", name));
+                }
+            }
+            _ => return Err(format!("could not parse line '{}'", line.trim())),
+        }
+    }
+
+    match (by_name.is_empty(), by_index.is_empty()) {
+        (true, true) => Err(String::from("no masses found")),
+        (false, false) => Err(String::from(
+            "lines with atom names and lines with only masses cannot be combined",
+        )),
+        (true, false) => Ok(Masses::ByIndex(by_index)),
+        (false, true) => Ok(Masses::ByName(by_name)),
+    }
+}
+
+/// Read masses from the file and assign them to the atoms of the system.
+/// Warns about atoms that have no mass after the assignment.
+pub fn assign_masses(system: &mut System, filename: &str) -> Result<(), RunError> {
+    let content = std::fs::read_to_string(filename).map_err(|_| {
+        RunError::MassesInvalid(filename.to_owned(), String::from("file could not be read"))
+    })?;
+
+    let masses =
+        parse_masses(&content).map_err(|e| RunError::MassesInvalid(filename.to_owned(), e))?;
+
+    match masses {
+        Masses::ByIndex(masses) => {
+            if masses.len() != system.get_n_atoms() {
+                return Err(RunError::MassesInvalid(
+                    filename.to_owned(),
+                    format!(
+                        "file contains {} mass(es) but the system contains {} atom(s)",
+                        masses.len(),
+                        system.get_n_atoms()
+                    ),
+                ));
+            }
+
+            for (atom, mass) in system.atoms_iter_mut().zip(masses) {
+                atom.set_mass(mass);
+            }
+        }
+        Masses::ByName(masses) => {
+            for atom in system.atoms_iter_mut() {
+                if let Some(mass) = masses.get(atom.get_atom_name()) {
+                    atom.set_mass(*mass);
+                }
+            }
+        }
+    }
+
+    let massless = system
+        .atoms_iter()
+        .filter(|atom| atom.get_mass().is_none())
+        .count();
+
+    if massless > 0 {
+        warn!(
+            "{} no mass assigned to {} atom(s) using the masses file '{}'.\n",
+            "warning:".yellow().bold(),
+            massless.to_string().yellow(),
+            filename.yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Parse the B-factors of atoms from the content of a pdb file.
+/// B-factors are read from columns 61-66 of the ATOM and HETATM records until the first END or ENDMDL record.
+fn parse_bfactors(content: &str) -> Result<Vec<f32>, String> {
+    let mut bfactors = Vec::new();
+
+    for line in content.lines() {
+        if line.starts_with("END") {
+            break;
+        }
+
+        if !line.starts_with("ATOM") && !line.starts_with("HETATM") {
+            continue;
+        }
+
+        let field = line.get(60..line.len().min(66)).unwrap_or_default().trim();
+
+        match field.parse::<f32>() {
+            Ok(x) if x.is_finite() && x >= 0.0 => bfactors.push(x),
+            _ if field.is_empty() => {
+                return Err(format!("atom {} has no B-factor", bfactors.len() + 1))
+            }
+            _ => {
+                return Err(format!(
+                    "invalid B-factor '{}' of atom {}",
+                    field,
+                    bfactors.len() + 1
+                ))
+            }
+        }
+    }
+
+    if bfactors.iter().all(|&x| x == 0.0) {
+        return Err(String::from("all B-factors are zero"));
+    }
+
+    Ok(bfactors)
+}
+
+/// Read B-factors from the pdb file and assign them to the atoms of the system as their masses.
+pub fn assign_bfactor_weights(system: &mut System, filename: &str) -> Result<(), RunError> {
+    let content = std::fs::read_to_string(filename).map_err(|_| {
+        RunError::BfactorsInvalid(filename.to_owned(), String::from("file could not be read"))
+    })?;
+
+    let bfactors =
+        parse_bfactors(&content).map_err(|e| RunError::BfactorsInvalid(filename.to_owned(), e))?;
+
+    if bfactors.len() != system.get_n_atoms() {
+        return Err(RunError::BfactorsInvalid(
+            filename.to_owned(),
+            format!(
+                "file contains {} B-factor(s) but the system contains {} atom(s)",
+                bfactors.len(),
+                system.get_n_atoms()
+            ),
+        ));
+    }
+
+    for (atom, bfactor) in system.atoms_iter_mut().zip(bfactors) {
+        atom.set_mass(bfactor);
+    }
+
+    Ok(())
+}
+
+/// Check that all atoms of the reference groups centered using their center of mass have a mass.
+pub fn check_reference_masses(
+    system: &System,
+    operations: &[Operation],
+    args: &Args,
+) -> Result<(), RunError> {
+    let mass_weighted = |weighting: &Option<Weighting>| match weighting {
+        Some(weighting) => *weighting == Weighting::Mass,
+        None => args.com || args.by_residue_com,
+    };
+
+    for (group, _, _) in operations
+        .iter()
+        .filter(|(_, _, weighting)| mass_weighted(weighting))
+    {
+        let massless = system
+            .group_iter(group)
+            .expect("\ngcenter: Fatal Error. Reference group does not exist.")
+            .filter(|atom| atom.get_mass().is_none())
+            .count();
+
+        if massless > 0 {
+            let name = match group.as_str() {
+                crate::MAIN_REFERENCE => crate::reference::main_reference_name(args),
+                _ => group.to_owned(),
+            };
+
+            return Err(RunError::ReferenceWithoutMass(name, massless));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_masses_by_name() {
+        let masses =
+            parse_masses("; Martini beads\nBB 72.0\nSC1 72.0 # side chain\n\nW 72.0\n").unwrap();
+
+        match masses {
+            Masses::ByName(masses) => {
+                assert_eq!(masses.len(), 3);
+                assert_eq!(masses.get("BB"), Some(&72.0));
+                assert_eq!(masses.get("W"), Some(&72.0));
+            }
+            Masses::ByIndex(_) => panic!("Masses should be assigned by name."),
+        }
+    }
+
+    #[test]
+    fn parse_masses_by_index() {
+        assert_eq!(
+            parse_masses("72.0\n54.0 ; TC5 bead\n0.0\n").unwrap(),
+            Masses::ByIndex(vec![72.0, 54.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn parse_masses_invalid() {
+        assert!(parse_masses("").is_err());
+        assert!(parse_masses("; only a comment\n").is_err());
+        assert!(parse_masses("BB 72.0\n54.0\n").is_err());
+        assert!(parse_masses("BB 72.0\nBB 54.0\n").is_err());
+        assert!(parse_masses("BB -72.0\n").is_err());
+        assert!(parse_masses("BB seventy\n").is_err());
+        assert!(parse_masses("BB 72.0 ASN\n").is_err());
+    }
+
+    #[test]
+    fn parse_bfactors_records() {
+        let content = "TITLE     Test\n\
+CRYST1   92.619   92.619  110.453  90.00  90.00  90.00 P 1           1\n\
+ATOM      1  BB  ASN     1      59.870  21.370  67.470  1.00  2.50            \n\
+HETATM    2  NA  ION     2      10.000  20.000  30.000  1.00 12.25\n\
+TER\n\
+ATOM      3  W   W       3      10.000  20.000  30.000  1.00  0.00            \n\
+ENDMDL\n\
+ATOM      1  BB  ASN     1      59.870  21.370  67.470  1.00  7.00            \n";
+
+        assert_eq!(parse_bfactors(content).unwrap(), vec![2.5, 12.25, 0.0]);
+    }
+
+    #[test]
+    fn parse_bfactors_invalid() {
+        let atom = "ATOM      1  BB  ASN     1      59.870  21.370  67.470  1.00";
+        assert!(parse_bfactors(&format!("{}\n", atom)).is_err());
+        assert!(parse_bfactors(&format!("{}  0.00\n{}  0.00\n", atom, atom)).is_err());
+        assert!(parse_bfactors(&format!("{} -1.00\n", atom)).is_err());
+        assert!(parse_bfactors(&format!("{}  abcd\n", atom)).is_err());
+        assert!(parse_bfactors("TITLE     Test\n").is_err());
+    }
+
+    #[test]
+    fn assign_masses_by_name() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        std::fs::write(path, "BB 72.0\nW 36.0\n").unwrap();
+        assign_masses(&mut system, path).unwrap();
+
+        for atom in system.atoms_iter() {
+            match atom.get_atom_name().as_str() {
+                "BB" => assert_eq!(atom.get_mass(), Some(72.0)),
+                "W" => assert_eq!(atom.get_mass(), Some(36.0)),
+                _ => assert_eq!(atom.get_mass(), None),
+            }
+        }
+    }
+
+    #[test]
+    fn assign_masses_count_mismatch() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        std::fs::write(path, "72.0\n72.0\n").unwrap();
+        assert!(matches!(
+            assign_masses(&mut system, path),
+            Err(RunError::MassesInvalid(_, _))
+        ));
+    }
+}
@@ -3,40 +3,197 @@
 
 //! Implementation of reference atoms selection.
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use colored::Colorize;
 use groan_rs::errors::GroupError;
 use groan_rs::structures::dimension::Dimension;
 use groan_rs::system::System;
+use log::warn;
 
-use crate::argparse::Args;
+use crate::argparse::{Args, Serials, Weighting};
 use crate::errors::RunError;
 
+/// Prefix marking a named selection in a query.
+const SELECTION_PREFIX: &str = "@@";
+
+/// Returns true if the character can be a part of the name of a named selection.
+fn is_selection_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Read named selections from a file with lines in the format 'name = query'.
+fn read_selections(filename: &str) -> Result<HashMap<String, String>, RunError> {
+    if !Path::new(filename).exists() {
+        return Err(RunError::SelectionFileNotFound(filename.to_owned()));
+    }
+
+    let content = std::fs::read_to_string(filename)
+        .map_err(|_| RunError::SelectionFileNotFound(filename.to_owned()))?;
+
+    let mut selections = HashMap::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, query) = line
+            .split_once('=')
+            .map(|(name, query)| (name.trim(), query.trim()))
+            .ok_or_else(|| RunError::SelectionFileInvalid(filename.to_owned(), i + 1))?;
+
+        if name.is_empty() || query.is_empty() || !name.chars().all(is_selection_name_char) {
+            return Err(RunError::SelectionFileInvalid(filename.to_owned(), i + 1));
+        }
+
+        selections.insert(name.to_owned(), query.to_owned());
+    }
+
+    Ok(selections)
+}
+
+/// Replace all named selections ('@@name') in a query with the corresponding queries.
+fn expand_query(
+    query: &str,
+    selections: &HashMap<String, String>,
+    filename: &str,
+) -> Result<String, RunError> {
+    let mut expanded = String::new();
+    let mut rest = query;
+
+    while let Some(start) = rest.find(SELECTION_PREFIX) {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + SELECTION_PREFIX.len()..];
+
+        let end = rest
+            .find(|c| !is_selection_name_char(c))
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+
+        match selections.get(name) {
+            Some(selection) => expanded.push_str(&format!("({})", selection)),
+            None => {
+                return Err(RunError::SelectionNotFound(
+                    name.to_owned(),
+                    filename.to_owned(),
+                ))
+            }
+        }
+
+        rest = &rest[end..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Expand named selections in all reference queries using the selection file.
+pub fn expand_selections(args: &mut Args) -> Result<(), RunError> {
+    let queries = [
+        Some(&mut args.reference),
+        args.xreference.as_mut(),
+        args.yreference.as_mut(),
+        args.zreference.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(
+        args.reference_groups
+            .iter_mut()
+            .map(|group| &mut group.query),
+    );
+
+    match &args.selection_file {
+        None => {
+            if let Some(query) = queries
+                .into_iter()
+                .find(|query| query.contains(SELECTION_PREFIX))
+            {
+                return Err(RunError::SelectionFileRequired(query.to_owned()));
+            }
+        }
+        Some(file) => {
+            let selections = read_selections(file)?;
+            for query in queries {
+                *query = expand_query(query, &selections, file)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Names of common groups, groan macros used to autodetect them if the groups do not exist,
+/// and the names of the autodetected atoms.
+const AUTODETECTED_GROUPS: [(&str, &str, &str); 4] = [
+    ("Protein", "@protein", "protein"),
+    ("Water", "@water", "water"),
+    ("Membrane", "@membrane", "membrane"),
+    ("Ion", "@ion", "ion"),
+];
+
+/// Get the macro and the name of the atoms used to autodetect the group, if autodetection is supported for it.
+fn autodetection(query: &str) -> Option<(&'static str, &'static str)> {
+    AUTODETECTED_GROUPS
+        .iter()
+        .find(|(name, _, _)| *name == query)
+        .map(|&(_, macro_name, atoms)| (macro_name, atoms))
+}
+
 /// Create the specified reference group.
-fn create_reference(
+/// If `invert` is true, the group contains all atoms NOT matching the query.
+/// Returns `false` if the group is empty and `allow_empty` is true.
+pub fn create_reference(
     system: &mut System,
     name: &str,
     query: &str,
-    silent: bool,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let autodetect = match system.group_create(name, query) {
+    invert: bool,
+    allow_empty: bool,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let full_query = if invert {
+        format!("not ({})", query)
+    } else {
+        query.to_owned()
+    };
+
+    let autodetected = match system.group_create(name, &full_query) {
         // ignore group overwrite
-        Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => false,
-        // if the reference group is 'Protein' and such group does not exist, try autodetecting the protein atoms
-        Err(GroupError::InvalidQuery(_)) if query == "Protein" => {
-            match system.group_create(name, "@protein") {
+        Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => None,
+        // if the reference group is a common group (e.g. 'Protein') and such group does not exist, try autodetecting its atoms
+        Err(GroupError::InvalidQuery(e)) => {
+            let (macro_name, atoms) = match autodetection(query) {
+                Some(x) => x,
+                None => return Err(Box::from(GroupError::InvalidQuery(e))),
+            };
+
+            let autodetect_query = if invert {
+                format!("not ({})", macro_name)
+            } else {
+                macro_name.to_owned()
+            };
+
+            match system.group_create(name, &autodetect_query) {
                 Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => {
-                    if !silent {
-                        println!(
-                            "{} group '{}' not found. Autodetected {} protein atoms.\n",
-                            "warning:".yellow().bold(),
-                            "Protein".yellow(),
-                            format!("{}", system.group_get_n_atoms(name).unwrap()).bright_blue()
-                        );
-                    }
-
-                    true
+                    let n_selected = system.group_get_n_atoms(name).unwrap();
+                    let n_detected = if invert {
+                        system.get_n_atoms() - n_selected
+                    } else {
+                        n_selected
+                    };
+
+                    warn!(
+                        "{} group '{}' not found. Autodetected {} {} atoms.\n",
+                        "warning:".yellow().bold(),
+                        query.yellow(),
+                        format!("{}", n_detected).bright_blue(),
+                        atoms
+                    );
+
+                    Some(atoms)
                 }
-                Err(_) => panic!("\ngcenter: Fatal Error. Autodetection failed."),
+                Err(_) => return Err(Box::new(RunError::AutodetectionFailed(atoms.to_owned()))),
             }
         }
         // propagate all the other errors
@@ -45,86 +202,297 @@ fn create_reference(
 
     // check that the reference group is not empty
     if system.group_get_n_atoms(name).unwrap() == 0 {
-        if !autodetect {
-            return Err(Box::new(RunError::EmptyReference(query.to_owned())));
-        } else {
-            return Err(Box::new(RunError::AutodetectionFailed));
+        match autodetected {
+            Some(atoms) if !invert => {
+                return Err(Box::new(RunError::AutodetectionFailed(atoms.to_owned())))
+            }
+            _ if allow_empty => return Ok(false),
+            _ => return Err(Box::new(RunError::EmptyReference(full_query))),
+        }
+    }
+
+    Ok(true)
+}
+
+/// Create the specified reference group from atom serial numbers.
+/// If `invert` is true, the group contains all atoms NOT listed.
+/// Returns `false` if the group is empty and `allow_empty` is true.
+fn create_reference_from_serials(
+    system: &mut System,
+    name: &str,
+    serials: &Serials,
+    invert: bool,
+    allow_empty: bool,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let n_atoms = system.get_n_atoms();
+    if serials.max() > n_atoms {
+        return Err(Box::new(RunError::SerialOutOfRange(serials.max(), n_atoms)));
+    }
+
+    let mut indices = serials.indices();
+    if invert {
+        let mut selected = vec![false; n_atoms];
+        indices.iter().for_each(|&i| selected[i] = true);
+        indices = (0..n_atoms).filter(|&i| !selected[i]).collect();
+    }
+
+    match system.group_create_from_indices(name, indices) {
+        // ignore group overwrite
+        Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => (),
+        Err(e) => return Err(Box::from(e)),
+    }
+
+    // the group can only be empty if it is inverted
+    if system.group_get_n_atoms(name).unwrap() == 0 {
+        if allow_empty {
+            return Ok(false);
+        }
+
+        return Err(Box::new(RunError::EmptyReference(format!(
+            "not (serials {})",
+            serials
+        ))));
+    }
+
+    Ok(true)
+}
+
+/// Default name of the main reference group written into an ndx file, if its query is not a single word.
+const DEFAULT_OUTPUT_GROUP_NAME: &str = "Centered";
+
+/// Get the name under which the main reference group is written into an ndx file.
+/// Uses the name provided by the user, the query of the main reference if it is a single word, or 'Centered'.
+pub fn main_reference_name(args: &Args) -> String {
+    if let Some(name) = &args.output_group_name {
+        return name.to_owned();
+    }
+
+    let simple_query = args.reference_groups.is_empty()
+        && args.reference_serials.is_none()
+        && !args.invert_selection
+        && !args.reference.is_empty()
+        && !args.reference.chars().any(char::is_whitespace);
+
+    if simple_query {
+        args.reference.to_owned()
+    } else {
+        DEFAULT_OUTPUT_GROUP_NAME.to_owned()
+    }
+}
+
+/// Get a description of the main reference group as specified by the user.
+fn main_reference_label(args: &Args) -> String {
+    if !args.reference_groups.is_empty() {
+        return args
+            .reference_groups
+            .iter()
+            .map(|group| group.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    let query = match &args.reference_serials {
+        Some(serials) => format!("serials {}", serials),
+        None => args.reference.clone(),
+    };
+
+    if args.invert_selection {
+        format!("not ({})", query)
+    } else {
+        query
+    }
+}
+
+/// Create the group of atoms to strip and the group of atoms to write into the output.
+pub fn create_output_group(
+    system: &mut System,
+    query: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for (name, full_query) in [
+        (crate::STRIPPED, query.to_owned()),
+        (crate::OUTPUT, format!("not ({})", query)),
+    ] {
+        match system.group_create(name, &full_query) {
+            Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => (),
+            Err(e) => return Err(Box::from(e)),
+        }
+    }
+
+    if system.group_get_n_atoms(crate::OUTPUT).unwrap() == 0 {
+        return Err(Box::new(RunError::StripAll(query.to_owned())));
+    }
+
+    Ok(())
+}
+
+/// Check that none of the reference groups contains stripped atoms.
+pub fn check_stripped(
+    system: &mut System,
+    operations: &[Operation],
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    const OVERLAP: &str = "CNTR-Overlap";
+
+    for (group, _, _) in operations {
+        match system.group_intersection(group, crate::STRIPPED, OVERLAP) {
+            Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => (),
+            Err(e) => return Err(Box::from(e)),
+        }
+
+        if system.group_get_n_atoms(OVERLAP).unwrap() != 0 {
+            let (query, invert) = match group.as_str() {
+                crate::X_REFERENCE => (args.xreference.clone(), args.xref_invert),
+                crate::Y_REFERENCE => (args.yreference.clone(), args.yref_invert),
+                crate::Z_REFERENCE => (args.zreference.clone(), args.zref_invert),
+                _ => (None, false),
+            };
+
+            let query = query.unwrap_or_else(|| main_reference_label(args));
+            let label = if invert {
+                format!("not ({})", query)
+            } else {
+                query
+            };
+
+            return Err(Box::new(RunError::StripReference(label)));
         }
     }
 
     Ok(())
 }
 
+/// Log a warning about an empty reference group and its consequence.
+fn empty_reference_warning(query: &str, consequence: &str) {
+    warn!(
+        "{} reference group '{}' is empty; {}.\n",
+        "warning:".yellow().bold(),
+        query.yellow(),
+        consequence
+    );
+}
+
 /// Check whether two groups contain the same atoms.
+/// Atoms are compared using their indices, so groups of different atoms sharing atom numbers and names are not the same.
+/// Empty or nonexistent groups are never the same as any other group.
 fn groups_are_same(system: &System, name1: &str, name2: &str) -> bool {
-    if system.group_get_n_atoms(name1).unwrap() != system.group_get_n_atoms(name2).unwrap() {
-        return false;
+    match (
+        system.group_get_n_atoms(name1),
+        system.group_get_n_atoms(name2),
+    ) {
+        (Ok(n1), Ok(n2)) if n1 != 0 && n1 == n2 => (),
+        _ => return false,
     }
 
-    // we should maybe check more properties
-    for (atom1, atom2) in system
+    system
         .group_iter(name1)
         .unwrap()
         .zip(system.group_iter(name2).unwrap())
-    {
-        if atom1.get_atom_number() != atom2.get_atom_number()
-            || atom1.get_atom_name() != atom2.get_atom_name()
-        {
-            return false;
+        .all(|(atom1, atom2)| atom1.get_index() == atom2.get_index())
+}
+
+/// Create a group containing all atoms that are NOT part of the `source` group.
+/// `label` describes the source group in error messages.
+/// Returns `false` if the group is empty and `allow_empty` is true.
+fn create_complement(
+    system: &mut System,
+    name: &str,
+    source: &str,
+    label: &str,
+    allow_empty: bool,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let mut selected = vec![false; system.get_n_atoms()];
+    system
+        .group_iter(source)?
+        .for_each(|atom| selected[atom.get_index()] = true);
+    let indices = (0..selected.len()).filter(|&i| !selected[i]).collect();
+
+    match system.group_create_from_indices(name, indices) {
+        // ignore group overwrite
+        Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => (),
+        Err(e) => return Err(Box::from(e)),
+    }
+
+    if system.group_get_n_atoms(name).unwrap() == 0 {
+        if allow_empty {
+            return Ok(false);
+        }
+
+        return Err(Box::new(RunError::EmptyReference(format!(
+            "not ({})",
+            label
+        ))));
+    }
+
+    Ok(true)
+}
+
+/// Get the name of the group created for the `index`th group contributing to the weighted reference.
+pub fn weighted_group_name(index: usize) -> String {
+    format!("{}-{}", crate::WEIGHTED_REFERENCE, index)
+}
+
+/// Create the groups contributing to the weighted reference and the main reference group containing all of their atoms.
+/// None of the groups can be empty.
+fn create_weighted_references(
+    system: &mut System,
+    args: &Args,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    for (i, group) in args.reference_groups.iter().enumerate() {
+        let name = weighted_group_name(i);
+        create_reference(system, &name, &group.query, false, false)?;
+
+        let previous = if i == 0 {
+            name.clone()
+        } else {
+            crate::MAIN_REFERENCE.to_owned()
+        };
+        match system.group_union(&previous, &name, crate::MAIN_REFERENCE) {
+            // ignore group overwrite
+            Ok(_) | Err(GroupError::AlreadyExistsWarning(_)) => (),
+            Err(e) => return Err(Box::from(e)),
         }
     }
 
-    true
+    Ok(true)
 }
 
+/// Centering operation: name of the reference group, dimensions to center in,
+/// and the weighting overriding the globally requested method (if any).
+pub type Operation = (String, Dimension, Option<Weighting>);
+
 /// Convert references to a vector of centering operations that should be performed.
+/// Dimensions using the same reference group with the same weighting are merged into a single operation.
 fn groups2operations<'a>(
     system: &'a System,
     mut groups: [Option<&'a str>; 3],
-) -> Vec<(String, Dimension)> {
+    weightings: [Option<Weighting>; 3],
+) -> Vec<Operation> {
     let mut operations = Vec::new();
-    if let Some(xref) = groups[0] {
-        let mut operation = (xref.to_owned(), [true, false, false]);
+    for i in 0..3 {
+        let reference = match groups[i] {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let mut dims = [false; 3];
+        dims[i] = true;
 
-        for (i, group) in groups.iter_mut().enumerate().skip(1) {
-            match group {
+        for j in (i + 1)..3 {
+            match groups[j] {
                 None => (),
                 Some(next) => {
-                    if groups_are_same(system, xref, next) {
-                        operation.1[i] = true;
-                        *group = None;
+                    if weightings[i] == weightings[j] && groups_are_same(system, reference, next) {
+                        dims[j] = true;
+                        groups[j] = None;
                     }
                 }
             }
         }
 
-        operations.push(operation);
-    }
-
-    if let Some(yref) = groups[1] {
-        let mut operation = (yref.to_owned(), [false, true, false]);
-
-        match groups[2] {
-            None => (),
-            Some(next) => {
-                if groups_are_same(system, yref, next) {
-                    operation.1[2] = true;
-                    groups[2] = None;
-                }
-            }
-        }
-
-        operations.push(operation);
-    }
-
-    if let Some(zref) = groups[2] {
-        operations.push((zref.to_owned(), [false, false, true]));
+        operations.push((reference.to_owned(), dims.into(), weightings[i]));
     }
 
     operations
-        .into_iter()
-        .map(|x| (x.0, x.1.into()))
-        .collect::<Vec<(String, Dimension)>>()
 }
 
 /// Select reference atoms for centering.
@@ -133,43 +501,155 @@ pub fn create_references(
     system: &mut System,
     dim: Dimension,
     args: &Args,
-) -> Result<Vec<(String, Dimension)>, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<Vec<Operation>, Box<dyn std::error::Error + Send + Sync>> {
+    // no centering requested
+    if args.reference == crate::NO_REFERENCE {
+        return Ok(Vec::new());
+    }
+
     // create the main reference group if it is required
+    let mut main_exists = false;
     if (args.xreference.is_none() && dim.is_x())
         || (args.yreference.is_none() && dim.is_y())
         || (args.zreference.is_none() && dim.is_z())
     {
-        create_reference(system, crate::MAIN_REFERENCE, &args.reference, args.silent)?;
+        main_exists = match &args.reference_serials {
+            None if !args.reference_groups.is_empty() => create_weighted_references(system, args)?,
+            Some(serials) => create_reference_from_serials(
+                system,
+                crate::MAIN_REFERENCE,
+                serials,
+                args.invert_selection,
+                args.allow_empty_reference,
+            )?,
+            None => create_reference(
+                system,
+                crate::MAIN_REFERENCE,
+                &args.reference,
+                args.invert_selection,
+                args.allow_empty_reference,
+            )?,
+        };
+
+        if !main_exists {
+            empty_reference_warning(
+                &main_reference_label(args),
+                "no centering will be performed in the dimension(s) it is used for",
+            );
+        }
     }
 
     // no dimension-specific groups
-    if args.xreference.is_none() && args.yreference.is_none() && args.zreference.is_none() {
-        return Ok(vec![(crate::MAIN_REFERENCE.to_owned(), dim)]);
+    if args.xreference.is_none()
+        && args.yreference.is_none()
+        && args.zreference.is_none()
+        && !args.xref_invert
+        && !args.yref_invert
+        && !args.zref_invert
+    {
+        if main_exists {
+            return Ok(vec![(crate::MAIN_REFERENCE.to_owned(), dim, None)]);
+        } else {
+            return Ok(Vec::new());
+        }
     }
 
     // create dimension-specific reference groups
+    // the weighting only applies to the dimension-specific reference itself, not to the main reference used as a fallback
     let mut references = [None; 3];
-    for (i, ((query, name), dimension)) in [&args.xreference, &args.yreference, &args.zreference]
-        .into_iter()
-        .zip([crate::X_REFERENCE, crate::Y_REFERENCE, crate::Z_REFERENCE].into_iter())
-        .zip([dim.is_x(), dim.is_y(), dim.is_z()].into_iter())
-        .enumerate()
+    let mut weightings = [None; 3];
+    for (i, ((((query, name), dimension), label), invert)) in
+        [&args.xreference, &args.yreference, &args.zreference]
+            .into_iter()
+            .zip([crate::X_REFERENCE, crate::Y_REFERENCE, crate::Z_REFERENCE].into_iter())
+            .zip([dim.is_x(), dim.is_y(), dim.is_z()].into_iter())
+            .zip(["x", "y", "z"])
+            .zip([args.xref_invert, args.yref_invert, args.zref_invert])
+            .enumerate()
     {
         if !dimension {
             continue;
         }
 
         match query {
-            None => references[i] = Some(crate::MAIN_REFERENCE),
+            None if main_exists && invert => {
+                if create_complement(
+                    system,
+                    name,
+                    crate::MAIN_REFERENCE,
+                    &main_reference_label(args),
+                    args.allow_empty_reference,
+                )? {
+                    references[i] = Some(name);
+                } else {
+                    empty_reference_warning(
+                        &format!("not ({})", main_reference_label(args)),
+                        &format!("no centering will be performed in the {} dimension", label),
+                    );
+                }
+            }
+            None if main_exists => references[i] = Some(crate::MAIN_REFERENCE),
+            None => (),
             Some(x) => {
-                create_reference(system, name, x, args.silent)?;
-                references[i] = Some(name);
+                let query = if invert {
+                    format!("not ({})", x)
+                } else {
+                    x.to_owned()
+                };
+
+                if create_reference(system, name, x, invert, args.allow_empty_reference)? {
+                    references[i] = Some(name);
+                    weightings[i] = [args.xweighting, args.yweighting, args.zweighting][i];
+                } else if main_exists {
+                    // fall back to the main reference
+                    empty_reference_warning(
+                        &query,
+                        &format!("the main reference will be used in the {} dimension", label),
+                    );
+                    references[i] = Some(crate::MAIN_REFERENCE);
+                } else {
+                    empty_reference_warning(
+                        &query,
+                        &format!("no centering will be performed in the {} dimension", label),
+                    );
+                }
             }
         }
     }
 
     // convert references to list of operations to perform
-    Ok(groups2operations(system, references))
+    Ok(groups2operations(system, references, weightings))
+}
+
+/// Write the reference groups used for centering into an ndx file.
+/// The main reference group is written under the name `main_name`.
+pub fn write_references(
+    system: &System,
+    operations: &[Operation],
+    filename: &str,
+    main_name: &str,
+) -> Result<(), RunError> {
+    let groups = system.get_groups_copy();
+    let mut content = Vec::new();
+
+    for (name, _, _) in operations {
+        let written_name = match name.as_str() {
+            crate::MAIN_REFERENCE => main_name,
+            _ => name,
+        };
+
+        match groups.get(name) {
+            Some(group) => group
+                .write_ndx(&mut content, written_name)
+                .map_err(|_| RunError::IndexNotWritten(filename.to_owned()))?,
+            None => panic!(
+                "\ngcenter: Fatal Error. Reference group '{}' does not exist.",
+                name
+            ),
+        }
+    }
+
+    std::fs::write(filename, content).map_err(|_| RunError::IndexNotWritten(filename.to_owned()))
 }
 
 #[cfg(test)]
@@ -177,6 +657,59 @@ mod test {
     use clap::Parser;
 
     use super::*;
+    use crate::test_utils::system_from_positions;
+
+    #[test]
+    fn create_references_none() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "-r=none",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        assert!(operations.is_empty());
+        assert!(!system.group_exists(crate::MAIN_REFERENCE));
+    }
+
+    #[test]
+    fn autodetection_common_groups() {
+        assert_eq!(autodetection("Protein"), Some(("@protein", "protein")));
+        assert_eq!(autodetection("Water"), Some(("@water", "water")));
+        assert_eq!(autodetection("Membrane"), Some(("@membrane", "membrane")));
+        assert_eq!(autodetection("Ion"), Some(("@ion", "ion")));
+        assert_eq!(autodetection("Lipids"), None);
+        assert_eq!(autodetection("protein"), None);
+    }
+
+    #[test]
+    fn create_reference_autodetect_water() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+
+        assert!(
+            create_reference(&mut system, crate::MAIN_REFERENCE, "Water", false, false).unwrap()
+        );
+        assert_eq!(
+            system.group_get_n_atoms(crate::MAIN_REFERENCE).unwrap(),
+            5097
+        );
+    }
+
+    #[test]
+    fn create_reference_autodetection_failed() {
+        let mut system = System::from_file("tests/test_files/input_aa_peptide.gro").unwrap();
+
+        let error = create_reference(&mut system, crate::MAIN_REFERENCE, "Membrane", false, true)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            RunError::AutodetectionFailed(String::from("membrane")).to_string()
+        );
+    }
 
     #[test]
     fn create_references_main() {
@@ -251,6 +784,33 @@ mod test {
         assert_eq!(operations[1].1, Dimension::YZ);
     }
 
+    #[test]
+    fn create_references_same_different_weighting() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "--xref=Protein",
+            "--yref=Protein",
+            "-o=output.gro",
+        ];
+        let mut args = Args::parse_from(command_line);
+        args.xweighting = Some(Weighting::Mass);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        system.read_ndx("tests/test_files/index.ndx").unwrap();
+
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        // identical groups are not merged if they use different weightings
+        assert_eq!(operations.len(), 2);
+        assert_eq!(&operations[0].0, crate::X_REFERENCE);
+        assert_eq!(operations[0].1, Dimension::X);
+        assert_eq!(operations[0].2, Some(Weighting::Mass));
+        assert_eq!(&operations[1].0, crate::Y_REFERENCE);
+        assert_eq!(operations[1].1, Dimension::YZ);
+        assert_eq!(operations[1].2, None);
+    }
+
     #[test]
     fn create_references_yzmain_xref_same() {
         let command_line = [
@@ -1045,4 +1605,343 @@ mod test {
         assert_eq!(&operations[0].0, crate::Z_REFERENCE);
         assert_eq!(operations[0].1, Dimension::Z);
     }
+
+    #[test]
+    fn create_references_empty_zref_fallback() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "--zref=resname XYZ",
+            "--allow-empty-reference",
+            "-o=output.gro",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        assert_eq!(operations.len(), 1);
+        assert_eq!(&operations[0].0, crate::MAIN_REFERENCE);
+        assert_eq!(operations[0].1, Dimension::XYZ);
+    }
+
+    #[test]
+    fn create_references_empty_zref_skipped() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "--xref=@membrane",
+            "--zref=resname XYZ",
+            "--allow-empty-reference",
+            "-o=output.gro",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+
+        let operations = create_references(&mut system, Dimension::XZ, &args).unwrap();
+
+        assert!(!system.group_exists(crate::MAIN_REFERENCE));
+        assert_eq!(operations.len(), 1);
+        assert_eq!(&operations[0].0, crate::X_REFERENCE);
+        assert_eq!(operations[0].1, Dimension::X);
+    }
+
+    #[test]
+    fn create_references_empty_main() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-r=resname XYZ",
+            "--allow-empty-reference",
+            "-o=output.gro",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+        assert!(operations.is_empty());
+    }
+
+    #[test]
+    fn create_references_empty_strict() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "--zref=resname XYZ",
+            "-o=output.gro",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+
+        assert!(create_references(&mut system, Dimension::XYZ, &args).is_err());
+    }
+
+    #[test]
+    fn create_references_serials() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "--reference-serials=3,5,7-12",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        assert_eq!(operations.len(), 1);
+        let indices = system
+            .group_iter(crate::MAIN_REFERENCE)
+            .unwrap()
+            .map(|atom| atom.get_index())
+            .collect::<Vec<usize>>();
+        assert_eq!(indices, vec![2, 4, 6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn create_references_serials_inverted_empty() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "--reference-serials=1-8327",
+            "--invert-selection",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        assert!(create_references(&mut system, Dimension::XYZ, &args).is_err());
+    }
+
+    #[test]
+    fn create_references_main_yzinverted() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "-r=@protein",
+            "--yref-invert",
+            "--zref-invert",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        assert!(!system.group_exists(crate::X_REFERENCE));
+        assert_eq!(system.group_get_n_atoms(crate::MAIN_REFERENCE).unwrap(), 42);
+        assert_eq!(system.group_get_n_atoms(crate::Y_REFERENCE).unwrap(), 8285);
+        assert_eq!(system.group_get_n_atoms(crate::Z_REFERENCE).unwrap(), 8285);
+
+        assert_eq!(operations.len(), 2);
+        assert_eq!(&operations[0].0, crate::MAIN_REFERENCE);
+        assert_eq!(operations[0].1, Dimension::X);
+        assert_eq!(&operations[1].0, crate::Y_REFERENCE);
+        assert_eq!(operations[1].1, Dimension::YZ);
+    }
+
+    #[test]
+    fn create_references_xref_inverted() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "-r=@protein",
+            "--xref=@membrane",
+            "--xref-invert",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        system.group_create("Membrane", "@membrane").unwrap();
+        let n_membrane = system.group_get_n_atoms("Membrane").unwrap();
+        assert_eq!(
+            system.group_get_n_atoms(crate::X_REFERENCE).unwrap(),
+            8327 - n_membrane
+        );
+
+        assert_eq!(operations.len(), 2);
+        assert_eq!(&operations[0].0, crate::X_REFERENCE);
+        assert_eq!(operations[0].1, Dimension::X);
+        assert_eq!(&operations[1].0, crate::MAIN_REFERENCE);
+        assert_eq!(operations[1].1, Dimension::YZ);
+    }
+
+    #[test]
+    fn create_references_inverted_empty() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "-r=all",
+            "--zref-invert",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let error = create_references(&mut system, Dimension::XYZ, &args).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            RunError::EmptyReference("not (all)".to_owned()).to_string()
+        );
+    }
+
+    #[test]
+    fn create_references_inverted_empty_allowed() {
+        let command_line = [
+            "gcenter",
+            "-s=tests/test_files/input.gro",
+            "-o=output.gro",
+            "-r=all",
+            "--zref-invert",
+            "--allow-empty-reference",
+        ];
+        let args = Args::parse_from(command_line);
+
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let operations = create_references(&mut system, Dimension::XYZ, &args).unwrap();
+
+        assert_eq!(operations.len(), 1);
+        assert_eq!(&operations[0].0, crate::MAIN_REFERENCE);
+        assert_eq!(operations[0].1, Dimension::XY);
+    }
+
+    #[test]
+    fn read_selections_valid() {
+        let selections = read_selections("tests/test_files/selections.sel").unwrap();
+
+        assert_eq!(selections.len(), 3);
+        assert_eq!(selections.get("membrane").unwrap(), "resname POPC");
+        assert_eq!(selections.get("protein-bb").unwrap(), "Protein and name BB");
+        assert_eq!(selections.get("water").unwrap(), "resname W");
+    }
+
+    #[test]
+    fn read_selections_invalid() {
+        assert_eq!(
+            read_selections("tests/test_files/selections_invalid.sel"),
+            Err(RunError::SelectionFileInvalid(
+                "tests/test_files/selections_invalid.sel".to_owned(),
+                2
+            ))
+        );
+
+        assert_eq!(
+            read_selections("tests/test_files/nonexistent.sel"),
+            Err(RunError::SelectionFileNotFound(
+                "tests/test_files/nonexistent.sel".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn expand_query_selections() {
+        let selections = read_selections("tests/test_files/selections.sel").unwrap();
+        let file = "selections.sel";
+
+        assert_eq!(
+            expand_query("@@membrane", &selections, file).unwrap(),
+            "(resname POPC)"
+        );
+        assert_eq!(
+            expand_query("@@protein-bb or @@water", &selections, file).unwrap(),
+            "(Protein and name BB) or (resname W)"
+        );
+        assert_eq!(
+            expand_query("not (@@water)", &selections, file).unwrap(),
+            "not ((resname W))"
+        );
+        assert_eq!(
+            expand_query("@protein", &selections, file).unwrap(),
+            "@protein"
+        );
+        assert_eq!(
+            expand_query("@@lipids", &selections, file),
+            Err(RunError::SelectionNotFound(
+                "lipids".to_owned(),
+                file.to_owned()
+            ))
+        );
+    }
+
+    /// Create a system of two residues with identical atom numbers and names.
+    fn system_duplicate_residues() -> System {
+        let positions = [1.0, 1.0, 2.0, 2.0].map(|x| [x, 1.0, 1.0]);
+        let mut system = system_from_positions("Duplicate", &positions, 5.0);
+
+        let numbers = [(1, 1), (1, 2), (2, 1), (2, 2)];
+        for (atom, (residue, number)) in system.atoms_iter_mut().zip(numbers) {
+            atom.set_residue_number(residue);
+            atom.set_atom_number(number);
+        }
+
+        system
+    }
+
+    #[test]
+    fn groups_are_same_duplicate_names() {
+        let mut system = system_duplicate_residues();
+        system.group_create("First", "resid 1").unwrap();
+        system.group_create("Second", "resid 2").unwrap();
+        system.group_create("FirstAgain", "serial 1 2").unwrap();
+
+        assert!(!groups_are_same(&system, "First", "Second"));
+        assert!(groups_are_same(&system, "First", "FirstAgain"));
+    }
+
+    #[test]
+    fn groups_are_same_empty() {
+        let mut system = system_duplicate_residues();
+        system.group_create("Empty", "resid 3").unwrap();
+        system.group_create("EmptyAgain", "resname POPC").unwrap();
+
+        assert!(!groups_are_same(&system, "Empty", "EmptyAgain"));
+        assert!(!groups_are_same(&system, "Empty", "all"));
+        assert!(!groups_are_same(&system, "Missing", "all"));
+    }
+
+    #[test]
+    fn groups2operations_duplicate_names_not_merged() {
+        let mut system = system_duplicate_residues();
+        system.group_create("First", "resid 1").unwrap();
+        system.group_create("Second", "resid 2").unwrap();
+
+        let operations = groups2operations(
+            &system,
+            [Some("First"), Some("Second"), Some("First")],
+            [None; 3],
+        );
+
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].0, "First");
+        assert_eq!(operations[0].1, Dimension::XZ);
+        assert_eq!(operations[1].0, "Second");
+        assert_eq!(operations[1].1, Dimension::Y);
+    }
+
+    #[test]
+    fn main_reference_name_default() {
+        let base = ["gcenter", "-s=tests/test_files/input.gro", "-o=output.gro"];
+
+        let args = Args::parse_from(base);
+        assert_eq!(main_reference_name(&args), "Protein");
+
+        let args = Args::parse_from(base.into_iter().chain(["-r=resname POPC"]));
+        assert_eq!(main_reference_name(&args), "Centered");
+
+        let args = Args::parse_from(
+            base.into_iter()
+                .chain(["-r=Membrane", "--invert-selection"]),
+        );
+        assert_eq!(main_reference_name(&args), "Centered");
+
+        let args = Args::parse_from(base.into_iter().chain(["--output-group-name=Lipids"]));
+        assert_eq!(main_reference_name(&args), "Lipids");
+    }
 }
@@ -0,0 +1,114 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of streaming the output into named pipes.
+
+use std::path::Path;
+
+use colored::Colorize;
+use groan_rs::files::FileType;
+use log::debug;
+
+use crate::argparse::Args;
+use crate::errors::RunError;
+
+/// Check whether the file is a named pipe (FIFO).
+#[cfg(unix)]
+pub fn is_fifo(filename: impl AsRef<Path>) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    std::fs::metadata(filename).is_ok_and(|metadata| metadata.file_type().is_fifo())
+}
+
+/// Check whether the file is a named pipe (FIFO). Named pipes are only detected on unix systems.
+#[cfg(not(unix))]
+pub fn is_fifo(_filename: impl AsRef<Path>) -> bool {
+    false
+}
+
+/// Stream the output if the output file is a named pipe.
+/// The output split into chunks or compressed using gzip is never written into the output file itself.
+pub fn detect_fifo(args: &mut Args) {
+    if args.stream
+        || args.no_output
+        || args.max_output_frames.is_some()
+        || args.gzip_output
+        || !is_fifo(&args.output)
+    {
+        return;
+    }
+
+    debug!(
+        "{} output file '{}' is a named pipe; the output will be streamed\n",
+        "note:".purple().bold(),
+        args.output.yellow()
+    );
+
+    args.stream = true;
+}
+
+/// Check that the frames of the streamed output can be flushed as soon as they are written.
+/// Only trr trajectories are written by a writer that flushes each frame;
+/// the output written from a structure file is complete once the single frame is written.
+pub fn check_streamable(args: &Args) -> Result<(), RunError> {
+    if !args.stream
+        || args.trajectories.is_empty()
+        || crate::file_type(&args.output) == FileType::TRR
+    {
+        return Ok(());
+    }
+
+    Err(RunError::StreamUnsupported(args.output.to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn streamable_formats() {
+        let args = |output: &str, trajectory: bool| {
+            let mut command_line = vec![
+                "gcenter",
+                "-s=tests/test_files/input.gro",
+                output,
+                "--stream",
+            ];
+            if trajectory {
+                command_line.push("-f=tests/test_files/input.xtc");
+            }
+            Args::parse_from(command_line)
+        };
+
+        assert!(check_streamable(&args("-o=output.trr", true)).is_ok());
+        assert!(check_streamable(&args("-o=output.gro", false)).is_ok());
+        assert_eq!(
+            check_streamable(&args("-o=output.xtc", true)),
+            Err(RunError::StreamUnsupported(String::from("output.xtc")))
+        );
+        assert!(check_streamable(&args("-o=output.gro", true)).is_err());
+    }
+
+    #[test]
+    fn regular_file_is_not_fifo() {
+        assert!(!is_fifo("tests/test_files/input.gro"));
+        assert!(!is_fifo("tests/test_files/nonexistent.gro"));
+        assert!(!is_fifo("tests/test_files"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fifo_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pipe.xtc");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        assert!(is_fifo(&path));
+    }
+}
@@ -0,0 +1,97 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of temporary trajectory files created while preparing the input trajectories.
+
+use std::path::Path;
+
+use crate::interrupt::Interrupt;
+
+/// Temporary files removed when this structure is dropped.
+/// The files are also registered with the interrupt handler which removes them if the program is interrupted.
+#[derive(Debug)]
+pub struct TemporaryFiles {
+    files: Vec<String>,
+    interrupt: Interrupt,
+}
+
+impl TemporaryFiles {
+    /// Create an empty collection of temporary files registered with the provided interrupt handler.
+    pub fn new(interrupt: &Interrupt) -> Self {
+        TemporaryFiles {
+            files: Vec::new(),
+            interrupt: interrupt.clone(),
+        }
+    }
+
+    /// Check whether any temporary file has been created.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Get the path to a new temporary file and register it for removal.
+    /// The file is placed into the directory of the output file and named after `purpose`, e.g. 'decompressed'.
+    /// The file is registered before it is created so that it is removed even if writing it fails.
+    pub fn create(&mut self, output: &str, purpose: &str, index: usize, extension: &str) -> String {
+        let path = temporary_path(output, purpose, index, extension);
+        self.interrupt.register(&path);
+        self.files.push(path.clone());
+        path
+    }
+}
+
+impl Drop for TemporaryFiles {
+    fn drop(&mut self) {
+        for file in self.files.iter() {
+            let _ = std::fs::remove_file(file);
+            self.interrupt.unregister(file);
+        }
+    }
+}
+
+/// Get the path to a temporary file placed into the directory of the output file.
+fn temporary_path(output: &str, purpose: &str, index: usize, extension: &str) -> String {
+    Path::new(output)
+        .with_file_name(format!(
+            ".gcenter_{}_{}_{}.{}",
+            purpose,
+            std::process::id(),
+            index,
+            extension
+        ))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn temporary_in_output_directory() {
+        let path = temporary_path("results/output.xtc", "decompressed", 2, "trr");
+        assert!(path.starts_with("results/.gcenter_decompressed_"));
+        assert!(path.ends_with("_2.trr"));
+    }
+
+    #[test]
+    fn temporary_removed_on_drop() {
+        let dir = std::env::temp_dir().join(format!("gcenter_temporary_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("output.xtc");
+
+        let path = {
+            let mut files = TemporaryFiles::new(&Interrupt::default());
+            assert!(files.is_empty());
+
+            let path = files.create(output.to_str().unwrap(), "transcoded", 1, "xtc");
+            std::fs::write(&path, "frame").unwrap();
+            assert!(!files.is_empty());
+            assert!(Path::new(&path).exists());
+            path
+        };
+
+        assert!(!Path::new(&path).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
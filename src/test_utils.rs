@@ -0,0 +1,21 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Helper functions shared by the unit tests.
+
+use groan_rs::structures::{atom::Atom, simbox::SimBox, vector3d::Vector3D};
+use groan_rs::system::System;
+
+/// Create a system of atoms placed at the provided positions in a cubic box with the provided edge (in nm).
+/// All atoms are named 'A', are numbered consecutively from 1, and belong to residue 1 named 'RES'.
+pub fn system_from_positions(title: &str, positions: &[[f32; 3]], edge: f32) -> System {
+    let atoms = positions
+        .iter()
+        .enumerate()
+        .map(|(i, &[x, y, z])| {
+            Atom::new(1, "RES", i + 1, "A").with_position(Vector3D::new(x, y, z))
+        })
+        .collect();
+
+    System::new(title, atoms, Some(SimBox::from([edge, edge, edge])))
+}
@@ -0,0 +1,126 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of transcoding trajectories so that xtc and trr files can be concatenated.
+
+use colored::Colorize;
+use groan_rs::files::FileType;
+use groan_rs::system::System;
+use log::debug;
+
+use crate::argparse::Args;
+use crate::errors::RunError;
+use crate::interrupt::Interrupt;
+use crate::temporary::TemporaryFiles;
+
+/// Check whether the trajectories are of different file formats.
+fn is_mixed(trajectories: &[String]) -> bool {
+    trajectories
+        .iter()
+        .any(|traj| crate::file_type(traj) != crate::file_type(&trajectories[0]))
+}
+
+/// Write all frames of the trajectory `input` into the trajectory `output` of the specified format.
+fn transcode(
+    system: &System,
+    input: &str,
+    output: &str,
+    output_type: FileType,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut copy = system.clone();
+
+    // frames of xtc files contain no velocities and forces; make sure that those from the structure are not written
+    for atom in copy.atoms_iter_mut() {
+        atom.reset_velocity();
+        atom.reset_force();
+    }
+
+    match output_type {
+        FileType::XTC => copy.xtc_writer_init(output)?,
+        FileType::TRR => copy.trr_writer_init(output)?,
+        _ => panic!("\ngcenter: Fatal Error. Only xtc and trr trajectories can be transcoded."),
+    }
+
+    match crate::file_type(input) {
+        FileType::XTC => {
+            for frame in copy.xtc_iter(input)? {
+                frame?.traj_write_frame()?;
+            }
+        }
+        FileType::TRR => {
+            for frame in copy.trr_iter(input)? {
+                frame?.traj_write_frame()?;
+            }
+        }
+        _ => panic!("\ngcenter: Fatal Error. Only xtc and trr trajectories can be transcoded."),
+    }
+
+    copy.traj_close();
+    Ok(())
+}
+
+/// Transcode trajectories with a different file format than the first trajectory, if `allow-mixed` is requested.
+/// The transcoded trajectories are read instead of the original files; the original names are kept for reporting.
+/// Returns the temporary files which are removed once they are dropped or once the program is interrupted.
+pub fn transcode_mixed(
+    system: &System,
+    args: &mut Args,
+    interrupt: &Interrupt,
+) -> Result<TemporaryFiles, RunError> {
+    let mut transcoded = TemporaryFiles::new(interrupt);
+    if !args.allow_mixed || !is_mixed(args.trajectory_paths()) {
+        return Ok(transcoded);
+    }
+
+    let mut paths = args.trajectory_paths().to_vec();
+    let target = crate::file_type(&paths[0]);
+    let extension = match target {
+        FileType::XTC => "xtc",
+        FileType::TRR => "trr",
+        _ => panic!("\ngcenter: Fatal Error. Only xtc and trr trajectories can be transcoded."),
+    };
+
+    for (i, (traj, read_path)) in args.trajectories.iter().zip(paths.iter_mut()).enumerate() {
+        if crate::file_type(read_path.as_str()) == target {
+            continue;
+        }
+
+        let path = transcoded.create(&args.output, "transcoded", i, extension);
+        if transcode(system, read_path, &path, target).is_err() {
+            return Err(RunError::TranscodingFailed(
+                traj.to_owned(),
+                extension.to_owned(),
+            ));
+        }
+
+        debug!(
+            "{} trajectory '{}' transcoded into {} format\n",
+            "note:".purple().bold(),
+            traj.yellow(),
+            extension.yellow()
+        );
+
+        *read_path = path;
+    }
+
+    args.trajectories_tmp = Some(paths);
+    Ok(transcoded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mixed() {
+        let same = [String::from("part1.xtc"), String::from("part2.xtc")];
+        assert!(!is_mixed(&same));
+
+        let mixed = [
+            String::from("part1.xtc"),
+            String::from("part2.trr"),
+            String::from("part3.xtc"),
+        ];
+        assert!(is_mixed(&mixed));
+    }
+}
@@ -0,0 +1,260 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of writing trr files whose frames can be flushed as soon as they are written.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use groan_rs::structures::atom::Atom;
+use groan_rs::structures::vector3d::Vector3D;
+use groan_rs::system::System;
+
+use crate::errors::RunError;
+
+/// Magic number identifying a trr frame.
+const TRR_MAGIC: i32 = 1993;
+
+/// Version string written into the header of each trr frame.
+const TRR_VERSION: &str = "GMX_trn_file";
+
+/// Writer of single-precision trr frames.
+/// Velocities and forces are written if all written atoms have them, unless only positions are requested.
+pub struct TrrWriter {
+    /// Name of the output file.
+    filename: String,
+    /// Buffered handle to the output file.
+    writer: BufWriter<File>,
+    /// Write frames without velocity and force blocks.
+    positions_only: bool,
+}
+
+impl TrrWriter {
+    /// Create a new trr file.
+    pub fn new(filename: &str, positions_only: bool) -> Result<Self, RunError> {
+        let file =
+            File::create(filename).map_err(|_| RunError::TrrNotWritten(filename.to_owned()))?;
+
+        Ok(TrrWriter {
+            filename: filename.to_owned(),
+            writer: BufWriter::new(file),
+            positions_only,
+        })
+    }
+
+    /// Write atoms and the simulation box of the system as a new frame.
+    /// If `group` is provided, only the atoms of this group are written.
+    pub fn write_frame(&mut self, system: &System, group: Option<&str>) -> Result<(), RunError> {
+        let atoms: Vec<&Atom> = match group {
+            Some(name) => system
+                .group_iter(name)
+                .map_err(|_| RunError::TrrNotWritten(self.filename.to_owned()))?
+                .collect(),
+            None => system.atoms_iter().collect(),
+        };
+
+        let (velocities, forces) = if self.positions_only {
+            (None, None)
+        } else {
+            (
+                vector_block(&atoms, Atom::get_velocity),
+                vector_block(&atoms, Atom::get_force),
+            )
+        };
+
+        let mut frame = Vec::with_capacity(100 + atoms.len() * 3 * 3 * 4);
+        let n_atoms = atoms.len() as i32;
+        let block_size = |block: &Option<Vec<[f32; 3]>>| match block {
+            Some(_) => n_atoms * 3 * 4,
+            None => 0,
+        };
+
+        // header
+        write_int(&mut frame, TRR_MAGIC);
+        write_int(&mut frame, TRR_VERSION.len() as i32 + 1);
+        write_int(&mut frame, TRR_VERSION.len() as i32);
+        frame.extend_from_slice(TRR_VERSION.as_bytes());
+
+        // ir, e, box, vir, pres, top, sym, x, v, and f sizes
+        for size in [
+            0,
+            0,
+            9 * 4,
+            0,
+            0,
+            0,
+            0,
+            n_atoms * 3 * 4,
+            block_size(&velocities),
+            block_size(&forces),
+        ] {
+            write_int(&mut frame, size);
+        }
+
+        write_int(&mut frame, n_atoms);
+        write_int(&mut frame, system.get_simulation_step() as i32);
+        // number of energies
+        write_int(&mut frame, 0);
+        write_float(&mut frame, system.get_simulation_time());
+        write_float(&mut frame, system.get_lambda());
+
+        // simulation box
+        for value in box_matrix(system) {
+            write_float(&mut frame, value);
+        }
+
+        // positions, velocities, and forces
+        let positions: Vec<[f32; 3]> = atoms.iter().map(|atom| atom_position(atom)).collect();
+        for block in [Some(positions), velocities, forces].iter().flatten() {
+            for value in block.iter().flatten() {
+                write_float(&mut frame, *value);
+            }
+        }
+
+        self.writer
+            .write_all(&frame)
+            .map_err(|_| RunError::TrrNotWritten(self.filename.to_owned()))
+    }
+
+    /// Flush the frames written so far into the file.
+    pub fn flush(&mut self) -> Result<(), RunError> {
+        self.writer
+            .flush()
+            .map_err(|_| RunError::TrrNotWritten(self.filename.to_owned()))
+    }
+
+    /// Flush all written frames into the file.
+    pub fn close(mut self) -> Result<(), RunError> {
+        self.writer
+            .flush()
+            .map_err(|_| RunError::TrrNotWritten(self.filename.to_owned()))
+    }
+}
+
+/// Get the position of an atom. Undefined positions are written as zeros.
+fn atom_position(atom: &Atom) -> [f32; 3] {
+    match atom.get_position() {
+        Some(position) => [position.x, position.y, position.z],
+        None => [0.0, 0.0, 0.0],
+    }
+}
+
+/// Collect the velocities (or forces) of the atoms into a block of a trr frame.
+/// Returns `None` if any of the atoms does not have the vector or if there are no atoms.
+fn vector_block<'a>(
+    atoms: &[&'a Atom],
+    vector: impl Fn(&'a Atom) -> Option<&'a Vector3D>,
+) -> Option<Vec<[f32; 3]>> {
+    if atoms.is_empty() {
+        return None;
+    }
+
+    atoms
+        .iter()
+        .map(|atom| vector(atom).map(|v| [v.x, v.y, v.z]))
+        .collect()
+}
+
+/// Get the simulation box of the system as a flattened matrix of box vectors.
+/// Undefined box is written as zeros.
+fn box_matrix(system: &System) -> [f32; 9] {
+    match system.get_box() {
+        Some(simbox) => [
+            simbox.v1x, simbox.v1y, simbox.v1z, simbox.v2x, simbox.v2y, simbox.v2z, simbox.v3x,
+            simbox.v3y, simbox.v3z,
+        ],
+        None => [0.0; 9],
+    }
+}
+
+/// Append a 32-bit integer encoded in the XDR (big-endian) format.
+fn write_int(buffer: &mut Vec<u8>, value: i32) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Append a 32-bit float encoded in the XDR format.
+fn write_float(buffer: &mut Vec<u8>, value: f32) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_frames_readable() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        let output = tempfile::Builder::new().suffix(".trr").tempfile().unwrap();
+        let filename = output.path().to_str().unwrap();
+
+        let mut writer = TrrWriter::new(filename, true).unwrap();
+        writer.write_frame(&system, None).unwrap();
+        writer.write_frame(&system, None).unwrap();
+        writer.close().unwrap();
+
+        assert_eq!(
+            crate::header::trajectory_velocities_forces(filename),
+            Some((false, false))
+        );
+
+        let expected = system.get_atoms_copy();
+        let mut n_frames = 0;
+        for frame in system.trr_iter(filename).unwrap() {
+            let frame = frame.unwrap();
+            for (atom, original) in frame.atoms_iter().zip(expected.iter()) {
+                assert_eq!(atom.get_position(), original.get_position());
+            }
+
+            n_frames += 1;
+        }
+
+        assert_eq!(n_frames, 2);
+    }
+
+    #[test]
+    fn write_frames_with_velocities() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        system
+            .atoms_iter_mut()
+            .for_each(|atom| atom.set_velocity(Vector3D::new(0.1, -0.2, 0.3)));
+        let output = tempfile::Builder::new().suffix(".trr").tempfile().unwrap();
+        let filename = output.path().to_str().unwrap();
+
+        let mut writer = TrrWriter::new(filename, false).unwrap();
+        writer.write_frame(&system, None).unwrap();
+        writer.close().unwrap();
+
+        assert_eq!(
+            crate::header::trajectory_velocities_forces(filename),
+            Some((true, false))
+        );
+
+        let expected = system.get_atoms_copy();
+        for frame in system.trr_iter(filename).unwrap() {
+            let frame = frame.unwrap();
+            for (atom, original) in frame.atoms_iter().zip(expected.iter()) {
+                assert_eq!(atom.get_position(), original.get_position());
+                assert_eq!(atom.get_velocity(), original.get_velocity());
+            }
+        }
+    }
+
+    #[test]
+    fn write_frames_positions_only() {
+        let mut system = System::from_file("tests/test_files/input.gro").unwrap();
+        system
+            .atoms_iter_mut()
+            .for_each(|atom| atom.set_velocity(Vector3D::new(0.1, -0.2, 0.3)));
+        let output = tempfile::Builder::new().suffix(".trr").tempfile().unwrap();
+        let filename = output.path().to_str().unwrap();
+
+        let mut writer = TrrWriter::new(filename, true).unwrap();
+        writer.write_frame(&system, None).unwrap();
+        writer.close().unwrap();
+
+        assert_eq!(
+            crate::header::trajectory_velocities_forces(filename),
+            Some((false, false))
+        );
+    }
+}
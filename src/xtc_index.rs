@@ -0,0 +1,322 @@
+// Released under MIT License.
+// Copyright (c) 2023-2024 Ladislav Bartos
+
+//! Implementation of index files listing byte offsets of the frames of xtc trajectories.
+
+use std::ffi::{c_int, c_long, c_void};
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+
+use colored::Colorize;
+use groan_rs::io::traj_read::TrajRead;
+use groan_rs::io::xtc_io::XtcReader;
+use log::{debug, warn};
+
+/// Magic number identifying an xtc frame.
+const XTC_MAGIC: i32 = 1995;
+
+/// First line of the index file.
+const INDEX_HEADER: &str = "gcenter-xtc-index";
+
+/// Frames of systems with at most this number of atoms are stored uncompressed.
+const MAX_UNCOMPRESSED_ATOMS: i32 = 9;
+
+/// Size of the header of an uncompressed xtc frame (in bytes).
+const UNCOMPRESSED_HEADER: u64 = 56;
+
+/// Size of the header of a compressed xtc frame including the number of bytes of compressed data.
+const COMPRESSED_HEADER: u64 = 92;
+
+/// Times closer than this value (in ps) are considered identical.
+/// Corresponds to the precision used by groan when jumping to the start of the trajectory.
+const TIME_TOLERANCE: f32 = 1e-3;
+
+/// Value of `whence` requesting a position relative to the start of the file.
+const SEEK_SET: c_int = 0;
+
+extern "C" {
+    /// Move the position in an open C stream. Provided by the C standard library.
+    fn fseek(stream: *mut c_void, offset: c_long, whence: c_int) -> c_int;
+}
+
+/// Simulation times and byte offsets of the frames of an xtc trajectory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XtcIndex {
+    /// Size of the indexed trajectory (in bytes).
+    size: u64,
+    /// Simulation time and byte offset of each frame.
+    frames: Vec<(f32, u64)>,
+}
+
+/// Read a big-endian 4-byte integer.
+fn read_i32(reader: &mut impl Read) -> std::io::Result<i32> {
+    let mut buffer = [0u8; 4];
+    reader.read_exact(&mut buffer)?;
+    Ok(i32::from_be_bytes(buffer))
+}
+
+/// Read the header of the xtc frame starting at the current position.
+/// Returns the simulation time of the frame and the size of the frame (in bytes)
+/// or `None` if the end of the file has been reached.
+fn read_frame_header(reader: &mut (impl Read + Seek)) -> std::io::Result<Option<(f32, u64)>> {
+    let magic = match read_i32(reader) {
+        Ok(x) => x,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    if magic != XTC_MAGIC {
+        return Err(ErrorKind::InvalidData.into());
+    }
+
+    let n_atoms = read_i32(reader)?;
+    let _step = read_i32(reader)?;
+    let time = f32::from_bits(read_i32(reader)? as u32);
+
+    if n_atoms <= MAX_UNCOMPRESSED_ATOMS {
+        return Ok(Some((time, UNCOMPRESSED_HEADER + 12 * n_atoms as u64)));
+    }
+
+    // skip the box, the number of atoms, the precision, and the integer bounds
+    reader.seek(SeekFrom::Current(72))?;
+    let bytes = read_i32(reader)? as u64;
+    // compressed data are padded to a multiple of 4 bytes
+    let padded = bytes.div_ceil(4) * 4;
+
+    Ok(Some((time, COMPRESSED_HEADER + padded)))
+}
+
+/// Read the simulation time of the frame starting at `offset` in the xtc trajectory.
+fn frame_time_at(trajectory: &str, offset: u64) -> Option<f32> {
+    let mut file = File::open(trajectory).ok()?;
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    read_frame_header(&mut file).ok()?.map(|(time, _)| time)
+}
+
+impl XtcIndex {
+    /// Scan the headers of all frames of the xtc trajectory.
+    fn build(trajectory: &str) -> std::io::Result<Self> {
+        let file = File::open(trajectory)?;
+        let size = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut frames = Vec::new();
+        let mut offset = 0;
+        while offset < size {
+            reader.seek(SeekFrom::Start(offset))?;
+            match read_frame_header(&mut reader)? {
+                Some((time, frame_size)) => {
+                    frames.push((time, offset));
+                    offset += frame_size;
+                }
+                None => break,
+            }
+        }
+
+        Ok(XtcIndex { size, frames })
+    }
+
+    /// Read the index from a file. Returns `None` if the file is not a valid index file.
+    fn read(file: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(file).ok()?;
+        let mut lines = content.lines();
+
+        let size = lines
+            .next()
+            .and_then(|line| line.strip_prefix(INDEX_HEADER))
+            .and_then(|size| size.trim().parse::<u64>().ok())?;
+
+        let frames = lines
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut split = line.split_whitespace();
+                let time = split.next()?.parse::<f32>().ok()?;
+                let offset = split.next()?.parse::<u64>().ok()?;
+                split.next().is_none().then_some((time, offset))
+            })
+            .collect::<Option<Vec<(f32, u64)>>>()?;
+
+        Some(XtcIndex { size, frames })
+    }
+
+    /// Write the index into a file.
+    fn write(&self, file: &str) -> std::io::Result<()> {
+        let mut content = format!("{} {}\n", INDEX_HEADER, self.size);
+        for (time, offset) in self.frames.iter() {
+            content.push_str(&format!("{} {}\n", time, offset));
+        }
+
+        std::fs::write(file, content)
+    }
+
+    /// Check that the index has been created for a trajectory of the same size.
+    fn matches_size(&self, trajectory: &str) -> bool {
+        std::fs::metadata(trajectory).is_ok_and(|metadata| metadata.len() == self.size)
+    }
+
+    /// Get the time and offset of the first frame with time higher than or equal to `start`.
+    /// This is the frame groan jumps to when scanning the trajectory from its start.
+    fn start_frame(&self, start: f32) -> Option<(f32, u64)> {
+        self.frames
+            .iter()
+            .find(|(time, _)| *time >= start - TIME_TOLERANCE)
+            .copied()
+    }
+}
+
+/// Read the index of the xtc trajectory from `file`.
+/// If the file does not exist or does not correspond to the trajectory, a new index is created and written into the file.
+/// The trajectory is read from `path`; `trajectory` is its name reported to the user.
+/// Returns `None` if no index can be used.
+pub fn load_index(file: &str, path: &str, trajectory: &str) -> Option<XtcIndex> {
+    if std::path::Path::new(file).exists() {
+        match XtcIndex::read(file) {
+            Some(index) if index.matches_size(path) => {
+                debug!(
+                    "{} index of frames of '{}' read from '{}'\n",
+                    "note:".purple().bold(),
+                    trajectory.yellow(),
+                    file.yellow()
+                );
+                return Some(index);
+            }
+            _ => warn!(
+                "{} index file '{}' does not correspond to trajectory '{}'; creating a new index.\n",
+                "warning:".yellow().bold(),
+                file.yellow(),
+                trajectory.yellow()
+            ),
+        }
+    }
+
+    let index = match XtcIndex::build(path) {
+        Ok(x) => x,
+        Err(_) => {
+            warn!(
+                "{} could not create an index of frames of '{}'; reading the trajectory from its start.\n",
+                "warning:".yellow().bold(),
+                trajectory.yellow()
+            );
+            return None;
+        }
+    };
+
+    match index.write(file) {
+        Ok(_) => debug!(
+            "{} index of frames of '{}' written into '{}'\n",
+            "note:".purple().bold(),
+            trajectory.yellow(),
+            file.yellow()
+        ),
+        Err(_) => warn!(
+            "{} could not write the index of frames into '{}'.\n",
+            "warning:".yellow().bold(),
+            file.yellow()
+        ),
+    }
+
+    Some(index)
+}
+
+/// Move the freshly opened xtc reader to the first frame with time higher than or equal to `start`.
+/// The frame at the target offset is checked before jumping; if it does not match the index, the reader is not moved.
+pub fn seek_to_start(reader: &mut XtcReader, index: &XtcIndex, trajectory: &str, start: f32) {
+    let (time, offset) = match index.start_frame(start) {
+        Some(x) => x,
+        None => return,
+    };
+
+    if offset == 0 {
+        return;
+    }
+
+    if frame_time_at(trajectory, offset).is_none_or(|x| (x - time).abs() > TIME_TOLERANCE) {
+        warn!(
+            "{} frame at {} ps not found at the position listed in the index; reading the trajectory from its start.\n",
+            "warning:".yellow().bold(),
+            time.to_string().yellow(),
+        );
+        return;
+    }
+
+    // SAFETY: the handle points to an open XDRFILE structure of the xdrfile library whose first member is
+    // the C stream of the file; the stream is not buffered by the xdr layer, so moving it to `offset`
+    // places the reader at the start of an existing frame.
+    let result = unsafe {
+        let stream = *(reader.get_file_handle().handle as *const *mut c_void);
+        fseek(stream, offset as c_long, SEEK_SET)
+    };
+
+    if result != 0 {
+        warn!(
+            "{} could not jump to the frame at {} ps; reading the trajectory from its start.\n",
+            "warning:".yellow().bold(),
+            time.to_string().yellow(),
+        );
+        return;
+    }
+
+    debug!(
+        "{} jumped directly to the frame at {} ps using the index\n",
+        "note:".purple().bold(),
+        time.to_string().yellow()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build_index() {
+        let index = XtcIndex::build("tests/test_files/input.xtc").unwrap();
+
+        assert_eq!(index.frames.len(), 11);
+        assert_eq!(index.frames[0], (0.0, 0));
+        for (i, (time, offset)) in index.frames.iter().enumerate() {
+            assert!((time - 100.0 * i as f32).abs() < 1e-3);
+            assert_eq!(
+                frame_time_at("tests/test_files/input.xtc", *offset),
+                Some(*time)
+            );
+        }
+    }
+
+    #[test]
+    fn write_read_index() {
+        let index = XtcIndex::build("tests/test_files/input.xtc").unwrap();
+        let file = tempfile::Builder::new().suffix(".idx").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        index.write(path).unwrap();
+        assert_eq!(XtcIndex::read(path), Some(index.clone()));
+        assert!(index.matches_size("tests/test_files/input.xtc"));
+        assert!(!index.matches_size("tests/test_files/input_part1.xtc"));
+    }
+
+    #[test]
+    fn read_invalid_index() {
+        let file = tempfile::Builder::new().suffix(".idx").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        std::fs::write(path, "gcenter-xtc-index 100\n0 0\n100\n").unwrap();
+        assert!(XtcIndex::read(path).is_none());
+
+        std::fs::write(path, "0 0\n100 1000\n").unwrap();
+        assert!(XtcIndex::read(path).is_none());
+    }
+
+    #[test]
+    fn start_frame() {
+        let index = XtcIndex {
+            size: 0,
+            frames: vec![(0.0, 0), (100.0, 10), (200.0, 20), (150.0, 30)],
+        };
+
+        assert_eq!(index.start_frame(-50.0), Some((0.0, 0)));
+        assert_eq!(index.start_frame(0.0), Some((0.0, 0)));
+        assert_eq!(index.start_frame(150.0), Some((200.0, 20)));
+        assert_eq!(index.start_frame(200.0005), Some((200.0, 20)));
+        assert_eq!(index.start_frame(1000.0), None);
+    }
+}
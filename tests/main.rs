@@ -2210,6 +2210,7 @@ mod pass_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input_nonorthogonal.gro",
+                "-r@protein",
                 "-ftests/test_files/input.xtc",
                 &output_arg,
             ])
@@ -2232,6 +2233,7 @@ mod pass_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input_invalid_box.gro",
+                "-r@protein",
                 "-ftests/test_files/input.xtc",
                 &output_arg,
             ])
@@ -2254,6 +2256,7 @@ mod pass_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input_no_box.pdb",
+                "-r@protein",
                 "-ftests/test_files/input.xtc",
                 &output_arg,
             ])
@@ -2623,97 +2626,7666 @@ mod pass_tests {
             output.path().to_str().unwrap()
         ))
     }
+
+    /// Get the center of the protein in each frame of the trajectory.
+    fn protein_centers(trajectory: &str) -> Vec<groan_rs::structures::vector3d::Vector3D> {
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        system.group_create("Protein", "@protein").unwrap();
+
+        system
+            .xtc_iter(trajectory)
+            .unwrap()
+            .map(|frame| frame.unwrap().group_get_center("Protein").unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn xyz_xtc_reference_from_frame() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--reference-from-frame=3",
+            ])
+            .assert()
+            .success();
+
+        // the protein drifts in the input trajectory
+        let original = protein_centers("tests/test_files/input.xtc");
+        assert!(original
+            .iter()
+            .any(|center| (center - &original[3]).len() > 0.1));
+
+        // the protein stays at its position from frame 3 in the output trajectory
+        let centered = protein_centers(output.path().to_str().unwrap());
+        assert_eq!(original.len(), centered.len());
+        for center in centered.iter() {
+            assert!((center - &original[3]).len() < 0.01);
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_reference_from_frame_iterations() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--reference-from-frame=3",
+                "--center-iterations=3",
+            ])
+            .assert()
+            .success();
+
+        let original = protein_centers("tests/test_files/input.xtc");
+        let centered = protein_centers(output.path().to_str().unwrap());
+        assert_eq!(original.len(), centered.len());
+        for center in centered.iter() {
+            assert!((center - &original[3]).len() < 0.01);
+        }
+    }
+
+    #[test]
+    fn x_xtc_reference_from_frame() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "-x",
+                "--reference-from-frame=0",
+            ])
+            .assert()
+            .success();
+
+        let original = protein_centers("tests/test_files/input.xtc");
+        let centered = protein_centers(output.path().to_str().unwrap());
+        for (center, orig) in centered.iter().zip(original.iter()) {
+            assert!((center.x - original[0].x).abs() < 0.01);
+            assert!((center.y - orig.y).abs() < 0.01);
+            assert!((center.z - orig.z).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn xy_gro_dim() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--dim=xy"])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xy.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xz_gro_dim_separated() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--dim=x,z"])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn y_xtc_dim() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--dim",
+                "y",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_y.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn backup_dir() {
+        let mut file = File::create("tests/test_files/temporary_backup_dir.gro").unwrap();
+        file.write_all(b"Some content to test.").unwrap();
+
+        let backup_dir = Builder::new().tempdir().unwrap();
+        let backup_dir_path = backup_dir.path().join("backups");
+
+        let output = "tests/test_files/temporary_backup_dir.gro";
+        let output_arg = format!("-o{}", output);
+        let backup_dir_arg = format!("--backup-dir={}", backup_dir_path.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, &backup_dir_arg])
+            .assert()
+            .success();
+
+        // check that the file has been successfully written
+        assert!(file_diff::diff("tests/test_files/output_xyz.gro", output));
+
+        // check that no backup has been created next to the output file
+        let backups: Vec<Result<std::path::PathBuf, glob::GlobError>> =
+            glob::glob("tests/test_files/#temporary_backup_dir.gro*")
+                .unwrap()
+                .collect();
+        assert_eq!(backups.len(), 0);
+
+        // check that the backup has been created in the backup directory
+        let pattern = format!("{}/#temporary_backup_dir.gro*", backup_dir_path.display());
+        let backups: Vec<Result<std::path::PathBuf, glob::GlobError>> =
+            glob::glob(&pattern).unwrap().collect();
+        assert_eq!(backups.len(), 1);
+
+        let mut content = String::new();
+        let mut read = File::open(backups[0].as_ref().unwrap()).unwrap();
+        read.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "Some content to test.");
+
+        fs::remove_file("tests/test_files/temporary_backup_dir.gro").unwrap();
+    }
+
+    #[test]
+    fn xyz_xtc_first_frame_only() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--first-frame-only",
+            ])
+            .assert()
+            .success();
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected_frame = expected
+            .xtc_iter("tests/test_files/output_xyz.xtc")
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .get_atoms_copy();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let frames = system
+            .xtc_iter(output.path())
+            .unwrap()
+            .map(|frame| frame.unwrap().get_atoms_copy())
+            .collect::<Vec<_>>();
+
+        assert_eq!(frames.len(), 1);
+        for (atom, expected_atom) in frames[0].iter().zip(expected_frame.iter()) {
+            assert_eq!(atom.get_position(), expected_atom.get_position());
+        }
+    }
+
+    #[test]
+    fn xyz_gro_traj_first_frame_only() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--first-frame-only",
+            ])
+            .assert()
+            .success();
+
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+        assert_eq!(system.get_n_atoms(), 8327);
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected_frame = expected
+            .gro_iter("tests/test_files/output_traj_xyz.gro")
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .get_atoms_copy();
+
+        for (atom, expected_atom) in system.atoms_iter().zip(expected_frame.iter()) {
+            assert_eq!(atom.get_position(), expected_atom.get_position());
+        }
+    }
+
+    #[test]
+    fn xyz_gro_com_quiet() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                "--com",
+                "--quiet",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(!stdout.contains("note:"));
+        assert!(stdout.contains("Successfully written output file"));
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_com_guessed.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_step_verbose() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "-t3",
+                "--verbose",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert_eq!(stdout.matches("[FRAME").count(), 4);
+        assert!(stdout.contains("step        15000"));
+        assert!(stdout.contains("read 4 frame(s), written 4 frame(s)"));
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_step.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn xyz_trr_frame_count_mismatch() {
+        // the output points to a device that is always full, so writing fails after the first frame
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.trr");
+        std::os::unix::fs::symlink("/dev/full", &output).unwrap();
+        let output_arg = format!("-o{}", output.display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.tpr",
+                "-ftests/test_files/input_aa_peptide.trr",
+                &output_arg,
+                "--positions-only",
+                "--no-atomic",
+                "--overwrite",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr
+            .contains("number of written frames (1) does not match the number of read frames (2)"));
+    }
+
+    #[test]
+    fn xyz_gro_com_from_env() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .env("GCENTER_COM", "1")
+            .args(["-stests/test_files/input_aa_peptide.gro", &output_arg])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains("enabled by the environment variable 'GCENTER_COM'"));
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_com_guessed.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xy_tpr_to_gro_whole_molecules_from_env() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .env("GCENTER_WHOLE", "true")
+            .args(["-stests/test_files/input.tpr", &output_arg, "-xy"])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xy_whole_from_tpr.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_whole_from_env_ignored() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .env("GCENTER_WHOLE", "1")
+            .env("GCENTER_COM", "0")
+            .args(["-stests/test_files/input.gro", &output_arg])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_invert_selection() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rnot W",
+                "--invert-selection",
+                "-ntests/test_files/index.ndx",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_water.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_step_max_frames() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-t3",
+                "--max-frames=2",
+            ])
+            .assert()
+            .success();
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected_frames = expected
+            .xtc_iter("tests/test_files/output_xyz_step.xtc")
+            .unwrap()
+            .take(2)
+            .map(|frame| {
+                let frame = frame.unwrap();
+                (frame.get_simulation_step(), frame.get_atoms_copy())
+            })
+            .collect::<Vec<_>>();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let frames = system
+            .xtc_iter(output.path())
+            .unwrap()
+            .map(|frame| {
+                let frame = frame.unwrap();
+                (frame.get_simulation_step(), frame.get_atoms_copy())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(frames.len(), 2);
+        for ((step, atoms), (expected_step, expected_atoms)) in
+            frames.iter().zip(expected_frames.iter())
+        {
+            assert_eq!(step, expected_step);
+            for (atom, expected_atom) in atoms.iter().zip(expected_atoms.iter()) {
+                assert_eq!(atom.get_position(), expected_atom.get_position());
+            }
+        }
+    }
+
+    #[test]
+    fn xyz_gro_traj_max_frames() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--max-frames=3",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let n_frames = system.gro_iter(output.path()).unwrap().count();
+
+        assert_eq!(n_frames, 3);
+    }
+
+    #[test]
+    fn xyz_gro_no_velocities_flag() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--no-velocities",
+            ])
+            .assert()
+            .success();
+
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        assert!(expected.has_velocities());
+        assert!(!system.has_velocities());
+
+        for (atom, expected_atom) in system.atoms_iter().zip(expected.atoms_iter()) {
+            assert_eq!(atom.get_position(), expected_atom.get_position());
+        }
+    }
+
+    #[test]
+    fn xyz_trr_to_trr_no_velocities() {
+        let output = Builder::new().suffix(".trr").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let output_vel = Builder::new().suffix(".trr").tempfile().unwrap();
+        let output_vel_arg = format!("-o{}", output_vel.path().display());
+
+        for (output, flag) in [
+            (&output_arg, "--no-velocities"),
+            (&output_vel_arg, "--silent"),
+        ] {
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args([
+                    "-stests/test_files/input_aa_peptide.gro",
+                    output,
+                    "-ftests/test_files/input_aa_peptide.trr",
+                    flag,
+                ])
+                .assert()
+                .success();
+        }
+
+        let mut system =
+            groan_rs::system::System::from_file("tests/test_files/input_aa_peptide.gro").unwrap();
+        let expected_frames = system
+            .trr_iter(output_vel.path())
+            .unwrap()
+            .map(|frame| frame.unwrap().get_atoms_copy())
+            .collect::<Vec<_>>();
+
+        let frames = system
+            .trr_iter(output.path())
+            .unwrap()
+            .map(|frame| frame.unwrap().get_atoms_copy())
+            .collect::<Vec<_>>();
+
+        assert!(expected_frames
+            .iter()
+            .any(|atoms| atoms.iter().any(|atom| atom.has_velocity())));
+
+        assert_eq!(frames.len(), expected_frames.len());
+        for (atoms, expected_atoms) in frames.iter().zip(expected_frames.iter()) {
+            for (atom, expected_atom) in atoms.iter().zip(expected_atoms.iter()) {
+                assert_eq!(atom.get_position(), expected_atom.get_position());
+                assert!(!atom.has_velocity());
+            }
+        }
+    }
+
+    #[test]
+    fn xyz_gro_abs_target() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--abs-target=2.5,3.0,4.5",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file(output.path()).unwrap();
+        system.group_create("Protein", "@protein").unwrap();
+        let center = system.group_get_center("Protein").unwrap();
+
+        assert!((center.x - 2.5).abs() < 0.001);
+        assert!((center.y - 3.0).abs() < 0.001);
+        assert!((center.z - 4.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn z_gro_abs_target_box_center() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-z",
+                "--abs-target=-,-,2.0",
+            ])
+            .assert()
+            .success();
+
+        let mut input = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        input.group_create("Protein", "@protein").unwrap();
+        let input_center = input.group_get_center("Protein").unwrap();
+
+        let mut system = groan_rs::system::System::from_file(output.path()).unwrap();
+        system.group_create("Protein", "@protein").unwrap();
+        let center = system.group_get_center("Protein").unwrap();
+
+        assert!((center.x - input_center.x).abs() < 0.001);
+        assert!((center.y - input_center.y).abs() < 0.001);
+        assert!((center.z - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn xyz_xtc_abs_target_unset() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--abs-target=-,-,-",
+            ])
+            .assert()
+            .success();
+
+        let centers = protein_centers(output.path().to_str().unwrap());
+        let expected = protein_centers("tests/test_files/output_xyz.xtc");
+
+        assert_eq!(centers.len(), expected.len());
+        for (center, expected_center) in centers.iter().zip(expected.iter()) {
+            assert!((center.x - expected_center.x).abs() < 0.001);
+            assert!((center.y - expected_center.y).abs() < 0.001);
+            assert!((center.z - expected_center.z).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn z_gro_membrane_center_iterations() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-z",
+                "-r@membrane",
+                "--center-iterations=2",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file(output.path()).unwrap();
+        system.group_create("Membrane", "@membrane").unwrap();
+        let center = system.group_get_center("Membrane").unwrap();
+        let box_center = system.get_box_center().unwrap();
+
+        assert!((center.z - box_center.z).abs() < 0.001);
+    }
+
+    #[test]
+    fn xyz_xtc_center_iterations() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--center-iterations=3",
+            ])
+            .assert()
+            .success();
+
+        let centers = protein_centers(output.path().to_str().unwrap());
+        let expected = protein_centers("tests/test_files/output_xyz.xtc");
+
+        assert_eq!(centers.len(), expected.len());
+        for (center, expected_center) in centers.iter().zip(expected.iter()) {
+            assert!((center.x - expected_center.x).abs() < 0.001);
+            assert!((center.y - expected_center.y).abs() < 0.001);
+            assert!((center.z - expected_center.z).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn xyz_cif_to_gro_com() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.cif",
+                &output_arg,
+                "--com",
+                "--require-masses",
+            ])
+            .assert()
+            .success();
+
+        // masses are assigned based on the element symbols of the cif file
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(!stdout.contains("will guess elements and assign masses"));
+
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz_cif_com.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        assert_eq!(system.get_n_atoms(), expected.get_n_atoms());
+        for (atom, expected_atom) in system.atoms_iter().zip(expected.atoms_iter()) {
+            let pos = atom.get_position().unwrap();
+            let expected_pos = expected_atom.get_position().unwrap();
+
+            assert!((pos.x - expected_pos.x).abs() < 0.002);
+            assert!((pos.y - expected_pos.y).abs() < 0.002);
+            assert!((pos.z - expected_pos.z).abs() < 0.002);
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_report_drift() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let drift = Builder::new().suffix(".xvg").tempfile().unwrap();
+        let drift_arg = format!("--report-drift={}", drift.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                &drift_arg,
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+
+        let content = std::fs::read_to_string(drift.path()).unwrap();
+        assert!(content.contains("@ s2 legend \"z\""));
+
+        let rows = content
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with('@'))
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|x| x.parse::<f32>().unwrap())
+                    .collect::<Vec<f32>>()
+            })
+            .collect::<Vec<_>>();
+
+        let expected = protein_centers("tests/test_files/input.xtc");
+        assert_eq!(rows.len(), expected.len());
+
+        for (row, center) in rows.iter().zip(expected.iter()) {
+            assert_eq!(row.len(), 4);
+            assert!((row[1] - center.x).abs() < 0.001);
+            assert!((row[2] - center.y).abs() < 0.001);
+            assert!((row[3] - center.z).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn xyz_gro_com_custom_elements() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                "--elements=tests/test_files/elements_martini.yaml",
+            ])
+            .assert()
+            .success();
+
+        // all beads have the same mass, so the center of mass matches the center of geometry
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        for (atom, expected_atom) in system.atoms_iter().zip(expected.atoms_iter()) {
+            let pos = atom.get_position().unwrap();
+            let expected_pos = expected_atom.get_position().unwrap();
+
+            assert!((pos.x - expected_pos.x).abs() < 0.002);
+            assert!((pos.y - expected_pos.y).abs() < 0.002);
+            assert!((pos.z - expected_pos.z).abs() < 0.002);
+        }
+    }
+
+    #[test]
+    fn xyz_gro_zref_empty_allowed() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-xyz",
+                "--zref=resname XYZ",
+                "--allow-empty-reference",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn z_gro_zref_empty_allowed_no_centering() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--zref=resname XYZ",
+                "--allow-empty-reference",
+            ])
+            .assert()
+            .success();
+
+        let expected = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        for (atom, expected_atom) in system.atoms_iter().zip(expected.atoms_iter()) {
+            assert_eq!(atom.get_position(), expected_atom.get_position());
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_output_precision() {
+        let output_default = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_fine = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_coarse = Builder::new().suffix(".xtc").tempfile().unwrap();
+
+        for (output, precision) in [
+            (&output_default, None),
+            (&output_fine, Some("--output-precision=100000")),
+            (&output_coarse, Some("--output-precision=10")),
+        ] {
+            let output_arg = format!("-o{}", output.path().display());
+            let mut args = vec![
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+            ];
+            args.extend(precision);
+
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args(args)
+                .assert()
+                .success();
+        }
+
+        // default precision is the precision of the input trajectory
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output_default.path().to_str().unwrap()
+        ));
+
+        let size = |file: &tempfile::NamedTempFile| std::fs::metadata(file.path()).unwrap().len();
+        assert!(size(&output_fine) > size(&output_default));
+        assert!(size(&output_coarse) < size(&output_default));
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        for frame in system.xtc_iter(output_coarse.path()).unwrap() {
+            assert_eq!(frame.unwrap().get_precision(), 10);
+        }
+    }
+
+    #[test]
+    fn xyz_trr_output_precision_ignored() {
+        let output = Builder::new().suffix(".trr").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--output-precision=10",
+            ])
+            .assert()
+            .success()
+            .stderr("warning: output precision is only supported for xtc files; option '--output-precision' will be ignored.\n\n");
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_from_xtc.trr",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn capabilities() {
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("--capabilities")
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains(&format!("\"version\": \"{}\"", env!("CARGO_PKG_VERSION"))));
+        assert!(stdout
+            .contains("\"structure\": [\"gro\", \"pdb\", \"pqr\", \"tpr\", \"cif\", \"g96\"]"));
+        assert!(stdout.contains("\"trajectory\": [\"xtc\", \"trr\", \"gro\"]"));
+        assert!(!stdout.contains(">> gcenter"));
+    }
+
+    #[test]
+    fn xyz_xtc_last() {
+        for extension in ["xtc", "trr"] {
+            let output_last = Builder::new()
+                .suffix(&format!(".{}", extension))
+                .tempfile()
+                .unwrap();
+            let output_begin = Builder::new()
+                .suffix(&format!(".{}", extension))
+                .tempfile()
+                .unwrap();
+            let trajectory = format!("-ftests/test_files/input.{}", extension);
+
+            for (output, option) in [(&output_last, "--last=300"), (&output_begin, "-b700")] {
+                let output_arg = format!("-o{}", output.path().display());
+
+                Command::cargo_bin("gcenter")
+                    .unwrap()
+                    .args([
+                        "-stests/test_files/input.gro",
+                        &output_arg,
+                        &trajectory,
+                        option,
+                    ])
+                    .assert()
+                    .success();
+            }
+
+            assert!(file_diff::diff(
+                output_begin.path().to_str().unwrap(),
+                output_last.path().to_str().unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_last_multiple() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-f",
+                "tests/test_files/input_part1.xtc",
+                "tests/test_files/input_part2.xtc",
+                "tests/test_files/input_part3.xtc",
+                "--last=200",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let times = system
+            .xtc_iter(output.path())
+            .unwrap()
+            .map(|frame| frame.unwrap().get_simulation_time())
+            .collect::<Vec<f32>>();
+
+        assert_eq!(times, vec![800.0, 900.0, 1000.0]);
+    }
+
+    #[test]
+    fn xyz_gro_compare() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--compare=tests/test_files/output_xyz.gro",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains("Output file matches the expected file"));
+    }
+
+    #[test]
+    fn xyz_xtc_compare() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--compare=tests/test_files/output_xyz.xtc",
+            ])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn xyz_gro_reference_serials() {
+        for (serials, query) in [
+            ("--reference-serials=1-61", "-rserial 1 to 61"),
+            (
+                "--reference-serials=3,5,7-12,100",
+                "-rserial 3 5 7 to 12 100",
+            ),
+        ] {
+            let output_serials = Builder::new().suffix(".gro").tempfile().unwrap();
+            let output_query = Builder::new().suffix(".gro").tempfile().unwrap();
+
+            for (output, option) in [(&output_serials, serials), (&output_query, query)] {
+                let output_arg = format!("-o{}", output.path().display());
+
+                Command::cargo_bin("gcenter")
+                    .unwrap()
+                    .args(["-stests/test_files/input.gro", &output_arg, option])
+                    .assert()
+                    .success();
+            }
+
+            assert!(file_diff::diff(
+                output_query.path().to_str().unwrap(),
+                output_serials.path().to_str().unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn xyz_gro_reference_serials_inverted() {
+        let output_serials = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_query = Builder::new().suffix(".gro").tempfile().unwrap();
+
+        for (output, option) in [
+            (&output_serials, "--reference-serials=1-61"),
+            (&output_query, "-rserial 1 to 61"),
+        ] {
+            let output_arg = format!("-o{}", output.path().display());
+
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args([
+                    "-stests/test_files/input.gro",
+                    &output_arg,
+                    option,
+                    "--invert-selection",
+                ])
+                .assert()
+                .success();
+        }
+
+        assert!(file_diff::diff(
+            output_query.path().to_str().unwrap(),
+            output_serials.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_renumber() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_numbering_gaps.gro",
+                &output_arg,
+                "-rresname LYS GLY ALA",
+                "--renumber",
+            ])
+            .assert()
+            .success();
+
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+        let numbers = system
+            .atoms_iter()
+            .map(|atom| (atom.get_atom_number(), atom.get_residue_number()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            numbers,
+            vec![(1, 1), (2, 1), (3, 2), (4, 3), (5, 3), (6, 4), (7, 5)]
+        );
+    }
+
+    #[test]
+    fn xyz_gro_renumber_pdb() {
+        let output = Builder::new().suffix(".pdb").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_numbering_gaps.gro",
+                &output_arg,
+                "-rresname LYS GLY ALA",
+                "--renumber",
+            ])
+            .assert()
+            .success();
+
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+        let residues = system
+            .atoms_iter()
+            .map(|atom| atom.get_residue_number())
+            .collect::<Vec<_>>();
+
+        assert_eq!(residues, vec![1, 1, 2, 3, 3, 4, 5]);
+    }
+
+    #[test]
+    fn xyz_xtc_renumber_ignored() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--renumber",
+            ])
+            .assert()
+            .success()
+            .stderr("warning: output file does not contain atom numbering; option '--renumber' will be ignored.\n\n");
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_selection_file() {
+        let output_alias = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_query = Builder::new().suffix(".gro").tempfile().unwrap();
+
+        for (output, options) in [
+            (
+                &output_alias,
+                vec![
+                    "-r@@membrane",
+                    "--selection-file=tests/test_files/selections.sel",
+                ],
+            ),
+            (&output_query, vec!["-rresname POPC"]),
+        ] {
+            let output_arg = format!("-o{}", output.path().display());
+            let mut args = vec!["-stests/test_files/input.gro", &output_arg];
+            args.extend(options);
+
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args(args)
+                .assert()
+                .success();
+        }
+
+        assert!(file_diff::diff(
+            output_query.path().to_str().unwrap(),
+            output_alias.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_strip() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--strip=resname W ION",
+            ])
+            .assert()
+            .success();
+
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        let result = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        let describe = |atom: &groan_rs::structures::atom::Atom| {
+            (
+                atom.get_residue_name().to_owned(),
+                atom.get_atom_name().to_owned(),
+                atom.get_position().unwrap().clone(),
+            )
+        };
+
+        let expected_atoms = expected
+            .atoms_iter()
+            .filter(|atom| atom.get_residue_name() != "W" && atom.get_residue_name() != "ION")
+            .map(describe)
+            .collect::<Vec<_>>();
+
+        assert_eq!(result.get_n_atoms(), 3114);
+        assert_eq!(
+            result.atoms_iter().map(describe).collect::<Vec<_>>(),
+            expected_atoms
+        );
+    }
+
+    #[test]
+    fn xyz_gro_strip_renumber() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--strip=resname POPC",
+                "--renumber",
+            ])
+            .assert()
+            .success();
+
+        let result = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        assert_eq!(result.get_n_atoms(), 5255);
+        for (i, atom) in result.atoms_iter().enumerate() {
+            assert_eq!(atom.get_atom_number(), (i + 1) % 100000);
+        }
+
+        // protein residues are followed by water molecules and ions
+        let residues = result
+            .atoms_iter()
+            .map(|atom| atom.get_residue_number())
+            .collect::<Vec<usize>>();
+        assert_eq!(residues[0], 1);
+        assert!(residues
+            .windows(2)
+            .all(|w| w[1] == w[0] || w[1] == w[0] + 1));
+    }
+
+    #[test]
+    fn xyz_xtc_strip() {
+        let output_structure = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+
+        for (output, trajectory) in [
+            (&output_structure, None),
+            (&output, Some("-ftests/test_files/input.xtc")),
+        ] {
+            let output_arg = format!("-o{}", output.path().display());
+            let mut args = vec![
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--strip=resname W ION",
+            ];
+            args.extend(trajectory);
+
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args(args)
+                .assert()
+                .success();
+        }
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        expected.group_create("Kept", "not resname W ION").unwrap();
+        let expected_frames = expected
+            .xtc_iter("tests/test_files/output_xyz.xtc")
+            .unwrap()
+            .map(|frame| {
+                let frame = frame.unwrap();
+                frame
+                    .group_iter("Kept")
+                    .unwrap()
+                    .map(|atom| atom.get_position().unwrap().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = groan_rs::system::System::from_file(output_structure.path()).unwrap();
+        let result_frames = result
+            .xtc_iter(output.path())
+            .unwrap()
+            .map(|frame| {
+                frame
+                    .unwrap()
+                    .atoms_iter()
+                    .map(|atom| atom.get_position().unwrap().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(result_frames.len(), 11);
+        assert_eq!(result_frames, expected_frames);
+    }
+
+    #[test]
+    fn z_gro_plane_fit() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rresname POPC",
+                "-z",
+                "--plane-fit",
+            ])
+            .assert()
+            .success();
+
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+        let box_center = system.get_box_center().unwrap();
+
+        // mean positions of phosphate beads in the upper and lower leaflet
+        let (mut upper, mut lower) = (Vec::new(), Vec::new());
+        for atom in system
+            .atoms_iter()
+            .filter(|atom| atom.get_atom_name() == "PO4")
+        {
+            let z = atom.get_position().unwrap().z;
+            if z > box_center.z {
+                upper.push(z);
+            } else {
+                lower.push(z);
+            }
+        }
+
+        let mean = |values: &[f32]| values.iter().sum::<f32>() / values.len() as f32;
+        let midplane = (mean(&upper) + mean(&lower)) / 2.0;
+        assert!((midplane - box_center.z).abs() < 0.1);
+    }
+
+    #[test]
+    fn xyz_gro_traj_frame_times() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--frame-times=tests/test_files/frame_times.txt",
+                "-b20",
+                "-e60",
+                "-t2",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let times = system
+            .xtc_iter(output.path())
+            .unwrap()
+            .map(|frame| frame.unwrap().get_simulation_time())
+            .collect::<Vec<f32>>();
+
+        assert_eq!(times, vec![20.0, 40.0, 60.0]);
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected_frames = expected
+            .xtc_iter("tests/test_files/output_xyz_from_gro.xtc")
+            .unwrap()
+            .map(|frame| frame.unwrap().get_atoms_copy())
+            .collect::<Vec<_>>();
+
+        for (frame, expected_frame) in system
+            .xtc_iter(output.path())
+            .unwrap()
+            .zip([2, 4, 6].map(|i| &expected_frames[i]))
+        {
+            for (atom, expected_atom) in frame.unwrap().atoms_iter().zip(expected_frame.iter()) {
+                assert_eq!(atom.get_position(), expected_atom.get_position());
+            }
+        }
+    }
+
+    #[test]
+    fn xyz_trr_to_xtc_lost_data() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                "-r@protein",
+                "-ftests/test_files/input_aa_peptide.trr",
+                &output_arg,
+            ])
+            .assert()
+            .success()
+            .stderr("warning: input trajectory contains velocities which cannot be written into the xtc output file; velocities will be dropped.\n\nwarning: input trajectory contains forces which cannot be written into the xtc output file; forces will be dropped.\n\n");
+    }
+
+    #[test]
+    fn xyz_trr_to_gro_no_velocities_lost_data() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                "-r@protein",
+                "-ftests/test_files/input_aa_peptide.trr",
+                &output_arg,
+                "--no-velocities",
+            ])
+            .assert()
+            .success()
+            .stderr("warning: input trajectory contains forces which cannot be written into the gro output file; forces will be dropped.\n\n");
+    }
+
+    #[test]
+    fn xyz_gro_to_pqr_lost_data() {
+        let output = Builder::new().suffix(".pqr").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", "-r@protein", &output_arg])
+            .assert()
+            .success()
+            .stderr("warning: input structure contains velocities which cannot be written into the pqr output file; velocities will be dropped.\n\nwarning: input structure does not contain charges; missing charges will be written as zero.\n\n");
+    }
+
+    #[test]
+    fn xyz_pqr_to_pqr_no_lost_data() {
+        let output = Builder::new().suffix(".pqr").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.pqr", "-r@protein", &output_arg])
+            .assert()
+            .success()
+            .stderr("");
+    }
+
+    #[test]
+    fn xyz_gro_invalid_box_override() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_invalid_box.gro",
+                &output_arg,
+                "--box=9.2619,9.2619,11.0453",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_keep_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--box=10,11,12",
+                "--keep-box",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let mut n_frames = 0;
+        for frame in system.xtc_iter(output.path()).unwrap() {
+            let simbox = frame.unwrap().get_box_copy().unwrap();
+            assert_eq!([simbox.x, simbox.y, simbox.z], [10.0, 11.0, 12.0]);
+            n_frames += 1;
+        }
+
+        assert_eq!(n_frames, 11);
+    }
+
+    #[test]
+    fn xyz_gro_reference_group_single() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-group=Protein:2.5",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_reference_group_weighted() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-group=@protein:3",
+                "--reference-group=resname POPC",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file(output.path()).unwrap();
+        system.group_create("Protein", "@protein").unwrap();
+        system.group_create("Membrane", "resname POPC").unwrap();
+
+        let simbox = system.get_box().unwrap();
+        let box_center = system.get_box_center().unwrap();
+        let protein = box_center.vector_to(&system.group_get_center("Protein").unwrap(), simbox);
+        let membrane = box_center.vector_to(&system.group_get_center("Membrane").unwrap(), simbox);
+
+        // the weighted average of the group centers lies in the center of the box
+        assert!(((3.0 * protein.x + membrane.x) / 4.0).abs() < 1e-3);
+        assert!(((3.0 * protein.y + membrane.y) / 4.0).abs() < 1e-3);
+        assert!(((3.0 * protein.z + membrane.z) / 4.0).abs() < 1e-3);
+
+        // the protein is not in the center of the box
+        assert!(protein.len() > 0.1);
+    }
+
+    #[test]
+    fn xyz_xtc_progress_json() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--progress-json",
+                "-b200",
+                "-e600",
+                "-t2",
+            ])
+            .assert()
+            .success()
+            .stderr("{\"frame\": 0, \"time\": 200.000, \"percent\": 0.0}\n{\"frame\": 1, \"time\": 400.000, \"percent\": 50.0}\n{\"frame\": 2, \"time\": 600.000, \"percent\": 100.0}\n");
+    }
+
+    #[test]
+    fn xyz_xtc_progress_json_last_frame() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--progress-json",
+                "--max-frames=2",
+                "--silent",
+            ])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("{\"frame\": 0, \"time\": 0.000, \"percent\": 0.0}\n{\"frame\": 1, \"time\": 100.000, \"percent\": 10.0}\n");
+    }
+
+    #[test]
+    fn xyz_gro_traj_progress_json() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--progress-json",
+                "--max-frames=2",
+            ])
+            .assert()
+            .success()
+            .stderr("{\"frame\": 0, \"time\": 0.000}\n{\"frame\": 1, \"time\": 100.000}\n");
+    }
+
+    #[test]
+    fn gro_no_reference() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "-rnone"])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/input.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xtc_no_reference() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "-rnone",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/input.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_rescale_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--rescale-box=10,11,12",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let mut n_frames = 0;
+        for frame in system.xtc_iter(output.path()).unwrap() {
+            let frame = frame.unwrap();
+            let simbox = frame.get_box_copy().unwrap();
+            assert_eq!([simbox.x, simbox.y, simbox.z], [10.0, 11.0, 12.0]);
+            for atom in frame.atoms_iter() {
+                let position = atom.get_position().unwrap();
+                assert!(position.x >= 0.0 && position.x <= 10.0);
+                assert!(position.y >= 0.0 && position.y <= 11.0);
+                assert!(position.z >= 0.0 && position.z <= 12.0);
+            }
+            n_frames += 1;
+        }
+
+        assert_eq!(n_frames, 11);
+    }
+
+    #[test]
+    fn xyz_xtc_strict_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--strict-box",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_index_out() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let ndx = Builder::new().suffix(".ndx").tempfile().unwrap();
+        let ndx_arg = format!("--index-out={}", ndx.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ntests/test_files/index.ndx",
+                &output_arg,
+                &ndx_arg,
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        system.read_ndx("tests/test_files/index.ndx").unwrap();
+        let expected: Vec<usize> = system
+            .group_iter("Protein")
+            .unwrap()
+            .map(|atom| atom.get_index())
+            .collect();
+
+        // the main reference is named after its query
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        system.read_ndx(ndx.path()).unwrap();
+        let written: Vec<usize> = system
+            .group_iter("Protein")
+            .unwrap()
+            .map(|atom| atom.get_index())
+            .collect();
+        assert_eq!(expected, written);
+        assert!(!system.group_exists("CNTR-Main"));
+        assert!(!system.group_exists("CNTR-X"));
+    }
+
+    #[test]
+    fn xyz_xtc_index_out_dimension_references() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let ndx = Builder::new().suffix(".ndx").tempfile().unwrap();
+        let ndx_arg = format!("--index-out={}", ndx.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                "-ntests/test_files/index.ndx",
+                &output_arg,
+                &ndx_arg,
+                "--xref=resid 1",
+                "--zref=Membrane",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        system.read_ndx(ndx.path()).unwrap();
+
+        // only the dimensions with a specific reference are centered
+        assert!(!system.group_exists("CNTR-Main"));
+        assert!(system.group_exists("CNTR-X"));
+        assert!(!system.group_exists("CNTR-Y"));
+        assert!(system.group_exists("CNTR-Z"));
+        system.group_create("Resid1", "resid 1").unwrap();
+        assert_eq!(
+            system.group_get_n_atoms("CNTR-X").unwrap(),
+            system.group_get_n_atoms("Resid1").unwrap()
+        );
+    }
+
+    #[test]
+    fn xyz_xtc_transform_center_whole() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--transform=center,whole",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_whole.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_transform_whole_center_wrap() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--transform=whole,center,wrap",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xy_tpr_to_gro_transform_center_whole() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "--transform=center,whole",
+                "-xy",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xy_whole_from_tpr.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_align_to() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--align-to=tests/test_files/output_xyz.gro",
+            ])
+            .assert()
+            .success();
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        expected.group_create("Reference", "@protein").unwrap();
+        let expected = expected.group_get_center("Reference").unwrap();
+
+        let mut aligned = groan_rs::system::System::from_file(output.path()).unwrap();
+        aligned.group_create("Reference", "@protein").unwrap();
+        let aligned = aligned.group_get_center("Reference").unwrap();
+
+        assert!((expected.x - aligned.x).abs() < 1e-3);
+        assert!((expected.y - aligned.y).abs() < 1e-3);
+        assert!((expected.z - aligned.z).abs() < 1e-3);
+    }
+
+    #[test]
+    fn xtc_print_centroid_no_output() {
+        let drift = Builder::new().suffix(".xvg").tempfile().unwrap();
+        let drift_arg = format!("--report-drift={}", drift.path().display());
+
+        let result = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                "--no-output",
+                "--print-centroid",
+                &drift_arg,
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&result.get_output().stdout).into_owned();
+        let expected: Vec<String> = std::fs::read_to_string(drift.path())
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with('@'))
+            .map(|line| line.to_owned())
+            .collect();
+
+        assert_eq!(expected.len(), 11);
+        assert_eq!(stdout.lines().collect::<Vec<&str>>(), expected);
+    }
+
+    #[test]
+    fn xtc_print_centroid_begin_end() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let result = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--print-centroid",
+                "-b400",
+                "-e800",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&result.get_output().stdout).into_owned();
+        let times: Vec<&str> = stdout
+            .lines()
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(
+            times,
+            ["400.000", "500.000", "600.000", "700.000", "800.000"]
+        );
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_begin_end.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyref_gro_dimension_not_selected() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let result = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "--xref=resid 1",
+                "-y",
+            ])
+            .assert()
+            .success()
+            .stderr("warning: reference '--xref' is provided but the x dimension is not among the selected dimensions (Y); centering will also be performed in the x dimension.\n\n");
+
+        let stdout = String::from_utf8_lossy(&result.get_output().stdout).into_owned();
+        assert!(stdout.contains("[DIMENSIONS]    XY"));
+    }
+
+    #[test]
+    fn xref_gro_dimension_selected() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "--xref=resid 1",
+                "--dim=xy",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+    }
+
+    #[test]
+    fn xyz_trr_positions_only() {
+        let output = Builder::new().suffix(".trr").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let full = Builder::new().suffix(".trr").tempfile().unwrap();
+        let full_arg = format!("-o{}", full.path().display());
+
+        for args in [
+            [
+                "-stests/test_files/input_aa_peptide.tpr",
+                "-r@protein",
+                "-ftests/test_files/input_aa_peptide.trr",
+                &output_arg,
+                "--positions-only",
+            ],
+            [
+                "-stests/test_files/input_aa_peptide.tpr",
+                "-r@protein",
+                "-ftests/test_files/input_aa_peptide.trr",
+                &full_arg,
+                "--silent",
+            ],
+        ] {
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args(args)
+                .assert()
+                .success()
+                .stderr("");
+        }
+
+        // the output file contains no velocity or force blocks
+        let mut header = [0u8; 64];
+        std::io::Read::read_exact(
+            &mut std::fs::File::open(output.path()).unwrap(),
+            &mut header,
+        )
+        .unwrap();
+        assert_eq!(&header[56..64], &[0u8; 8]);
+
+        let size = std::fs::metadata(output.path()).unwrap().len();
+        let full_size = std::fs::metadata(full.path()).unwrap().len();
+        assert!(size < full_size / 2);
+
+        let mut system =
+            groan_rs::system::System::from_file("tests/test_files/input_aa_peptide.tpr").unwrap();
+        let expected: Vec<Vec<Option<groan_rs::structures::vector3d::Vector3D>>> = system
+            .trr_iter(full.path())
+            .unwrap()
+            .map(|frame| {
+                frame
+                    .unwrap()
+                    .atoms_iter()
+                    .map(|atom| atom.get_position().cloned())
+                    .collect()
+            })
+            .collect();
+
+        let mut n_frames = 0;
+        for (frame, positions) in system.trr_iter(output.path()).unwrap().zip(expected.iter()) {
+            let frame = frame.unwrap();
+            for (atom, position) in frame.atoms_iter().zip(positions.iter()) {
+                assert_eq!(atom.get_position(), position.as_ref());
+            }
+            n_frames += 1;
+        }
+
+        assert_eq!(n_frames, expected.len());
+    }
+
+    #[test]
+    fn xyz_xtc_benchmark() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let result = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--benchmark",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&result.get_output().stdout).into_owned();
+        for stage in [
+            "[BENCHMARK]",
+            "loading structure",
+            "guessing elements",
+            "selecting references",
+            "reading frames",
+            "centering frames",
+            "writing frames",
+            "total",
+        ] {
+            assert!(stdout.contains(stage));
+        }
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_benchmark_silent() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--benchmark",
+                "--silent",
+            ])
+            .assert()
+            .success()
+            .stdout("");
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn output_template_box_max() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--output-template-box=max",
+            ])
+            .assert()
+            .success();
+
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        let simbox = system.get_box().unwrap();
+        assert_eq!([simbox.x, simbox.y, simbox.z], [11.0453, 11.0453, 11.0453]);
+
+        let shift = (11.0453 - 9.2619) / 2.0;
+        for (atom, original) in system.atoms_iter().zip(expected.atoms_iter()) {
+            let position = atom.get_position().unwrap();
+            let original = original.get_position().unwrap();
+            assert!((position.x - original.x - shift).abs() < 2e-3);
+            assert!((position.y - original.y - shift).abs() < 2e-3);
+            assert!((position.z - original.z).abs() < 2e-3);
+        }
+    }
+
+    #[test]
+    fn output_template_box_smaller() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "--output-template-box=8",
+            ])
+            .assert()
+            .success()
+            .stderr("warning: output box edge (8 nm) is smaller than the current simulation box (9.2619 9.2619 11.0453 nm); some atoms may end up outside of the output box.\n\n");
+
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+        let simbox = system.get_box().unwrap();
+        assert_eq!([simbox.x, simbox.y, simbox.z], [8.0, 8.0, 8.0]);
+    }
+
+    #[test]
+    fn pdb_ter() {
+        let output = Builder::new().suffix(".pdb").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_chains.pdb",
+                &output_arg,
+                "--pdb-ter",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+
+        assert!(lines.iter().any(|line| line.starts_with("CRYST1")));
+        assert_eq!(lines.iter().filter(|line| **line == "TER").count(), 3);
+
+        // TER records are placed after the last atom of the chains A and B
+        let ters: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| **line == "TER")
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(lines[ters[0] - 1].chars().nth(21), Some('A'));
+        assert_eq!(lines[ters[0] + 1].chars().nth(21), Some('B'));
+        assert_eq!(lines[ters[1] - 1].chars().nth(21), Some('B'));
+        assert_eq!(lines[ters[1] + 1].chars().nth(21), Some(' '));
+        assert_eq!(lines[ters[2] + 1], "ENDMDL");
+
+        // atoms are identical to the output without TER records
+        let reference = Builder::new().suffix(".pdb").tempfile().unwrap();
+        let reference_arg = format!("-o{}", reference.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input_chains.pdb", &reference_arg])
+            .assert()
+            .success();
+
+        let reference = std::fs::read_to_string(reference.path()).unwrap();
+        let without_ters: Vec<&str> = lines.into_iter().filter(|line| *line != "TER").collect();
+        let reference: Vec<&str> = reference.lines().filter(|line| *line != "TER").collect();
+        assert_eq!(without_ters, reference);
+    }
+
+    #[test]
+    fn pdb_ter_gro_output() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_chains.pdb",
+                "-r@protein",
+                &output_arg,
+                "--pdb-ter",
+            ])
+            .assert()
+            .success()
+            .stderr(
+                "warning: output file is not a pdb file; option '--pdb-ter' will be ignored.\n\n",
+            );
+    }
+
+    #[test]
+    fn mirror_structure() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--mirror=z"])
+            .assert()
+            .success();
+
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+        let box_z = expected.get_box().unwrap().z;
+
+        for (atom, original) in system.atoms_iter().zip(expected.atoms_iter()) {
+            let position = atom.get_position().unwrap();
+            let original_position = original.get_position().unwrap();
+            assert!((position.x - original_position.x).abs() < 2e-3);
+            assert!((position.y - original_position.y).abs() < 2e-3);
+            assert!((position.z - (box_z - original_position.z)).abs() < 2e-3);
+
+            if let (Some(velocity), Some(original_velocity)) =
+                (atom.get_velocity(), original.get_velocity())
+            {
+                assert!((velocity.x - original_velocity.x).abs() < 2e-4);
+                assert!((velocity.z + original_velocity.z).abs() < 2e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn mirror_twice_identity() {
+        let mirrored = Builder::new().suffix(".gro").tempfile().unwrap();
+        let mirrored_arg = format!("-o{}", mirrored.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &mirrored_arg, "--mirror=xy"])
+            .assert()
+            .success();
+
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                &format!("-s{}", mirrored.path().display()),
+                &output_arg,
+                "--mirror=xy",
+            ])
+            .assert()
+            .success();
+
+        let expected =
+            groan_rs::system::System::from_file("tests/test_files/output_xyz.gro").unwrap();
+        let system = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        for (atom, original) in system.atoms_iter().zip(expected.atoms_iter()) {
+            let position = atom.get_position().unwrap();
+            let original_position = original.get_position().unwrap();
+            assert!((position.x - original_position.x).abs() < 3e-3);
+            assert!((position.y - original_position.y).abs() < 3e-3);
+            assert!((position.z - original_position.z).abs() < 3e-3);
+        }
+    }
+
+    #[test]
+    fn mirror_trajectory() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--mirror=x",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected: Vec<Vec<[f32; 3]>> = system
+            .xtc_iter("tests/test_files/output_xyz.xtc")
+            .unwrap()
+            .map(|frame| {
+                let frame = frame.unwrap();
+                let box_x = frame.get_box().unwrap().x;
+                frame
+                    .atoms_iter()
+                    .map(|atom| {
+                        let position = atom.get_position().unwrap();
+                        [box_x - position.x, position.y, position.z]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut n_frames = 0;
+        for (frame, expected) in system.xtc_iter(output.path()).unwrap().zip(expected.iter()) {
+            let frame = frame.unwrap();
+            // positions are rounded to the precision of the xtc file independently in the output and the expected file
+            for (atom, expected) in frame.atoms_iter().zip(expected.iter()) {
+                let position = atom.get_position().unwrap();
+                assert!((position.x - expected[0]).abs() < 1.1e-2);
+                assert!((position.y - expected[1]).abs() < 1.1e-2);
+                assert!((position.z - expected[2]).abs() < 1.1e-2);
+            }
+            n_frames += 1;
+        }
+
+        assert_eq!(n_frames, 11);
+    }
+
+    #[test]
+    fn xtc_trr_mixed_inputs_allowed() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.xtc");
+        let output_arg = format!("-o{}", output.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input_part2.trr",
+                "-ftests/test_files/input_part3.xtc",
+                "--allow-mixed",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.to_str().unwrap()
+        ));
+
+        // transcoded trajectories are removed
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn trr_xtc_mixed_inputs_allowed() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.trr");
+        let output_arg = format!("-o{}", output.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_part1.trr",
+                "-ftests/test_files/input_part2.xtc",
+                "-ftests/test_files/input_part3.trr",
+                "--allow-mixed",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected: Vec<(f32, Vec<groan_rs::structures::vector3d::Vector3D>)> = system
+            .trr_iter("tests/test_files/output_xyz_from_trr.trr")
+            .unwrap()
+            .map(|frame| {
+                let frame = frame.unwrap();
+                (
+                    frame.get_simulation_time(),
+                    frame
+                        .atoms_iter()
+                        .map(|atom| atom.get_position().unwrap().clone())
+                        .collect(),
+                )
+            })
+            .collect();
+
+        let mut n_frames = 0;
+        for (frame, (time, positions)) in system.trr_iter(&output).unwrap().zip(expected.iter()) {
+            let frame = frame.unwrap();
+            assert_eq!(frame.get_simulation_time(), *time);
+            for (atom, position) in frame.atoms_iter().zip(positions.iter()) {
+                let written = atom.get_position().unwrap();
+                assert!((written.x - position.x).abs() < 1e-3);
+                assert!((written.y - position.y).abs() < 1e-3);
+                assert!((written.z - position.z).abs() < 1e-3);
+            }
+            n_frames += 1;
+        }
+
+        assert_eq!(n_frames, expected.len());
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn dimension_weighting_com() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                "--xref=Protein:com",
+                "--yref=Protein:com",
+                "--zref=Protein:com",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_com_guessed.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    /// Center the all-atom peptide using the specified arguments and return the centered system.
+    fn center_aa_peptide(arguments: &[&str]) -> groan_rs::system::System {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input_aa_peptide.gro", &output_arg])
+            .args(arguments)
+            .assert()
+            .success();
+
+        groan_rs::system::System::from_file(output.path()).unwrap()
+    }
+
+    /// Check that each coordinate of the atoms of `system` matches the same coordinate
+    /// in `com` if `use_com` is set for this dimension or in `geom` otherwise.
+    fn assert_dimension_weighting(
+        system: &groan_rs::system::System,
+        com: &groan_rs::system::System,
+        geom: &groan_rs::system::System,
+        use_com: [bool; 3],
+    ) {
+        for ((atom, com_atom), geom_atom) in system
+            .atoms_iter()
+            .zip(com.atoms_iter())
+            .zip(geom.atoms_iter())
+        {
+            let position = atom.get_position().unwrap();
+            let com_position = com_atom.get_position().unwrap();
+            let geom_position = geom_atom.get_position().unwrap();
+
+            for (i, (value, (com_value, geom_value))) in [position.x, position.y, position.z]
+                .into_iter()
+                .zip(
+                    [com_position.x, com_position.y, com_position.z]
+                        .into_iter()
+                        .zip([geom_position.x, geom_position.y, geom_position.z]),
+                )
+                .enumerate()
+            {
+                let expected = if use_com[i] { com_value } else { geom_value };
+                assert!((value - expected).abs() < 2e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn dimension_weighting_geom_overrides_com() {
+        let geom = center_aa_peptide(&[]);
+        let system = center_aa_peptide(&[
+            "--com",
+            "--xref=Protein:geom",
+            "--yref=Protein:geom",
+            "--zref=Protein:geom",
+        ]);
+
+        assert_dimension_weighting(&system, &geom, &geom, [false; 3]);
+    }
+
+    #[test]
+    fn dimension_weighting_mixed() {
+        let com = center_aa_peptide(&["--com"]);
+        let geom = center_aa_peptide(&[]);
+
+        // x and y are centered using center of mass, z using center of geometry
+        let system = center_aa_peptide(&["--xref=Protein:com", "--yref=Protein:com", "--dim=xyz"]);
+        assert_dimension_weighting(&system, &com, &geom, [true, true, false]);
+    }
+
+    #[test]
+    fn dimension_weighting_mixed_com_global() {
+        let com = center_aa_peptide(&["--com"]);
+        let geom = center_aa_peptide(&[]);
+
+        // x is centered using center of geometry, y and z using the global center of mass
+        let system = center_aa_peptide(&["--com", "--xref=Protein:geom", "--dim=xyz"]);
+        assert_dimension_weighting(&system, &com, &geom, [false, true, true]);
+    }
+
+    #[test]
+    fn index_out_output_group_name() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let ndx = Builder::new().suffix(".ndx").tempfile().unwrap();
+        let ndx_arg = format!("--index-out={}", ndx.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                &ndx_arg,
+                "--output-group-name=Peptide",
+                "--zref=Membrane",
+                "--dim=xyz",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(ndx.path()).unwrap();
+        let names: Vec<&str> = content
+            .lines()
+            .filter(|line| line.starts_with('['))
+            .collect();
+        assert_eq!(names, ["[ Peptide ]", "[ CNTR-Z ]"]);
+    }
+
+    #[test]
+    fn index_out_default_group_name() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let ndx = Builder::new().suffix(".ndx").tempfile().unwrap();
+        let ndx_arg = format!("--index-out={}", ndx.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                &ndx_arg,
+                "--reference=resid 1 to 5",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(ndx.path()).unwrap();
+        let names: Vec<&str> = content
+            .lines()
+            .filter(|line| line.starts_with('['))
+            .collect();
+        assert_eq!(names, ["[ Centered ]"]);
+    }
+
+    #[test]
+    fn config_file() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let config = Builder::new().suffix(".toml").tempfile().unwrap();
+        fs::write(
+            config.path(),
+            "# centering of the peptide\ncom = true\ndim = \"z\"",
+        )
+        .unwrap();
+        let config_arg = format!("--config={}", config.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                &config_arg,
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_z_com_guessed.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn config_file_default() {
+        let directory = tempfile::tempdir().unwrap();
+        let test_files = fs::canonicalize("tests/test_files").unwrap();
+        fs::write(
+            directory.path().join("gcenter.toml"),
+            format!(
+                "reference = \"W\"\nindex = \"{}\"\n",
+                test_files.join("index.ndx").display()
+            ),
+        )
+        .unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .current_dir(directory.path())
+            .args([
+                &format!("-s{}", test_files.join("input.gro").display()),
+                "-ooutput.gro",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_water.gro",
+            directory.path().join("output.gro").to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn config_file_overridden() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let config = Builder::new().suffix(".toml").tempfile().unwrap();
+        fs::write(
+            config.path(),
+            "reference = \"W\"\ndim = \"z\"\nmedian = true",
+        )
+        .unwrap();
+        let config_arg = format!("--config={}", config.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                &config_arg,
+                "-rProtein",
+                "-z",
+                "--com",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_z_com_guessed.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn every_uniform_spacing() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                "-ftests/test_files/input_aa_peptide.xtc",
+                "--com",
+                "-yz",
+                "-b10",
+                "-e80",
+                "--every=30",
+            ])
+            .assert()
+            .success();
+
+        // frames are spaced by 10 ps, so this is equivalent to `-t3`
+        assert!(file_diff::diff(
+            "tests/test_files/output_yz_com_guessed_begin_end_step.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn every_uneven_spacing() {
+        // the concatenated trajectory contains frames at 0, 100, 200, 300, 400, ... ps
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input_part2.xtc",
+                "-ftests/test_files/input_part3.xtc",
+                "--every=250",
+                "--no-output",
+                "--print-centroid",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        let times: Vec<&str> = stdout
+            .lines()
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(times, ["0.000", "300.000", "600.000", "900.000"]);
+    }
+
+    #[test]
+    fn halt_on_jump_below_threshold() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--halt-on-jump=1.0",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn warn_on_jump() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--halt-on-jump=0.4",
+                "--warn-on-jump",
+            ])
+            .assert()
+            .success();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("warning: reference moved by 0.418 nm between frames 1 and 2 (200.000 ps), which exceeds the threshold of 0.4 nm."));
+        assert!(stderr.contains("warning: reference moved by 0.429 nm between frames 7 and 8 (800.000 ps), which exceeds the threshold of 0.4 nm."));
+        assert_eq!(stderr.matches("reference moved by").count(), 2);
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn atomic_output_renamed() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.xtc");
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &format!("-o{}", output.display()),
+                "-ftests/test_files/input.xtc",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.to_str().unwrap()
+        ));
+
+        // no temporary file is left behind
+        let files: Vec<_> = fs::read_dir(directory.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn no_atomic() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--no-atomic"])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    /// Get the average z-coordinate of the residues of the asymmetric bilayer reference
+    /// and the average z-coordinate of its atoms.
+    fn asymmetric_bilayer_centers(file: &std::path::Path) -> (f32, f32, f32) {
+        let mut system = groan_rs::system::System::from_file(file).unwrap();
+        system
+            .group_create("Reference", ASYMMETRIC_BILAYER)
+            .unwrap();
+
+        let mut residues: Vec<Vec<f32>> = Vec::new();
+        let mut last = None;
+        for atom in system.group_iter("Reference").unwrap() {
+            if last != Some(atom.get_residue_number()) {
+                residues.push(Vec::new());
+                last = Some(atom.get_residue_number());
+            }
+            residues
+                .last_mut()
+                .unwrap()
+                .push(atom.get_position().unwrap().z);
+        }
+
+        let residue_mean = residues
+            .iter()
+            .map(|z| z.iter().sum::<f32>() / z.len() as f32)
+            .sum::<f32>()
+            / residues.len() as f32;
+        let atom_mean = residues.iter().flatten().sum::<f32>()
+            / residues.iter().map(|z| z.len()).sum::<usize>() as f32;
+
+        (residue_mean, atom_mean, system.get_box().unwrap().z / 2.0)
+    }
+
+    /// Upper leaflet lipids and only the headgroups of the lower leaflet lipids.
+    const ASYMMETRIC_BILAYER: &str = "resid 22 to 149 or (resid 150 to 277 and name NC3 PO4)";
+
+    #[test]
+    fn by_residue_com_bilayer() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-z",
+                &format!("-r{}", ASYMMETRIC_BILAYER),
+                "--by-residue-com",
+            ])
+            .assert()
+            .success();
+
+        // all beads have the same mass, so the center of mass of each residue is its center of geometry;
+        // the average is calculated using the Bai & Breen algorithm, so it deviates slightly from the arithmetic mean
+        let (residue_mean, atom_mean, center) = asymmetric_bilayer_centers(output.path());
+        assert!((residue_mean - center).abs() < 0.02);
+        assert!((atom_mean - center).abs() > 0.5);
+
+        // centering using the plain center of mass places the reference differently
+        let output_com = Builder::new().suffix(".gro").tempfile().unwrap();
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &format!("-o{}", output_com.path().display()),
+                "-z",
+                &format!("-r{}", ASYMMETRIC_BILAYER),
+                "--com",
+            ])
+            .assert()
+            .success();
+
+        let (residue_mean_com, _, _) = asymmetric_bilayer_centers(output_com.path());
+        assert!((residue_mean - residue_mean_com).abs() > 0.5);
+    }
+
+    #[test]
+    fn by_residue_com_from_env() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .env("GCENTER_COM", "1")
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-z",
+                &format!("-r{}", ASYMMETRIC_BILAYER),
+                "--by-residue-com",
+            ])
+            .assert()
+            .success();
+
+        // the environment variable does not replace the per-residue weighting with the plain center of mass
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(!stdout.contains("enabled by the environment variable 'GCENTER_COM'"));
+
+        let (residue_mean, _, center) = asymmetric_bilayer_centers(output.path());
+        assert!((residue_mean - center).abs() < 0.02);
+    }
+
+    #[test]
+    fn xyz_gro_traj_to_xtc_no_structure() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([&output_arg, "-ftests/test_files/input_traj.gro"])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_from_gro.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_transform_wrap_clamp_outside() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--transform=whole,center,wrap",
+                "--clamp-outside",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains("all atoms lie inside the simulation box"));
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_whole_clamp_outside() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "-r@protein",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--whole",
+                "--clamp-outside",
+            ])
+            .assert()
+            .success();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert_eq!(stderr, "warning: atoms lie outside the simulation box after centering in 11 frame(s) (at most 293 atom(s) in a single frame); use '--fix-outside' to wrap them into the box.\n\n");
+
+        // atoms are not moved
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_whole.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_whole_fix_outside() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "-r@protein",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--whole",
+                "--clamp-outside",
+                "--fix-outside",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+
+        let wrapped = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let wrapped_arg = format!("-o{}", wrapped.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "-r@protein",
+                &wrapped_arg,
+                "-ftests/test_files/input.xtc",
+                "--transform=center,whole,wrap",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            wrapped.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn tpr_to_gro_whole_clamp_outside() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "-r@protein",
+                &output_arg,
+                "--whole",
+                "--clamp-outside",
+            ])
+            .assert()
+            .success()
+            .stderr("warning: 266 atom(s) lie outside the simulation box after centering; use '--fix-outside' to wrap them into the box.\n\n");
+    }
+
+    #[test]
+    fn xyz_xtc_begin_end_xtc_index() {
+        let directory = tempfile::tempdir().unwrap();
+        let index = directory.path().join("input.idx");
+        let index_arg = format!("--xtc-index={}", index.display());
+
+        for note in ["written into", "read from"] {
+            let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+            let output_arg = format!("-o{}", output.path().display());
+
+            let assert = Command::cargo_bin("gcenter")
+                .unwrap()
+                .args([
+                    "-stests/test_files/input.gro",
+                    &output_arg,
+                    "-ftests/test_files/input.xtc",
+                    "-b400",
+                    "-e800",
+                    &index_arg,
+                ])
+                .assert()
+                .success();
+
+            let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+            assert!(stdout.contains(&format!(
+                "index of frames of 'tests/test_files/input.xtc' {}",
+                note
+            )));
+            assert!(stdout.contains("jumped directly to the frame at 400 ps using the index"));
+
+            assert!(file_diff::diff(
+                "tests/test_files/output_xyz_begin_end.xtc",
+                output.path().to_str().unwrap()
+            ));
+        }
+
+        let content = std::fs::read_to_string(&index).unwrap();
+        assert!(content.starts_with("gcenter-xtc-index 332340\n0 0\n100 30232\n"));
+        assert_eq!(content.lines().count(), 12);
+    }
+
+    #[test]
+    fn xyz_xtc_begin_step_xtc_index_invalid() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let directory = tempfile::tempdir().unwrap();
+        let index = directory.path().join("input.idx");
+        let index_arg = format!("--xtc-index={}", index.display());
+        std::fs::write(&index, "gcenter-xtc-index 100\n0 0\n").unwrap();
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-b400",
+                "-t3",
+                &index_arg,
+            ])
+            .assert()
+            .success();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert_eq!(
+            stderr,
+            format!("warning: index file '{}' does not correspond to trajectory 'tests/test_files/input.xtc'; creating a new index.\n\n", index.display())
+        );
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_begin_step.xtc",
+            output.path().to_str().unwrap()
+        ));
+
+        assert!(std::fs::read_to_string(&index)
+            .unwrap()
+            .starts_with("gcenter-xtc-index 332340\n"));
+    }
+
+    #[test]
+    fn xyz_xtc_cat_xtc_index_ignored() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let directory = tempfile::tempdir().unwrap();
+        let index = directory.path().join("input.idx");
+        let index_arg = format!("--xtc-index={}", index.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input_part2.xtc",
+                "-ftests/test_files/input_part3.xtc",
+                "-b400",
+                "-e800",
+                &index_arg,
+            ])
+            .assert()
+            .success()
+            .stderr("warning: option '--xtc-index' can only be used with a single xtc trajectory; the option will be ignored.\n\n");
+
+        assert!(!index.exists());
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_begin_end.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_even_frames() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--even-frames",
+            ])
+            .assert()
+            .success();
+
+        let expected = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let expected_arg = format!("-o{}", expected.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &expected_arg,
+                "-ftests/test_files/input.xtc",
+                "-t2",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_begin_end_odd_frames() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        // parity is calculated over the frames in the time range
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-b100",
+                "-e800",
+                "--odd-frames",
+            ])
+            .assert()
+            .success();
+
+        let expected = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let expected_arg = format!("-o{}", expected.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &expected_arg,
+                "-ftests/test_files/input.xtc",
+                "-b200",
+                "-e800",
+                "-t2",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_step_odd_frames() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-t3",
+                "--odd-frames",
+                "--verbose",
+            ])
+            .assert()
+            .success();
+
+        // frames 0, 300, 600, and 900 are selected by step; 300 and 900 are written
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains("read 2 frame(s), written 2 frame(s)"));
+        assert!(stdout.contains("time      300.000 ps"));
+        assert!(stdout.contains("time      900.000 ps"));
+        assert!(!stdout.contains("time      600.000 ps"));
+    }
+
+    #[test]
+    fn xyz_xtc_whole_threshold() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--whole",
+                "--whole-threshold=2.5",
+            ])
+            .assert()
+            .success();
+
+        // no bond of the system is longer than the threshold
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_whole.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn title_gro() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--title=centered on Protein, xyz",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        let expected = std::fs::read_to_string("tests/test_files/output_xyz.gro").unwrap();
+
+        assert_eq!(content.lines().next(), Some("centered on Protein, xyz"));
+        assert!(content.lines().skip(1).eq(expected.lines().skip(1)));
+    }
+
+    #[test]
+    fn title_pdb() {
+        let output = Builder::new().suffix(".pdb").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--title=centered on Protein, xyz",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        let expected = std::fs::read_to_string("tests/test_files/output_xyz.pdb").unwrap();
+
+        assert_eq!(
+            content.lines().next(),
+            Some("TITLE     centered on Protein, xyz")
+        );
+        assert!(content.lines().skip(1).eq(expected.lines().skip(1)));
+    }
+
+    #[test]
+    fn title_pqr() {
+        let output = Builder::new().suffix(".pqr").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.pqr",
+                &output_arg,
+                "--title=centered on Protein, xyz",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        let expected = std::fs::read_to_string("tests/test_files/output_xyz.pqr").unwrap();
+
+        assert_eq!(
+            content.lines().next(),
+            Some("TITLE     centered on Protein, xyz")
+        );
+        assert!(content.lines().skip(1).eq(expected.lines().skip(1)));
+    }
+
+    #[test]
+    fn title_verbose() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--verbose"])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        assert_eq!(
+            content.lines().next().unwrap(),
+            format!(
+                "Centered on Protein (XYZ) by gcenter {}; transform: center",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+
+    #[test]
+    fn max_output_frames_xtc() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.xtc");
+        let output_arg = format!("-o{}", output.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--max-output-frames=4",
+            ])
+            .assert()
+            .success();
+
+        assert!(!output.exists());
+        assert!(!directory.path().join("output.0004.xtc").exists());
+
+        // xtc frames are independent, so the chunks joined together form the complete trajectory
+        let mut joined = Vec::new();
+        for number in 1..=3 {
+            let chunk = directory.path().join(format!("output.{:04}.xtc", number));
+            joined.extend(std::fs::read(chunk).unwrap());
+        }
+
+        assert_eq!(
+            joined,
+            std::fs::read("tests/test_files/output_xyz.xtc").unwrap()
+        );
+    }
+
+    #[test]
+    fn max_output_frames_gro() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.gro");
+        let output_arg = format!("-o{}", output.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--max-output-frames=5",
+            ])
+            .assert()
+            .success();
+
+        for (number, n_frames) in [(1, 5), (2, 5), (3, 1)] {
+            let chunk = directory.path().join(format!("output.{:04}.gro", number));
+            let content = std::fs::read_to_string(chunk).unwrap();
+            assert_eq!(content.lines().count(), n_frames * 8330);
+        }
+
+        assert!(!directory.path().join("output.0004.gro").exists());
+    }
+
+    #[test]
+    fn max_output_frames_backup() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.xtc");
+        let output_arg = format!("-o{}", output.display());
+        let first = directory.path().join("output.0001.xtc");
+        std::fs::write(&first, "previous").unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--max-output-frames=11",
+            ])
+            .assert()
+            .success();
+
+        assert_eq!(
+            std::fs::read(&first).unwrap(),
+            std::fs::read("tests/test_files/output_xyz.xtc").unwrap()
+        );
+        let backups = glob::glob(&format!(
+            "{}/#output.0001.xtc-*#",
+            directory.path().display()
+        ))
+        .unwrap()
+        .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(backups[0].as_ref().unwrap()).unwrap(),
+            "previous"
+        );
+    }
+
+    #[test]
+    fn g96_to_gro() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.g96", &output_arg])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            output.path().to_str().unwrap(),
+            "tests/test_files/output_xyz.gro"
+        ));
+    }
+
+    #[test]
+    fn gro_to_g96() {
+        let output = Builder::new().suffix(".g96").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            output.path().to_str().unwrap(),
+            "tests/test_files/output_xyz.g96"
+        ));
+    }
+
+    #[test]
+    fn g96_to_g96_title() {
+        let output = Builder::new().suffix(".g96").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.g96",
+                &output_arg,
+                "--title=centered on Protein, xyz",
+            ])
+            .assert()
+            .success();
+
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        let expected = std::fs::read_to_string("tests/test_files/output_xyz.g96").unwrap();
+
+        assert!(content.starts_with("TITLE\ncentered on Protein, xyz\nEND\n"));
+        assert!(content.lines().skip(2).eq(expected.lines().skip(2)));
+    }
+
+    #[test]
+    fn keep_existing() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.gro");
+        let output_arg = format!("-o{}", output.display());
+        std::fs::write(&output, "previous").unwrap();
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--keep-existing",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains("already exists; nothing to do"));
+        assert!(!stdout.contains("Successfully written"));
+
+        // the output is not modified and no backup is created
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "previous");
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn keep_existing_missing_output() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.gro");
+        let output_arg = format!("-o{}", output.display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--keep-existing",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            output.to_str().unwrap(),
+            "tests/test_files/output_xyz.gro"
+        ));
+    }
+
+    #[test]
+    fn masses_by_name() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let masses = Builder::new().suffix(".txt").tempfile().unwrap();
+        std::fs::write(
+            masses.path(),
+            "; Martini protein beads\nBB 72.0\nSC1 72.0\n",
+        )
+        .unwrap();
+        let masses_arg = format!("--masses={}", masses.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                &masses_arg,
+            ])
+            .assert()
+            .success();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("warning: no mass assigned to 8285 atom(s)"));
+
+        // identical masses of all reference atoms, so the center of mass is the center of geometry
+        assert!(file_diff::diff(
+            output.path().to_str().unwrap(),
+            "tests/test_files/output_xyz.gro"
+        ));
+    }
+
+    #[test]
+    fn masses_by_index() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let masses = Builder::new().suffix(".txt").tempfile().unwrap();
+        std::fs::write(masses.path(), "72.0\n".repeat(8327)).unwrap();
+        let masses_arg = format!("--masses={}", masses.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                &output_arg,
+                "--com",
+                &masses_arg,
+            ])
+            .assert()
+            .success()
+            .stderr("");
+
+        assert!(file_diff::diff(
+            output.path().to_str().unwrap(),
+            "tests/test_files/output_xyz.gro"
+        ));
+    }
+
+    #[test]
+    fn bbox_center_protein() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rProtein",
+                "--bbox-center",
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file(output.path()).unwrap();
+        system.group_create("Protein", "@protein").unwrap();
+        let center = system.get_box_center().unwrap();
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for atom in system.group_iter("Protein").unwrap() {
+            let position = atom.get_position().unwrap();
+            for (i, x) in [position.x, position.y, position.z].into_iter().enumerate() {
+                min[i] = min[i].min(x);
+                max[i] = max[i].max(x);
+            }
+        }
+
+        for (i, c) in [center.x, center.y, center.z].into_iter().enumerate() {
+            assert!(((min[i] + max[i]) / 2.0 - c).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn bbox_center_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("gcenter.toml");
+        fs::write(&config, "bbox-center = true\n").unwrap();
+
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rProtein",
+                &format!("--config={}", config.display()),
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+        assert!(stdout.contains("bounding box center"));
+    }
+
+    #[test]
+    fn xyz_xtc_gro_undefined_quiet_box_warnings() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_no_box.pdb",
+                "-r@protein",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--quiet-box-warnings",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_gro_invalid_quiet_box_warnings() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_invalid_box.gro",
+                "-r@protein",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--quiet-box-warnings",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+    }
+
+    /// Read the simulation times of the frames of an xtc trajectory.
+    fn xtc_times(file: &std::path::Path) -> Vec<f32> {
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        system
+            .xtc_iter(file)
+            .unwrap()
+            .map(|frame| frame.unwrap().get_simulation_time())
+            .collect()
+    }
+
+    #[test]
+    fn xyz_xtc_time_offset() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--time-offset=-200",
+            ])
+            .assert()
+            .success();
+
+        let original = xtc_times(std::path::Path::new("tests/test_files/input.xtc"));
+        let shifted = xtc_times(output.path());
+
+        assert_eq!(original.len(), shifted.len());
+        for (time, shifted_time) in original.iter().zip(shifted.iter()) {
+            assert!((time - 200.0 - shifted_time).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_time_reset() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--begin=300",
+                "--time-reset",
+                "--time-offset=50",
+            ])
+            .assert()
+            .success();
+
+        let original = xtc_times(std::path::Path::new("tests/test_files/input.xtc"));
+        let step = original[1] - original[0];
+        let shifted = xtc_times(output.path());
+
+        assert!(!shifted.is_empty());
+        for (i, time) in shifted.iter().enumerate() {
+            assert!((time - (50.0 + i as f32 * step)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn xyz_gro_traj_time_offset_ignored() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--time-offset=100",
+            ])
+            .assert()
+            .success();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(stderr.contains("shifting output times is only supported for xtc and trr files"));
+    }
+
+    /// Write an xtc trajectory containing every frame of the input trajectory twice in a row.
+    fn duplicated_xtc(output: &std::path::Path) {
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let mut writer = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        writer.xtc_writer_init(output).unwrap();
+
+        for frame in system.xtc_iter("tests/test_files/input.xtc").unwrap() {
+            let frame = frame.unwrap();
+            writer.set_simulation_step(frame.get_simulation_step());
+            writer.set_simulation_time(frame.get_simulation_time());
+            writer.set_box(frame.get_box().unwrap().clone());
+            for (atom, frame_atom) in writer.atoms_iter_mut().zip(frame.atoms_iter()) {
+                atom.set_position(frame_atom.get_position().unwrap().clone());
+            }
+
+            writer.traj_write_frame().unwrap();
+            writer.traj_write_frame().unwrap();
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_dedup() {
+        let input = Builder::new().suffix(".xtc").tempfile().unwrap();
+        duplicated_xtc(input.path());
+
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &format!("-f{}", input.path().display()),
+                &output_arg,
+                "--dedup",
+            ])
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+        assert!(stdout.contains("removed 11 duplicate frame(s)"));
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let n_frames = system.xtc_iter(output.path()).unwrap().count();
+        assert_eq!(n_frames, 11);
+    }
+
+    #[test]
+    fn xyz_xtc_no_dedup() {
+        let input = Builder::new().suffix(".xtc").tempfile().unwrap();
+        duplicated_xtc(input.path());
+
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &format!("-f{}", input.path().display()),
+                &output_arg,
+            ])
+            .assert()
+            .success();
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let n_frames = system.xtc_iter(output.path()).unwrap().count();
+        assert_eq!(n_frames, 22);
+    }
+
+    #[test]
+    fn xyz_xtc_dedup_unique() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--dedup",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_tpr_to_gro_reference_words() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "--reference-words",
+                "molwith",
+                "serial",
+                "3",
+                &output_arg,
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz_from_tpr.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    /// Read the data rows of an xvg file.
+    fn xvg_rows(file: &std::path::Path) -> Vec<Vec<f32>> {
+        std::fs::read_to_string(file)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with('@'))
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|x| x.parse::<f32>().unwrap())
+                    .collect::<Vec<f32>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn xyz_xtc_report_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let report = Builder::new().suffix(".xvg").tempfile().unwrap();
+        let report_arg = format!("--report-box={}", report.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                &report_arg,
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+
+        let mut system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected = system
+            .xtc_iter("tests/test_files/input.xtc")
+            .unwrap()
+            .map(|frame| {
+                let frame = frame.unwrap();
+                let simbox = frame.get_box().unwrap();
+                (frame.get_simulation_time(), simbox.x, simbox.y, simbox.z)
+            })
+            .collect::<Vec<_>>();
+
+        let rows = xvg_rows(report.path());
+        assert_eq!(rows.len(), expected.len());
+
+        for (row, (time, x, y, z)) in rows.iter().zip(expected.iter()) {
+            assert_eq!(row.len(), 5);
+            assert!((row[0] - time).abs() < 0.001);
+            assert!((row[1] - x).abs() < 0.001);
+            assert!((row[2] - y).abs() < 0.001);
+            assert!((row[3] - z).abs() < 0.001);
+            assert!((row[4] - x * y * z).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn xyz_gro_report_box() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let report = Builder::new().suffix(".xvg").tempfile().unwrap();
+        let report_arg = format!("--report-box={}", report.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, &report_arg])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.path().to_str().unwrap()
+        ));
+
+        let system = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let simbox = system.get_box().unwrap();
+
+        let rows = xvg_rows(report.path());
+        assert_eq!(rows.len(), 1);
+        assert!((rows[0][1] - simbox.x).abs() < 0.001);
+        assert!((rows[0][2] - simbox.y).abs() < 0.001);
+        assert!((rows[0][3] - simbox.z).abs() < 0.001);
+    }
+
+    #[test]
+    fn clean_backups() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.gro");
+        let output_arg = format!("-o{}", output.display());
+        std::fs::write(&output, "previous").unwrap();
+
+        let backups = [
+            "#output.gro-2024-01-01-10-00-00#",
+            "#output.gro-2024-01-01-10-00-00-123456#",
+        ];
+        let kept = [
+            "#output.gro.1#",
+            "#output.gro-notes#",
+            "#other.gro-2024-01-01-10-00-00#",
+            "output.gro-2024-01-01-10-00-00",
+        ];
+        for file in backups.iter().chain(kept.iter()) {
+            std::fs::write(directory.path().join(file), "backup").unwrap();
+        }
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--overwrite",
+                "--clean-backups",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.to_str().unwrap()
+        ));
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+        for file in backups {
+            assert!(!directory.path().join(file).exists());
+            assert!(stdout.contains(file));
+        }
+
+        for file in kept {
+            assert!(directory.path().join(file).exists());
+        }
+    }
+
+    /// Center the trajectory twice using the provided method and check that the outputs are byte-identical.
+    fn assert_reproducible(method: &str) {
+        let outputs = (0..2)
+            .map(|_| {
+                let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+                Command::cargo_bin("gcenter")
+                    .unwrap()
+                    .args([
+                        "-stests/test_files/input.gro",
+                        "-ftests/test_files/input.xtc",
+                        &format!("-o{}", output.path().display()),
+                        // even number of atoms
+                        "-rProtein",
+                        method,
+                    ])
+                    .assert()
+                    .success();
+
+                std::fs::read(output.path()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(outputs[0], outputs[1]);
+    }
+
+    #[test]
+    fn median_reproducible() {
+        assert_reproducible("--median");
+    }
+
+    #[test]
+    fn bbox_center_reproducible() {
+        assert_reproducible("--bbox-center");
+    }
+
+    /// Check that the positions of atoms in the output are the positions in the input translated by `shift`.
+    /// Positions are compared using the minimum image convention since the atoms are wrapped into the box.
+    fn assert_translated(output: &std::path::Path, shift: [f32; 3]) {
+        let input = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let simbox = input.get_box().unwrap();
+        let lengths = [simbox.x, simbox.y, simbox.z];
+        let centered = groan_rs::system::System::from_file(output).unwrap();
+
+        for (atom, original) in centered.atoms_iter().zip(input.atoms_iter()) {
+            let position = atom.get_position().unwrap();
+            let original = original.get_position().unwrap();
+            for dim in 0..3 {
+                let diff = position[dim] - original[dim] - shift[dim];
+                let diff = diff - lengths[dim] * (diff / lengths[dim]).round();
+                assert!(diff.abs() < 0.002);
+            }
+        }
+    }
+
+    #[test]
+    fn recenter_box_abs_target() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--recenter-box",
+                "--abs-target=0,0,0",
+            ])
+            .assert()
+            .success();
+
+        let input = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let center = input.get_box_center().unwrap();
+        assert_translated(output.path(), [-center.x, -center.y, -center.z]);
+    }
+
+    #[test]
+    fn recenter_box_no_target() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--recenter-box"])
+            .assert()
+            .success();
+
+        assert_translated(output.path(), [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn recenter_box_com_from_env() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .env("GCENTER_COM", "1")
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--recenter-box",
+            ])
+            .assert()
+            .success();
+
+        // the environment variable does not replace the box center with the center of mass of the system
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(!stdout.contains("enabled by the environment variable 'GCENTER_COM'"));
+        assert_translated(output.path(), [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn xyz_gro_uppercase_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.GRO");
+        std::fs::copy("tests/test_files/input.gro", &input).unwrap();
+        let output = dir.path().join("output.GRO");
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", input.display()))
+            .arg(format!("-o{}", output.display()))
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_uppercase_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let trajectory = dir.path().join("input.XTC");
+        std::fs::copy("tests/test_files/input.xtc", &trajectory).unwrap();
+        let output = dir.path().join("output.XTC");
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg(format!("-f{}", trajectory.display()))
+            .arg(format!("-o{}", output.display()))
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_yz_reference_inverted() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-r@protein",
+                "--yref-invert",
+                "--zref-invert",
+            ])
+            .assert()
+            .success();
+
+        let expected = Builder::new().suffix(".gro").tempfile().unwrap();
+        let expected_arg = format!("-o{}", expected.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &expected_arg,
+                "--xref=@protein",
+                "--yref=not @protein",
+                "--zref=not @protein",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_xref_inverted() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--xref=@membrane",
+                "--xref-invert",
+            ])
+            .assert()
+            .success();
+
+        let expected = Builder::new().suffix(".gro").tempfile().unwrap();
+        let expected_arg = format!("-o{}", expected.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &expected_arg,
+                "--xref=not (@membrane)",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn list_formats() {
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("--list-formats")
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        assert!(stdout.contains("Input structure:      gro, pdb, pqr, tpr, cif, g96\n"));
+        assert!(stdout.contains("Input trajectory:     xtc, trr, gro\n"));
+        assert!(stdout.contains("Output (trajectory):  xtc, trr, gro\n"));
+        assert!(!stdout.contains(">> gcenter"));
+    }
+
+    #[test]
+    fn require_masses_tpr() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let expected = Builder::new().suffix(".gro").tempfile().unwrap();
+        let expected_arg = format!("-o{}", expected.path().display());
+
+        for (out, require) in [(&output_arg, true), (&expected_arg, false)] {
+            let mut command = Command::cargo_bin("gcenter").unwrap();
+            command.args(["-stests/test_files/input.tpr", out, "--com"]);
+            if require {
+                command.arg("--require-masses");
+            }
+            command.assert().success();
+        }
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn require_masses_file() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let masses = Builder::new().suffix(".txt").tempfile().unwrap();
+        std::fs::write(masses.path(), "BB 72.0\nSC1 72.0\n").unwrap();
+        let masses_arg = format!("--masses={}", masses.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                &masses_arg,
+                "--require-masses",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            output.path().to_str().unwrap(),
+            "tests/test_files/output_xyz.gro"
+        ));
+    }
+
+    #[test]
+    fn require_masses_geometry() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--require-masses",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            output.path().to_str().unwrap(),
+            "tests/test_files/output_xyz.gro"
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_stats_jsonl() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let stats = Builder::new().suffix(".jsonl").tempfile().unwrap();
+        let stats_arg = format!("--stats-jsonl={}", stats.path().display());
+        let drift = Builder::new().suffix(".xvg").tempfile().unwrap();
+        let drift_arg = format!("--report-drift={}", drift.path().display());
+        let report = Builder::new().suffix(".xvg").tempfile().unwrap();
+        let report_arg = format!("--report-box={}", report.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                &stats_arg,
+                &drift_arg,
+                &report_arg,
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+
+        let content = std::fs::read_to_string(stats.path()).unwrap();
+        let lines = content.lines().collect::<Vec<&str>>();
+        let drift = xvg_rows(drift.path());
+        let boxes = xvg_rows(report.path());
+        assert_eq!(lines.len(), 11);
+
+        for (i, line) in lines.iter().enumerate() {
+            assert!(line.starts_with(&format!(
+                "{{\"frame\": {}, \"time\": {:.3}, \"step\": {}, ",
+                i,
+                i as f32 * 100.0,
+                i * 5000
+            )));
+            assert!(line.contains(&format!(
+                "\"box\": [{:.4}, {:.4}, {:.4}]",
+                boxes[i][1], boxes[i][2], boxes[i][3]
+            )));
+            assert!(line.ends_with(&format!(
+                "\"centroid\": [{:.4}, {:.4}, {:.4}]}}",
+                drift[i][1], drift[i][2], drift[i][3]
+            )));
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_stats_jsonl_partial() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let stats = Builder::new().suffix(".jsonl").tempfile().unwrap();
+        let stats_arg = format!("--stats-jsonl={}", stats.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                &stats_arg,
+                "--halt-on-jump=0.0001",
+            ])
+            .assert()
+            .failure();
+
+        // statistics of the frame processed before the error are preserved
+        let content = std::fs::read_to_string(stats.path()).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.starts_with("{\"frame\": 0, \"time\": 0.000, \"step\": 0, "));
+    }
+
+    #[test]
+    fn xyz_trr_begin_end_simulation_step() {
+        for (step_args, time_args) in [
+            (
+                vec!["--begin-step=15000", "--end-step=35000"],
+                vec!["-b300", "-e700"],
+            ),
+            (vec!["--begin-step=12345"], vec!["-b300"]),
+            (vec!["--end-step=20000"], vec!["-e400"]),
+        ] {
+            let output = Builder::new().suffix(".trr").tempfile().unwrap();
+            let output_arg = format!("-o{}", output.path().display());
+            let expected = Builder::new().suffix(".trr").tempfile().unwrap();
+            let expected_arg = format!("-o{}", expected.path().display());
+
+            for (out, selection) in [(&output_arg, &step_args), (&expected_arg, &time_args)] {
+                Command::cargo_bin("gcenter")
+                    .unwrap()
+                    .args([
+                        "-stests/test_files/input.gro",
+                        "-ftests/test_files/input.trr",
+                        out,
+                    ])
+                    .args(selection)
+                    .assert()
+                    .success();
+            }
+
+            assert!(file_diff::diff(
+                expected.path().to_str().unwrap(),
+                output.path().to_str().unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_discard() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let expected = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let expected_arg = format!("-o{}", expected.path().display());
+
+        // discarding is applied after the selection using `begin` and `step`
+        for (out, selection) in [
+            (&output_arg, ["-b200", "-t2", "--discard=2"]),
+            (&expected_arg, ["-b600", "-t2", "--discard=0"]),
+        ] {
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args([
+                    "-stests/test_files/input.gro",
+                    "-ftests/test_files/input.xtc",
+                    out,
+                ])
+                .args(selection)
+                .assert()
+                .success();
+        }
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_traj_discard() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_traj.gro",
+                "--discard=3",
+            ])
+            .assert()
+            .success();
+
+        // each frame of the gro trajectory consists of 8330 lines
+        let expected = std::fs::read_to_string("tests/test_files/output_traj_xyz.gro").unwrap();
+        let expected = expected.lines().skip(3 * 8330).collect::<Vec<&str>>();
+        let content = std::fs::read_to_string(output.path()).unwrap();
+        assert_eq!(content.lines().collect::<Vec<&str>>(), expected);
+    }
+
+    /// Compress the file `input` using gzip into the file `output`.
+    fn gzip_file(input: &str, output: &std::path::Path) {
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(output).unwrap(),
+            flate2::Compression::default(),
+        );
+        std::io::copy(&mut std::fs::File::open(input).unwrap(), &mut encoder).unwrap();
+        encoder.finish().unwrap();
+    }
+
+    #[test]
+    fn xyz_xtc_gzip() {
+        for extension in ["xtc", "trr"] {
+            let dir = tempfile::tempdir().unwrap();
+            let trajectory = dir.path().join(format!("input.{}.gz", extension));
+            gzip_file(&format!("tests/test_files/input.{}", extension), &trajectory);
+            let output = dir.path().join("output.xtc");
+
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .arg("-stests/test_files/input.gro")
+                .arg(format!("-f{}", trajectory.display()))
+                .arg(format!("-o{}", output.display()))
+                .assert()
+                .success();
+
+            assert!(file_diff::diff(
+                "tests/test_files/output_xyz.xtc",
+                output.to_str().unwrap()
+            ));
+
+            // temporary decompressed trajectory is removed
+            assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_gzip_multiple_inputs_begin() {
+        let dir = tempfile::tempdir().unwrap();
+        let part2 = dir.path().join("input_part2.xtc.gz");
+        gzip_file("tests/test_files/input_part2.xtc", &part2);
+        let output = dir.path().join("output.xtc");
+        let expected = Builder::new().suffix(".xtc").tempfile().unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg("-ftests/test_files/input_part1.xtc")
+            .arg(format!("-f{}", part2.display()))
+            .arg("-ftests/test_files/input_part3.xtc")
+            .arg(format!("-o{}", output.display()))
+            .arg("-b400")
+            .assert()
+            .success();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                "-b400",
+            ])
+            .arg(format!("-o{}", expected.path().display()))
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.path().to_str().unwrap(),
+            output.to_str().unwrap()
+        ));
+    }
+
+    /// Decompress the gzip-compressed file `input` into a string.
+    fn gunzip_file(input: &std::path::Path) -> String {
+        let mut content = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(input).unwrap())
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn xyz_gro_gzip_output() {
+        for output_name in ["output.gro", "output.gro.gz"] {
+            let dir = tempfile::tempdir().unwrap();
+            let output = dir.path().join(output_name);
+
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .arg("-stests/test_files/input.gro")
+                .arg(format!("-o{}", output.display()))
+                .arg("--gzip-output")
+                .assert()
+                .success();
+
+            let compressed = dir.path().join("output.gro.gz");
+            assert_eq!(
+                gunzip_file(&compressed),
+                fs::read_to_string("tests/test_files/output_xyz.gro").unwrap()
+            );
+
+            // only the compressed file is written
+            assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        }
+    }
+
+    #[test]
+    fn xyz_gro_gzip_output_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("output.pdb");
+        let compressed = dir.path().join("output.pdb.gz");
+        fs::write(&plain, "Uncompressed content.").unwrap();
+        fs::write(&compressed, "Compressed content.").unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg(format!("-o{}", plain.display()))
+            .arg("--gzip-output")
+            .assert()
+            .success();
+
+        assert_eq!(
+            gunzip_file(&compressed),
+            fs::read_to_string("tests/test_files/output_xyz.pdb").unwrap()
+        );
+
+        // the uncompressed file is untouched
+        assert_eq!(fs::read_to_string(&plain).unwrap(), "Uncompressed content.");
+
+        // the previous compressed file is backed up
+        let backups: Vec<_> = glob::glob(&format!("{}/#output.pdb.gz*", dir.path().display()))
+            .unwrap()
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(
+            fs::read_to_string(backups[0].as_ref().unwrap()).unwrap(),
+            "Compressed content."
+        );
+    }
+
+    #[test]
+    fn xyz_xtc_gzip_output_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("output.xtc");
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                "--gzip-output",
+            ])
+            .arg(format!("-o{}", output.display()))
+            .assert()
+            .success();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(stderr.contains("is a binary trajectory"));
+        assert!(stderr.contains("will be ignored"));
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.to_str().unwrap()
+        ));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    /// Write the pdb file `tests/test_files/input.pdb` into `output` replacing the B-factors of atoms.
+    fn write_bfactors(output: &std::path::Path, bfactor: impl Fn(usize) -> f32) {
+        let content = fs::read_to_string("tests/test_files/input.pdb").unwrap();
+        let mut atom = 0;
+        let lines: Vec<String> = content
+            .lines()
+            .map(|line| {
+                if line.starts_with("ATOM") || line.starts_with("HETATM") {
+                    atom += 1;
+                    format!("{}{:6.2}{}", &line[..60], bfactor(atom - 1), &line[66..])
+                } else {
+                    line.to_owned()
+                }
+            })
+            .collect();
+
+        fs::write(output, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn xyz_pdb_weights_from_bfactor_protein() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.pdb");
+        write_bfactors(&input, |i| if i < 42 { 1.0 } else { 0.0 });
+        let output = dir.path().join("output.pdb");
+        let expected = dir.path().join("expected.pdb");
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", input.display()))
+            .arg(format!("-o{}", output.display()))
+            .args(["-rall", "--weights-from-bfactor"])
+            .assert()
+            .success();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", input.display()))
+            .arg(format!("-o{}", expected.display()))
+            .arg("-r@protein")
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.to_str().unwrap(),
+            output.to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_pdb_weights_from_bfactor_masses() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.pdb");
+        let weight = |i: usize| 0.5 + (i % 4) as f32;
+        write_bfactors(&input, weight);
+
+        let masses = dir.path().join("masses.txt");
+        let content: String = (0..8327).map(|i| format!("{}\n", weight(i))).collect();
+        fs::write(&masses, content).unwrap();
+
+        let output = dir.path().join("output.pdb");
+        let expected = dir.path().join("expected.pdb");
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", input.display()))
+            .arg(format!("-o{}", output.display()))
+            .arg("--center-weights-from-bfactor")
+            .assert()
+            .success();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", input.display()))
+            .arg(format!("-o{}", expected.display()))
+            .arg(format!("--masses={}", masses.display()))
+            .arg("--com")
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            expected.to_str().unwrap(),
+            output.to_str().unwrap()
+        ));
+    }
+
+    /// Time of a trr frame and the position, velocity, and force of each atom.
+    type TrrFrame = (
+        f32,
+        Vec<[Option<groan_rs::structures::vector3d::Vector3D>; 3]>,
+    );
+
+    /// Read the times, positions, velocities, and forces of all frames of a trr file.
+    fn read_trr_frames(structure: &str, trajectory: &std::path::Path) -> Vec<TrrFrame> {
+        let mut system = groan_rs::system::System::from_file(structure).unwrap();
+        system
+            .trr_iter(trajectory)
+            .unwrap()
+            .map(|frame| {
+                let frame = frame.unwrap();
+                (
+                    frame.get_simulation_time(),
+                    frame
+                        .atoms_iter()
+                        .map(|atom| {
+                            [
+                                atom.get_position().cloned(),
+                                atom.get_velocity().cloned(),
+                                atom.get_force().cloned(),
+                            ]
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn xyz_trr_stream_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("output.trr");
+        fs::write(&output, "Some content to test.").unwrap();
+        let expected = Builder::new().suffix(".trr").tempfile().unwrap();
+
+        for (target, stream) in [(output.as_path(), true), (expected.path(), false)] {
+            let mut command = Command::cargo_bin("gcenter").unwrap();
+            command
+                .args([
+                    "-stests/test_files/input_aa_peptide.tpr",
+                    "-ftests/test_files/input_aa_peptide.trr",
+                    "--overwrite",
+                ])
+                .arg(format!("-o{}", target.display()));
+            if stream {
+                command.arg("--stream");
+            }
+            command.assert().success();
+        }
+
+        // the streamed frames contain the same data as the frames written by the trajectory writer
+        let streamed = read_trr_frames("tests/test_files/input_aa_peptide.tpr", &output);
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(
+            streamed,
+            read_trr_frames("tests/test_files/input_aa_peptide.tpr", expected.path())
+        );
+        // velocities and forces are written together with the positions
+        let (_, atoms) = &streamed[0];
+        assert!(atoms
+            .iter()
+            .all(|[_, velocity, force]| velocity.is_some() || force.is_some()));
+
+        // the existing regular file is overwritten
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn xyz_trr_stream_regular_file_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("output.trr");
+        fs::write(&output, "Some content to test.").unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.trr",
+                "--stream",
+            ])
+            .arg(format!("-o{}", output.display()))
+            .assert()
+            .success();
+
+        assert_eq!(
+            read_trr_frames("tests/test_files/input.gro", &output).len(),
+            read_trr_frames(
+                "tests/test_files/input.gro",
+                std::path::Path::new("tests/test_files/output_xyz_from_trr.trr")
+            )
+            .len()
+        );
+
+        // the existing regular file is backed up
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn xyz_trr_stream_fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        let pipe = dir.path().join("pipe.trr");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(&pipe)
+            .status()
+            .unwrap()
+            .success());
+
+        let reader = {
+            let pipe = pipe.clone();
+            std::thread::spawn(move || fs::read(pipe).unwrap())
+        };
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.trr",
+                "--silent",
+            ])
+            .arg(format!("-o{}", pipe.display()))
+            .assert()
+            .success();
+
+        // the output streamed into the named pipe is the same as the output streamed into a regular file
+        let expected = Builder::new().suffix(".trr").tempfile().unwrap();
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.trr",
+                "--stream",
+                "--overwrite",
+            ])
+            .arg(format!("-o{}", expected.path().display()))
+            .assert()
+            .success();
+
+        assert_eq!(reader.join().unwrap(), fs::read(expected.path()).unwrap());
+
+        // the named pipe is neither backed up nor replaced
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert!(std::os::unix::fs::FileTypeExt::is_fifo(
+            &fs::metadata(&pipe).unwrap().file_type()
+        ));
+    }
+
+    #[test]
+    fn xyz_gro_reference_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("reference.ndx");
+        let output = dir.path().join("output.gro");
+
+        for expected_note in ["selected and cached", "read from the cache"] {
+            let assert = Command::cargo_bin("gcenter")
+                .unwrap()
+                .arg("-stests/test_files/input.gro")
+                .arg(format!("-o{}", output.display()))
+                .arg(format!("--reference-cache={}", cache.display()))
+                .arg("--overwrite")
+                .assert()
+                .success();
+
+            let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+            let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+            assert!(stderr.contains(expected_note) || stdout.contains(expected_note));
+
+            assert!(file_diff::diff(
+                "tests/test_files/output_xyz.gro",
+                output.to_str().unwrap()
+            ));
+        }
+
+        let content = fs::read_to_string(&cache).unwrap();
+        assert!(content.starts_with("; gcenter reference cache\n; atoms 8327\n"));
+        assert!(content.contains("[ CNTR-Main ]"));
+    }
+
+    #[test]
+    fn xyz_gro_reference_cache_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("reference.ndx");
+        let output = dir.path().join("output.gro");
+
+        // cache written for a different reference
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", "-r@membrane"])
+            .arg(format!("-o{}", output.display()))
+            .arg(format!("--reference-cache={}", cache.display()))
+            .assert()
+            .success();
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg(format!("-o{}", output.display()))
+            .arg(format!("--reference-cache={}", cache.display()))
+            .arg("--overwrite")
+            .assert()
+            .success();
+
+        let output_text = String::from_utf8_lossy(&assert.get_output().stdout).to_string()
+            + &String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(output_text.contains("can not be used"));
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.gro",
+            output.to_str().unwrap()
+        ));
+
+        // cache is rewritten for the current reference and reused
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg(format!("-o{}", output.display()))
+            .arg(format!("--reference-cache={}", cache.display()))
+            .arg("--overwrite")
+            .assert()
+            .success();
+
+        let output_text = String::from_utf8_lossy(&assert.get_output().stdout).to_string()
+            + &String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(output_text.contains("read from the cache"));
+    }
+
+    #[test]
+    fn z_gro_wrap_dims() {
+        let wrapped = Builder::new().suffix(".gro").tempfile().unwrap();
+        let partial = Builder::new().suffix(".gro").tempfile().unwrap();
+
+        for (output, wrap) in [(&wrapped, "--wrap-dims=xyz"), (&partial, "--wrap-dims=z")] {
+            Command::cargo_bin("gcenter")
+                .unwrap()
+                .args(["-stests/test_files/input.gro", "-z", wrap])
+                .arg(format!("-o{}", output.path().display()))
+                .arg("--overwrite")
+                .assert()
+                .success();
+        }
+
+        let input = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let wrapped = groan_rs::system::System::from_file(wrapped.path()).unwrap();
+        let partial = groan_rs::system::System::from_file(partial.path()).unwrap();
+
+        for ((original, wrapped), partial) in input
+            .atoms_iter()
+            .zip(wrapped.atoms_iter())
+            .zip(partial.atoms_iter())
+        {
+            let (original, wrapped, partial) = (
+                original.get_position().unwrap(),
+                wrapped.get_position().unwrap(),
+                partial.get_position().unwrap(),
+            );
+
+            // atoms are not wrapped in x and y
+            assert_eq!(partial.x, original.x);
+            assert_eq!(partial.y, original.y);
+            assert_eq!(partial.z, wrapped.z);
+        }
+    }
+
+    #[test]
+    fn xyz_gro_wrap_dims_none() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", "--wrap-dims=none"])
+            .arg(format!("-o{}", output.path().display()))
+            .arg("--overwrite")
+            .assert()
+            .success();
+
+        let input = groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let output = groan_rs::system::System::from_file(output.path()).unwrap();
+
+        // all atoms are shifted by the same vector
+        let first = output.get_atom(0).unwrap().get_position().unwrap().x
+            - input.get_atom(0).unwrap().get_position().unwrap().x;
+        let mut outside = 0;
+        for (original, shifted) in input.atoms_iter().zip(output.atoms_iter()) {
+            let (original, shifted) = (
+                original.get_position().unwrap(),
+                shifted.get_position().unwrap(),
+            );
+            assert!((shifted.x - original.x - first).abs() < 0.002);
+
+            if shifted.x < 0.0 || shifted.x > output.get_box().unwrap().x {
+                outside += 1;
+            }
+        }
+
+        assert!(outside > 0);
+    }
+
+    #[test]
+    fn xyz_xtc_progress_interval() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--progress-interval=3",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn xyz_xtc_progress_json_interval() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--progress-json",
+                "--progress-interval=5",
+            ])
+            .assert()
+            .success()
+            .stderr("{\"frame\": 0, \"time\": 0.000, \"percent\": 0.0}\n{\"frame\": 5, \"time\": 500.000, \"percent\": 50.0}\n{\"frame\": 10, \"time\": 1000.000, \"percent\": 100.0}\n");
+    }
+
+    /// Write the first `n_atoms` atoms of the input gro file into a temporary gro file.
+    fn write_truncated_gro(n_atoms: usize) -> tempfile::NamedTempFile {
+        let content = fs::read_to_string("tests/test_files/input.gro").unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+
+        let truncated = Builder::new().suffix(".gro").tempfile().unwrap();
+        let atoms = &lines[2..2 + n_atoms];
+        let box_line = lines.last().unwrap();
+        fs::write(
+            truncated.path(),
+            format!("{}\n{}\n{}\n{}\n", lines[0], n_atoms, atoms.join("\n"), box_line),
+        )
+        .unwrap();
+
+        truncated
+    }
+
+    #[test]
+    fn xyz_xtc_ignore_extra_atoms() {
+        let structure = write_truncated_gro(8000);
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", structure.path().display()))
+            .args(["-ftests/test_files/input.xtc", "--ignore-extra-atoms"])
+            .arg(format!("-o{}", output.path().display()))
+            .assert()
+            .success();
+
+        let mut expected =
+            groan_rs::system::System::from_file("tests/test_files/input.gro").unwrap();
+        let expected_frames = expected
+            .xtc_iter("tests/test_files/output_xyz.xtc")
+            .unwrap()
+            .map(|frame| frame.unwrap().get_atoms_copy())
+            .collect::<Vec<_>>();
+
+        // the extra atoms are not written into the output
+        let mut system = groan_rs::system::System::from_file(structure.path()).unwrap();
+        let frames = system
+            .xtc_iter(output.path())
+            .unwrap()
+            .map(|frame| frame.unwrap().get_atoms_copy())
+            .collect::<Vec<_>>();
+
+        assert_eq!(frames.len(), expected_frames.len());
+        for (frame, expected_frame) in frames.iter().zip(expected_frames.iter()) {
+            assert_eq!(frame.len(), 8000);
+            for (atom, expected_atom) in frame.iter().zip(expected_frame.iter()) {
+                assert_eq!(atom.get_position(), expected_atom.get_position());
+            }
+        }
+    }
+
+    #[test]
+    fn xyz_xtc_ignore_extra_atoms_same_count() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--ignore-extra-atoms",
+            ])
+            .assert()
+            .success();
+
+        assert!(file_diff::diff(
+            "tests/test_files/output_xyz.xtc",
+            output.path().to_str().unwrap()
+        ));
+    }
 }
 
-#[cfg(test)]
-mod fail_tests {
-    use assert_cmd::Command;
-    use tempfile::Builder;
+#[cfg(test)]
+mod fail_tests {
+    use assert_cmd::Command;
+    use tempfile::Builder;
+
+    #[test]
+    fn file_protection_gro() {
+        std::fs::copy(
+            "tests/test_files/input.gro",
+            "tests/test_files/tmp_input.gro",
+        )
+        .unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/tmp_input.gro",
+                "-otests/test_files/tmp_input.gro",
+            ])
+            .assert()
+            .failure();
+
+        std::fs::remove_file("tests/test_files/tmp_input.gro").unwrap();
+    }
+
+    #[test]
+    fn file_protection_xtc() {
+        std::fs::copy(
+            "tests/test_files/input.xtc",
+            "tests/test_files/tmp_input.xtc",
+        )
+        .unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/tmp_input.xtc",
+                "-otests/test_files/tmp_input.xtc",
+            ])
+            .assert()
+            .failure();
+
+        std::fs::remove_file("tests/test_files/tmp_input.xtc").unwrap();
+    }
+
+    #[test]
+    fn file_protection_xtc_multiple() {
+        std::fs::copy(
+            "tests/test_files/input.xtc",
+            "tests/test_files/tmp_input2.xtc",
+        )
+        .unwrap();
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                "-ftests/test_files/tmp_input2.xtc",
+                "-otests/test_files/tmp_input2.xtc",
+            ])
+            .assert()
+            .failure();
+
+        std::fs::remove_file("tests/test_files/tmp_input2.xtc").unwrap();
+    }
+
+    #[test]
+    fn nonexistent_group() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-rNonexistent",
+                "-ntests/test_files/index.ndx",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn empty_group() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-r(resname LYS and name PO4)",
+                "-ntests/test_files/index.ndx",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn gro_file_not_found() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_nonexistent.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-ntests/test_files/index.ndx",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn xtc_file_not_found() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_nonexistent.xtc",
+                "-ntests/test_files/index.ndx",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn ndx_file_not_found() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-ntests/test_files/index_nonexistent.ndx",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn inconsistent_gro_xtc() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_tiny.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn nonorthogonal_box() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input_nonorthogonal.gro", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn invalid_box() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input_invalid_box.gro", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn undefined_box() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input_no_box.pdb", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn no_extension() {
+        let output = Builder::new().tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn unsupported_extension_gro() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn unsupported_extension_xtc() {
+        let output = Builder::new().suffix(".pdb").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn begin_requires_traj() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "-b400"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn end_requires_traj() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "-e700"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn step_requires_traj() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "-s3"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn multiple_inputs_identical() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input_part1.xtc",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn unknown_mass() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "--com",
+                "-r@membrane",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn molwith_unsupported() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-rmolecule with serial 17",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn molwith_unsupported_xref() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "--xref=molecule with serial 17",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn molwith_unsupported_yref() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "--yref=molecule with serial 17",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn molwith_unsupported_zref() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "--zref=molecule with serial 17",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn whole_without_tpr() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                "--whole",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn xtc_trr_mixed_inputs() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input_part2.trr",
+                "-ftests/test_files/input_part3.xtc",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn xtc_trp_cg_element() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-relement name carbon",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn gro_traj_multiple_inputs() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/output_z.gro",
+                "-ftests/test_files/output_yz.gro",
+                "-ftests/test_files/output_xz.gro",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn gro_traj_begin() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.gro",
+                "-b100",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn gro_traj_end() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.gro",
+                "-e1000",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn coordinates_atoms_mismatch() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.tpr",
+                "--coordinates=tests/test_files/input.gro",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn coordinates_not_found() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "--coordinates=tests/test_files/input_nonexistent.gro",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn coordinates_unsupported_extension() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                "--coordinates=tests/test_files/input.pdb",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn reference_from_frame_out_of_range() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--reference-from-frame=1000",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn reference_from_frame_requires_traj() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-from-frame=0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn ndx_index_out_of_range() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                "-ntests/test_files/index.ndx",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("in the ndx file 'tests/test_files/index.ndx' references atom"));
+    }
+
+    #[test]
+    fn default_ndx_index_out_of_range() {
+        let directory = tempfile::tempdir().unwrap();
+        let test_files = std::fs::canonicalize("tests/test_files").unwrap();
+        std::fs::copy(
+            test_files.join("index.ndx"),
+            directory.path().join("index.ndx"),
+        )
+        .unwrap();
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .current_dir(directory.path())
+            .args([
+                &format!("-s{}", test_files.join("input_aa_peptide.gro").display()),
+                "-ooutput.gro",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("in the ndx file 'index.ndx' references atom"));
+    }
+
+    #[test]
+    fn dim_conflicts_with_flags() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--dim=xy",
+                "-z",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn dim_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--dim=xw"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn median_with_com() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--median",
+                "--com",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn backup_dir_with_overwrite() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--backup-dir=backups",
+                "--overwrite",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn backup_dir_not_created() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        // backup directory cannot be created inside a regular file
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--backup-dir=tests/test_files/input.gro/backups",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn first_frame_only_requires_traj() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--first-frame-only",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn verbose_with_silent() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--verbose",
+                "--silent",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn invert_selection_empty() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rall",
+                "--invert-selection",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn invert_selection_with_xref() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--invert-selection",
+                "--xref=@membrane",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn max_frames_zero() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--max-frames=0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn max_frames_requires_traj() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--max-frames=3",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn abs_target_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--abs-target=1.0,2.0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn abs_target_with_reference_from_frame() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--abs-target=1.0,2.0,3.0",
+                "--reference-from-frame=1",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn inconsistent_gro_trr_no_output() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.trr");
+        let output_arg = format!("-o{}", output.display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_tiny.gro",
+                &output_arg,
+                "-ftests/test_files/input.trr",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(stderr.contains("8327"));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn inconsistent_gro_xtc_concatenated() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_tiny.gro",
+                &output_arg,
+                "-ftests/test_files/input_part1.xtc",
+                "tests/test_files/input_part2.xtc",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn center_iterations_zero() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--center-iterations=0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn cif_to_cif() {
+        let output = Builder::new().suffix(".cif").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input_aa_peptide.cif", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn report_drift_matches_output() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let drift_arg = format!("--report-drift={}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                &drift_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn report_drift_requires_traj() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--report-drift=drift.xvg",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn elements_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                "--elements=tests/test_files/elements_invalid.yaml",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn elements_not_found() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                "--elements=tests/test_files/nonexistent.yaml",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn zero_output_precision() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--output-precision=0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn output_precision_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--output-precision=100",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn capabilities_with_other_options() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "--capabilities",
+                "-stests/test_files/input.gro",
+                "-ooutput.gro",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn last_gro_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_traj.gro",
+                "--last=300",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn last_with_begin() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--last=300",
+                "-b100",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn compare_differs() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-x",
+                "--compare=tests/test_files/output_xyz.gro",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("differs from the expected file"));
+        assert!(stderr.contains("first difference at byte"));
+
+        // the output file is still written
+        assert!(file_diff::diff(
+            "tests/test_files/output_x.gro",
+            output.path().to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn compare_nonexistent() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--compare=tests/test_files/nonexistent.gro",
+            ])
+            .assert()
+            .failure();
+
+        assert_eq!(std::fs::metadata(output.path()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn reference_serials_out_of_range() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-serials=1-8328",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("the system only contains"));
+    }
+
+    #[test]
+    fn reference_serials_with_reference() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-serials=1-61",
+                "-rProtein",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn reference_serials_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-serials=12-7",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn selection_not_in_file() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-r@@lipids",
+                "--selection-file=tests/test_files/selections.sel",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("selection 'lipids' is not defined"));
+    }
+
+    #[test]
+    fn selection_without_file() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "-r@@membrane"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn selection_file_nonexistent() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-r@@membrane",
+                "--selection-file=tests/test_files/nonexistent.sel",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn strip_reference() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rresname POPC",
+                "--strip=name PO4",
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("reference group 'resname POPC' contains atoms that are removed"));
+    }
+
+    #[test]
+    fn strip_all() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--strip=all"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn plane_fit_with_com() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--plane-fit",
+                "--com",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn frame_times_xtc() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--frame-times=tests/test_files/frame_times.txt",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn frame_times_nonexistent() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--frame-times=tests/test_files/nonexistent.txt",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn frame_times_short() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--frame-times=tests/test_files/frame_times_short.txt",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn frame_times_invalid() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input_traj.gro",
+                &output_arg,
+                "--frame-times=tests/test_files/frame_times_invalid.txt",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn box_not_positive() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--box=10,0,10"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn box_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--box=10,10"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn keep_box_without_box() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--keep-box",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn keep_box_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "--box=10,10,10",
+                &output_arg,
+                "--keep-box",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn reference_group_with_reference() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-group=Protein",
+                "-rProtein",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn reference_group_zero_weight() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-group=Protein:0",
+                "--reference-group=resname POPC",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn reference_group_empty() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--reference-group=Protein",
+                "--reference-group=resname XYZ",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn progress_json_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--progress-json",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn no_reference_with_zref() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rnone",
+                "--zref=Protein",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn no_reference_invalid_box() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_invalid_box.gro",
+                &output_arg,
+                "-rnone",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn rescale_box_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--rescale-box=10,10,10",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn rescale_box_invalid() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--rescale-box=10,-10,10",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn rescale_box_with_keep_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--box=10,10,10",
+                "--keep-box",
+                "--rescale-box=10,10,10",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn mass_query_guesses_masses() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let result = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "-rmass < 2"])
+            .assert()
+            // selection by mass or charge is not supported by the current groan_rs
+            .failure();
+
+        let stdout = String::from_utf8_lossy(&result.get_output().stdout).into_owned();
+        assert!(stdout.contains("mass keyword detected in a query"));
+    }
+
+    #[test]
+    fn charge_query_note() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let result = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rcharge > 0.5",
+            ])
+            .assert()
+            // selection by mass or charge is not supported by the current groan_rs
+            .failure();
+
+        let stdout = String::from_utf8_lossy(&result.get_output().stdout).into_owned();
+        assert!(stdout.contains("charge keyword detected in a query"));
+    }
+
+    #[test]
+    fn xtc_gro_invalid_strict_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_invalid_box.gro",
+                "-r@protein",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--strict-box",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: simulation box is not a valid simulation box; some required dimensions are not positive\n\n");
+    }
+
+    #[test]
+    fn xtc_pdb_undefined_strict_box() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_no_box.pdb",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--strict-box",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn strict_box_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--strict-box"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn transform_unknown_operation() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "--transform=center,fit",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn transform_whole_without_tpr() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--transform=whole,center",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: invalid value 'whole' for '--transform <STEPS>': this operation is only supported when a tpr file is provided\n\nFor more information, try '--help'.\n");
+    }
+
+    #[test]
+    fn transform_with_whole() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "--transform=center,whole",
+                "--whole",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn align_to_reference_not_found() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rresname POPC",
+                "--align-to=tests/test_files/input_aa_peptide.gro",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: reference group 'resname POPC' is empty\n\n");
+    }
+
+    #[test]
+    fn align_to_nonexistent() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--align-to=tests/test_files/nonexistent.gro",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn align_to_with_abs_target() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--align-to=tests/test_files/output_xyz.gro",
+                "--abs-target=1,1,1",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn no_output_without_trajectory() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", "--no-output"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn no_output_with_output() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--no-output",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn print_centroid_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--print-centroid",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn output_template_box_with_trajectory() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--output-template-box=max",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn output_template_box_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--output-template-box=-5",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn pdb_ter_with_trajectory() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--pdb-ter",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn mirror_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--mirror=w"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn gro_xtc_mixed_inputs_allowed() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input_traj.gro",
+                "--allow-mixed",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn allow_mixed_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--allow-mixed"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn dimension_weighting_unknown() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        // unknown suffixes are treated as a part of the query
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input_aa_peptide.gro",
+                &output_arg,
+                "--xref=Protein:median",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn output_group_name_whitespace() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let ndx = Builder::new().suffix(".ndx").tempfile().unwrap();
+        let ndx_arg = format!("--index-out={}", ndx.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                &ndx_arg,
+                "--output-group-name=my peptide",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn config_nonexistent() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--config=tests/test_files/nonexistent.toml",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: invalid value 'tests/test_files/nonexistent.toml' for '--config <CONFIG>': configuration file does not exist\n\nFor more information, try '--help'.\n");
+    }
+
+    #[test]
+    fn config_unknown_option() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let config = Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(config.path(), "referenc = \"Membrane\"").unwrap();
+        let config_arg = format!("--config={}", config.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, &config_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn config_conflicting_options() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let config = Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(config.path(), "com = true\nmedian = true").unwrap();
+        let config_arg = format!("--config={}", config.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, &config_arg])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert_eq!(
+            stderr,
+            format!(
+                "error: options 'com' and 'median' cannot be used together (configuration file '{}')\n\n",
+                config.path().display()
+            )
+        );
+    }
+
+    #[test]
+    fn every_gro_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input_traj.gro",
+                "--every=100",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: invalid value '100' for '--every <DT>': gro trajectories are not guaranteed to contain information about simulation time, therefore `every` cannot be specified\n\nFor more information, try '--help'.\n");
+    }
+
+    #[test]
+    fn every_with_step() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--every=100",
+                "-t2",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn every_not_positive() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--every=0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn halt_on_jump() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "-z",
+                "--halt-on-jump=0.35",
+                "--silent",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: reference moved by 0.405 nm between frames 1 and 2 (200.000 ps), which exceeds the threshold of 0.35 nm set using '--halt-on-jump'\n\n");
+    }
+
+    #[test]
+    fn warn_on_jump_without_threshold() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-ftests/test_files/input.xtc",
+                "--warn-on-jump",
+            ])
+            .assert()
+            .failure();
+    }
 
     #[test]
-    fn file_protection_gro() {
-        std::fs::copy(
-            "tests/test_files/input.gro",
-            "tests/test_files/tmp_input.gro",
-        )
-        .unwrap();
+    fn atomic_output_not_left_on_failure() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.xtc");
 
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
-                "-stests/test_files/tmp_input.gro",
-                "-otests/test_files/tmp_input.gro",
+                "-stests/test_files/input.gro",
+                &format!("-o{}", output.display()),
+                "-ftests/test_files/input.xtc",
+                "-z",
+                "--halt-on-jump=0.35",
             ])
             .assert()
             .failure();
 
-        std::fs::remove_file("tests/test_files/tmp_input.gro").unwrap();
+        // neither the incomplete output nor the temporary file is left behind
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 0);
     }
 
     #[test]
-    fn file_protection_xtc() {
-        std::fs::copy(
-            "tests/test_files/input.xtc",
-            "tests/test_files/tmp_input.xtc",
-        )
-        .unwrap();
+    fn no_atomic_output_left_on_failure() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("output.xtc");
 
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                "-ftests/test_files/tmp_input.xtc",
-                "-otests/test_files/tmp_input.xtc",
+                &format!("-o{}", output.display()),
+                "-ftests/test_files/input.xtc",
+                "-z",
+                "--halt-on-jump=0.35",
+                "--no-atomic",
             ])
             .assert()
             .failure();
 
-        std::fs::remove_file("tests/test_files/tmp_input.xtc").unwrap();
+        assert!(output.exists());
     }
 
     #[test]
-    fn file_protection_xtc_multiple() {
-        std::fs::copy(
-            "tests/test_files/input.xtc",
-            "tests/test_files/tmp_input2.xtc",
-        )
-        .unwrap();
+    fn by_residue_com_with_com() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "--by-residue-com",
+                "--com",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn xtc_no_structure() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([&output_arg, "-ftests/test_files/input.xtc"])
+            .assert()
+            .failure()
+            .stderr("error: the following required argument was not provided: '--structure <STRUCTURE>'; the structure file can only be omitted if the trajectory is a single gro file\n\nFor more information, try '--help'.\n");
+    }
+
+    #[test]
+    fn no_structure_no_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([&output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn fix_outside_without_clamp_outside() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--fix-outside"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn even_frames_with_odd_frames() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
+                &output_arg,
                 "-ftests/test_files/input.xtc",
-                "-ftests/test_files/tmp_input2.xtc",
-                "-otests/test_files/tmp_input2.xtc",
+                "--even-frames",
+                "--odd-frames",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn even_frames_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/input.gro", &output_arg, "--even-frames"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn whole_threshold_without_whole() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "--whole-threshold=1.0",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: invalid argument '--whole-threshold <NM>': molecules are not made whole; use '--whole' or the 'whole' operation of '--transform'\n\nFor more information, try '--help'.\n");
+    }
+
+    #[test]
+    fn whole_threshold_negative() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.tpr",
+                &output_arg,
+                "--whole",
+                "--whole-threshold=-1.0",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn title_with_trajectory() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--title=centered",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn empty_system() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/empty.gro", &output_arg, "-rall"])
+            .assert()
+            .failure()
+            .stderr("error: system loaded from 'tests/test_files/empty.gro' contains no atoms\n\n");
+    }
+
+    #[test]
+    fn max_output_frames_zero() {
+        let directory = tempfile::tempdir().unwrap();
+        let output_arg = format!("-o{}", directory.path().join("output.xtc").display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+                "--max-output-frames=0",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: invalid value '0' for '--max-output-frames <N>': each output file must contain at least one frame\n\nFor more information, try '--help'.\n");
+    }
+
+    #[test]
+    fn max_output_frames_structure() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--max-output-frames=2",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn max_output_frames_no_output() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                "--no-output",
+                "--max-output-frames=2",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn g96_trajectory() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.g96",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.contains("g96 files are only supported as structure files"));
+    }
+
+    #[test]
+    fn g96_output_with_trajectory() {
+        let output = Builder::new().suffix(".g96").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
+                &output_arg,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn g96_nonexistent() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args(["-stests/test_files/nonexistent.g96", &output_arg])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn keep_existing_overwrite() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--keep-existing",
+                "--overwrite",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn masses_reference_without_mass() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+        let output_arg = format!("-o{}", output.path().display());
+        let masses = Builder::new().suffix(".txt").tempfile().unwrap();
+        std::fs::write(masses.path(), "BB 72.0\n").unwrap();
+        let masses_arg = format!("--masses={}", masses.path().display());
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                &masses_arg,
             ])
             .assert()
             .failure();
 
-        std::fs::remove_file("tests/test_files/tmp_input2.xtc").unwrap();
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.ends_with("error: 21 atom(s) of the reference group 'Protein' have no mass assigned; center of mass cannot be calculated\n\n"));
     }
 
     #[test]
-    fn nonexistent_group() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn masses_invalid() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
+        let masses = Builder::new().suffix(".txt").tempfile().unwrap();
+        std::fs::write(masses.path(), "BB 72.0\n72.0\n").unwrap();
+        let masses_arg = format!("--masses={}", masses.path().display());
 
-        Command::cargo_bin("gcenter")
+        let assert = Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
                 &output_arg,
-                "-ftests/test_files/input.xtc",
-                "-rNonexistent",
-                "-ntests/test_files/index.ndx",
+                "--com",
+                &masses_arg,
             ])
             .assert()
             .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(
+            stderr.contains("lines with atom names and lines with only masses cannot be combined")
+        );
     }
 
     #[test]
-    fn empty_group() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn bbox_center_with_median() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
@@ -2721,34 +10293,34 @@ mod fail_tests {
             .args([
                 "-stests/test_files/input.gro",
                 &output_arg,
-                "-ftests/test_files/input.xtc",
-                "-r(resname LYS and name PO4)",
-                "-ntests/test_files/index.ndx",
+                "--bbox-center",
+                "--median",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn gro_file_not_found() {
+    fn quiet_box_warnings_with_strict_box() {
         let output = Builder::new().suffix(".xtc").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
-                "-stests/test_files/input_nonexistent.gro",
-                &output_arg,
+                "-stests/test_files/input_no_box.pdb",
                 "-ftests/test_files/input.xtc",
-                "-ntests/test_files/index.ndx",
+                &output_arg,
+                "--strict-box",
+                "--quiet-box-warnings",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn xtc_file_not_found() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn quiet_box_warnings_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
@@ -2756,16 +10328,15 @@ mod fail_tests {
             .args([
                 "-stests/test_files/input.gro",
                 &output_arg,
-                "-ftests/test_files/input_nonexistent.xtc",
-                "-ntests/test_files/index.ndx",
+                "--quiet-box-warnings",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn ndx_file_not_found() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn time_offset_without_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
@@ -2773,92 +10344,115 @@ mod fail_tests {
             .args([
                 "-stests/test_files/input.gro",
                 &output_arg,
-                "-ftests/test_files/input.xtc",
-                "-ntests/test_files/index_nonexistent.ndx",
+                "--time-offset=100",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn inconsistent_gro_xtc() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn dedup_gro_trajectory() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
-                "-stests/test_files/input_tiny.gro",
+                "-stests/test_files/input.gro",
+                "-ftests/test_files/input.gro",
                 &output_arg,
-                "-ftests/test_files/input.xtc",
+                "--dedup",
             ])
             .assert()
-            .failure();
+            .failure()
+            .stderr("error: gro trajectories are not guaranteed to contain information about simulation step and time, therefore '--dedup' cannot be used\n\nFor more information, try '--help'.\n");
     }
 
     #[test]
-    fn nonorthogonal_box() {
+    fn reference_words_with_reference() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input_nonorthogonal.gro", &output_arg])
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rProtein",
+                "--reference-words",
+                "resname",
+                "ASN",
+            ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn invalid_box() {
+    fn report_box_matches_structure() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input_invalid_box.gro", &output_arg])
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--report-box=tests/test_files/input.gro",
+            ])
             .assert()
-            .failure();
+            .failure()
+            .stderr("error: invalid value 'tests/test_files/input.gro' for '--report-box <REPORT_BOX>': path matches an input or output file\n\nFor more information, try '--help'.\n");
     }
 
     #[test]
-    fn undefined_box() {
+    fn clean_backups_without_overwrite() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input_no_box.pdb", &output_arg])
+            .args(["-stests/test_files/input.gro", &output_arg, "--clean-backups"])
             .assert()
             .failure();
     }
 
     #[test]
-    fn no_extension() {
-        let output = Builder::new().tempfile().unwrap();
+    fn recenter_box_with_reference() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input.gro", &output_arg])
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--recenter-box",
+                "-rProtein",
+            ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn unsupported_extension_gro() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn xref_invert_with_invert_selection() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input.gro", &output_arg])
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--invert-selection",
+                "--xref-invert",
+            ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn unsupported_extension_xtc() {
-        let output = Builder::new().suffix(".pdb").tempfile().unwrap();
+    fn zref_invert_empty() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
@@ -2866,67 +10460,78 @@ mod fail_tests {
             .args([
                 "-stests/test_files/input.gro",
                 &output_arg,
-                "-ftests/test_files/input.xtc",
+                "-rall",
+                "--zref-invert",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn begin_requires_traj() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
-
+    fn list_formats_with_other_options() {
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input.gro", &output_arg, "-b400"])
+            .args([
+                "--list-formats",
+                "-stests/test_files/input.gro",
+                "-ooutput.gro",
+            ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn end_requires_traj() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn require_masses_com_gro() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input.gro", &output_arg, "-e700"])
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "--com",
+                "--require-masses",
+            ])
             .assert()
-            .failure();
+            .failure()
+            .stderr("error: masses of atoms are not available in 'tests/test_files/input.gro' and '--require-masses' forbids guessing them; use a tpr file or provide the masses using '--masses <FILE>'\n\n");
     }
 
     #[test]
-    fn step_requires_traj() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+    fn require_masses_mass_query() {
+        let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args(["-stests/test_files/input.gro", &output_arg, "-s3"])
+            .args([
+                "-stests/test_files/input.gro",
+                &output_arg,
+                "-rmass > 50",
+                "--require-masses",
+            ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn multiple_inputs_identical() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
-
+    fn stats_jsonl_matches_output() {
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                &output_arg,
-                "-ftests/test_files/input_part1.xtc",
-                "-ftests/test_files/input_part1.xtc",
+                "-ftests/test_files/input.xtc",
+                "-ooutput.xtc",
+                "--stats-jsonl=output.xtc",
             ])
             .assert()
-            .failure();
+            .failure()
+            .stderr("error: invalid value 'output.xtc' for '--stats-jsonl <FILE>': path matches an input or output file\n\nFor more information, try '--help'.\n");
     }
 
     #[test]
-    fn unknown_mass() {
+    fn stats_jsonl_without_trajectory() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
@@ -2934,16 +10539,15 @@ mod fail_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                "--com",
-                "-r@membrane",
                 &output_arg,
+                "--stats-jsonl=stats.jsonl",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn molwith_unsupported() {
+    fn begin_step_gro_trajectory() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
@@ -2951,47 +10555,47 @@ mod fail_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                "-rmolecule with serial 17",
+                "-ftests/test_files/input_traj.gro",
                 &output_arg,
+                "--begin-step=5000",
             ])
             .assert()
-            .failure();
+            .failure()
+            .stderr("error: invalid value '5000' for '--begin-step <STEP>': gro trajectories do not contain information about simulation steps\n\nFor more information, try '--help'.\n");
     }
 
     #[test]
-    fn molwith_unsupported_xref() {
-        let output = Builder::new().suffix(".gro").tempfile().unwrap();
+    fn end_step_with_begin() {
+        let output = Builder::new().suffix(".trr").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                "--xref=molecule with serial 17",
+                "-ftests/test_files/input.trr",
                 &output_arg,
+                "-b300",
+                "--end-step=35000",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn molwith_unsupported_yref() {
+    fn discard_without_trajectory() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
         Command::cargo_bin("gcenter")
             .unwrap()
-            .args([
-                "-stests/test_files/input.gro",
-                "--yref=molecule with serial 17",
-                &output_arg,
-            ])
+            .args(["-stests/test_files/input.gro", &output_arg, "--discard=3"])
             .assert()
             .failure();
     }
 
     #[test]
-    fn molwith_unsupported_zref() {
+    fn gzip_gro_trajectory() {
         let output = Builder::new().suffix(".gro").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
@@ -2999,7 +10603,7 @@ mod fail_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                "--zref=molecule with serial 17",
+                "-ftests/test_files/input_traj.gro.gz",
                 &output_arg,
             ])
             .assert()
@@ -3007,76 +10611,217 @@ mod fail_tests {
     }
 
     #[test]
-    fn whole_without_tpr() {
-        let output = Builder::new().suffix(".gro").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
+    fn gzip_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let trajectory = dir.path().join("input.xtc.gz");
+        std::fs::copy("tests/test_files/input.xtc", &trajectory).unwrap();
+        let output = dir.path().join("output.xtc");
+
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg(format!("-f{}", trajectory.display()))
+            .arg(format!("-o{}", output.display()))
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert_eq!(
+            stderr,
+            format!(
+                "error: could not decompress trajectory '{}'\n\n",
+                trajectory.display()
+            )
+        );
+
+        // temporary decompressed trajectory is removed
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn gzip_atom_count_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let trajectory = dir.path().join("input.xtc.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&trajectory).unwrap(),
+            flate2::Compression::default(),
+        );
+        std::io::copy(
+            &mut std::fs::File::open("tests/test_files/input_aa_peptide.xtc").unwrap(),
+            &mut encoder,
+        )
+        .unwrap();
+        encoder.finish().unwrap();
+        let output = dir.path().join("output.xtc");
 
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg("-stests/test_files/input.gro")
+            .arg(format!("-f{}", trajectory.display()))
+            .arg(format!("-o{}", output.display()))
+            .assert()
+            .failure();
+
+        // the error names the compressed trajectory provided by the user, not the temporary file
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        assert!(stderr.starts_with(&format!(
+            "error: number of atoms in the trajectory file '{}'",
+            trajectory.display()
+        )));
+        assert!(!stderr.contains(".gcenter_decompressed_"));
+
+        // temporary decompressed trajectory is removed
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn gzip_output_with_no_output() {
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
-                "-stests/test_files/input_aa_peptide.gro",
-                &output_arg,
-                "--whole",
+                "-stests/test_files/input.gro",
+                "--no-output",
+                "--gzip-output",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn xtc_trr_mixed_inputs() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
-
+    fn weights_from_bfactor_gro() {
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                &output_arg,
-                "-ftests/test_files/input_part1.xtc",
-                "-ftests/test_files/input_part2.trr",
-                "-ftests/test_files/input_part3.xtc",
+                "-otests/test_files/this_file_should_not_be_created.gro",
+                "--weights-from-bfactor",
             ])
             .assert()
-            .failure();
+            .failure()
+            .stderr("error: invalid value 'tests/test_files/input.gro' for '--weights-from-bfactor': B-factors can only be used as weights for a pdb input structure file\n\nFor more information, try '--help'.\n");
     }
 
     #[test]
-    fn xtc_trp_cg_element() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
+    fn weights_from_bfactor_all_zero() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.pdb",
+                "-otests/test_files/this_file_should_not_be_created.pdb",
+                "--weights-from-bfactor",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: could not use B-factors from 'tests/test_files/input.pdb' as weights: all B-factors are zero\n\n");
+    }
 
+    #[test]
+    fn stream_xtc_trajectory() {
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
-                "-stests/test_files/input.tpr",
-                &output_arg,
+                "-stests/test_files/input.gro",
                 "-ftests/test_files/input.xtc",
-                "-relement name carbon",
+                "-otests/test_files/this_file_should_not_be_created.xtc",
+                "--stream",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: output file 'tests/test_files/this_file_should_not_be_created.xtc' cannot be streamed: only frames of trr trajectories are flushed as soon as they are written; use an output file with the 'trr' extension\n\n");
+    }
+
+    #[test]
+    fn stream_with_gzip_output() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-otests/test_files/this_file_should_not_be_created.gro",
+                "--stream",
+                "--gzip-output",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn gro_traj_multiple_inputs() {
-        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
+    fn error_format_json() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-otests/test_files/this_file_should_not_be_created.txt",
+                "--error-format",
+                "json",
+            ])
+            .assert()
+            .code(1)
+            .stderr("{\"kind\": \"OutputUnsupported\", \"message\": \"invalid value 'tests/test_files/this_file_should_not_be_created.txt' for '--output <OUTPUT>': unsupported file extension\"}\n");
+    }
 
+    #[test]
+    fn error_format_json_invalid_argument() {
         Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                &output_arg,
-                "-ftests/test_files/output_z.gro",
-                "-ftests/test_files/output_yz.gro",
-                "-ftests/test_files/output_xz.gro",
+                "-otests/test_files/this_file_should_not_be_created.gro",
+                "--error-format=json",
+                "--dim=abc",
             ])
             .assert()
-            .failure();
+            .code(2)
+            .stderr("{\"kind\": \"ValueValidation\", \"message\": \"invalid value 'abc' for '--dim <DIMENSIONS>': unknown dimension 'a'\"}\n");
     }
 
     #[test]
-    fn gro_traj_begin() {
+    fn error_format_json_runtime() {
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-otests/test_files/this_file_should_not_be_created.gro",
+                "-rresname NONEXISTENT",
+                "--error-format=json",
+            ])
+            .assert()
+            .code(1);
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+        assert_eq!(stderr.lines().count(), 1);
+        assert!(stderr.starts_with("{\"kind\": \"EmptyReference\", \"message\": \"reference group"));
+    }
+
+    #[test]
+    fn error_format_invalid() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-otests/test_files/this_file_should_not_be_created.gro",
+                "--error-format=yaml",
+            ])
+            .assert()
+            .code(2);
+    }
+
+    #[test]
+    fn reference_cache_not_written() {
+        Command::cargo_bin("gcenter")
+            .unwrap()
+            .args([
+                "-stests/test_files/input.gro",
+                "-r@protein",
+                "-otests/test_files/this_file_should_not_be_created.gro",
+                "--reference-cache=tests/test_files/nonexistent/reference.ndx",
+            ])
+            .assert()
+            .failure()
+            .stderr("error: could not write the reference cache 'tests/test_files/nonexistent/reference.ndx'\n\n");
+    }
+
+    #[test]
+    fn progress_interval_zero() {
         let output = Builder::new().suffix(".xtc").tempfile().unwrap();
         let output_arg = format!("-o{}", output.path().display());
 
@@ -3084,28 +10829,60 @@ mod fail_tests {
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
+                "-ftests/test_files/input.xtc",
                 &output_arg,
-                "-ftests/test_files/input.gro",
-                "-b100",
+                "--progress-interval=0",
             ])
             .assert()
             .failure();
     }
 
     #[test]
-    fn gro_traj_end() {
+    fn extra_atoms_without_option() {
+        let structure = Builder::new().suffix(".gro").tempfile().unwrap();
+        let content = std::fs::read_to_string("tests/test_files/input.gro").unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        std::fs::write(
+            structure.path(),
+            format!(
+                "{}\n8000\n{}\n{}\n",
+                lines[0],
+                lines[2..8002].join("\n"),
+                lines.last().unwrap()
+            ),
+        )
+        .unwrap();
+
         let output = Builder::new().suffix(".xtc").tempfile().unwrap();
-        let output_arg = format!("-o{}", output.path().display());
 
-        Command::cargo_bin("gcenter")
+        let assert = Command::cargo_bin("gcenter")
+            .unwrap()
+            .arg(format!("-s{}", structure.path().display()))
+            .args(["-ftests/test_files/input.xtc"])
+            .arg(format!("-o{}", output.path().display()))
+            .assert()
+            .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(stderr.contains("does not match the number of atoms in the structure file"));
+    }
+
+    #[test]
+    fn ignore_extra_atoms_structure_larger() {
+        let output = Builder::new().suffix(".xtc").tempfile().unwrap();
+
+        let assert = Command::cargo_bin("gcenter")
             .unwrap()
             .args([
                 "-stests/test_files/input.gro",
-                &output_arg,
-                "-ftests/test_files/input.gro",
-                "-e1000",
+                "-ftests/test_files/input_aa_peptide.xtc",
+                "--ignore-extra-atoms",
             ])
+            .arg(format!("-o{}", output.path().display()))
             .assert()
             .failure();
+
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+        assert!(stderr.contains("does not match the number of atoms in the structure file"));
     }
 }